@@ -0,0 +1,89 @@
+//! 从交易日志中解码 pumpfun 事件（`emit!` 写入的 `Program data:` 行）。
+
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use pumpfun::{
+    BondingCurveCompleted, BuyEvent, ConfigChangeCancelled, ConfigChangeExecuted, ConfigChangeQueued, ConfigInitialized,
+    CouncilProposalApproved, CouncilProposalCancelled, CouncilProposalCreated, CouncilProposalExecuted,
+    CreationFeeUpdated, CreatorFeeClaimed, CreatorFeeRecipientUpdated, CreatorTransferCancelled,
+    CreatorTransferProposed, CreatorTransferred, CreatorVestingClaimed, CreatorVestingCreated, CurveFreezeUpdated,
+    CurvePresetUpdated, DexMigrated, DexSwapEvent, EmergencyRedeemed, EmergencyUnwindUpdated, FeeSplitClaimed,
+    FeeSplitUpdated, FeesUpdated, GovernanceAccepted, GovernanceCouncilUpdated, GovernanceProposalCancelled,
+    GovernanceProposed, GuardianUpdated, LiquidityAdded, LiquidityRemoved, MigrationThresholdUpdated, PausedUpdated,
+    SellEvent, TimelockDelayUpdated, TokenCreated, TokenMetadataFrozen, TokenMetadataUpdated, TreasuryUpdated,
+};
+
+const PROGRAM_DATA: &str = "Program data: "; // emit! 的日志前缀
+
+fn decode_as<T: AnchorDeserialize + Discriminator + std::fmt::Debug>(name: &str, data: &[u8]) -> Option<String> {
+    // 函数：判别符匹配时解码为 T 并格式化
+    let payload = data.strip_prefix(T::DISCRIMINATOR)?;
+    T::deserialize(&mut &payload[..])
+        .ok()
+        .map(|event| format!("{name} {event:#?}"))
+}
+
+macro_rules! decode_event {
+    // 宏：依次尝试每种事件
+    ($data:expr, $($event:ident),+ $(,)?) => {
+        None$(.or_else(|| decode_as::<$event>(stringify!($event), $data)))+
+    };
+}
+
+pub fn decode(data: &[u8]) -> Option<String> {
+    // 函数：解码一条事件数据，未知判别符返回 None
+    decode_event!(
+        data,
+        ConfigInitialized,
+        FeesUpdated,
+        TreasuryUpdated,
+        CreationFeeUpdated,
+        MigrationThresholdUpdated,
+        TimelockDelayUpdated,
+        ConfigChangeQueued,
+        ConfigChangeExecuted,
+        ConfigChangeCancelled,
+        PausedUpdated,
+        GuardianUpdated,
+        GovernanceProposed,
+        GovernanceAccepted,
+        GovernanceProposalCancelled,
+        GovernanceCouncilUpdated,
+        CouncilProposalCreated,
+        CouncilProposalApproved,
+        CouncilProposalExecuted,
+        CouncilProposalCancelled,
+        CurvePresetUpdated,
+        TokenCreated,
+        TokenMetadataUpdated,
+        TokenMetadataFrozen,
+        BuyEvent,
+        BondingCurveCompleted,
+        SellEvent,
+        CreatorFeeClaimed,
+        CreatorTransferProposed,
+        CreatorTransferred,
+        CreatorTransferCancelled,
+        CreatorFeeRecipientUpdated,
+        FeeSplitUpdated,
+        FeeSplitClaimed,
+        CreatorVestingCreated,
+        CreatorVestingClaimed,
+        CurveFreezeUpdated,
+        EmergencyUnwindUpdated,
+        EmergencyRedeemed,
+        DexMigrated,
+        DexSwapEvent,
+        LiquidityAdded,
+        LiquidityRemoved,
+    )
+}
+
+pub fn from_logs(logs: &[String]) -> Vec<String> {
+    // 函数：解码日志中的全部 pumpfun 事件
+    logs.iter()
+        .filter_map(|line| line.strip_prefix(PROGRAM_DATA))
+        .filter_map(|encoded| STANDARD.decode(encoded).ok())
+        .filter_map(|data| decode(&data))
+        .collect()
+}
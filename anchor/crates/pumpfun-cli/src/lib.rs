@@ -0,0 +1,899 @@
+//! pumpfun 运维和创作者命令行工具。
+//!
+//! 所有金额都是最小单位（lamports、代币最小单位），与链上指令参数一致。
+//! 命令只依赖 [`rpc::Rpc`]，因此既可以连真实集群，也可以在测试中连内存中的验证器替身。
+
+pub mod events; // 事件解码
+pub mod rpc; // 集群接口
+
+use std::{
+    io::Write,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anchor_lang::{prelude::Pubkey, solana_program::instruction::Instruction, Discriminator};
+use anyhow::{anyhow, bail, Context, Result};
+use pumpfun::MAX_COUNCIL_MEMBERS;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use pumpfun_client::{
+    accounts, instructions as ix, pda, BondingCurve, ConfigChange, CouncilAction, CouncilProposal, FeeSplit,
+    GovernanceCouncil, PendingConfigChange, ProtocolConfig, ID,
+};
+use solana_keypair::{read_keypair_file, Keypair};
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+use crate::rpc::Rpc;
+
+pub const DEFAULT_URL: &str = "http://127.0.0.1:8899"; // 本地验证器
+
+#[derive(Debug, Parser)] // 命令行参数
+#[command(name = "pumpfun-cli", version, about = "Administer and trade on the pumpfun program")]
+pub struct Cli {
+    #[arg(long, global = true, default_value = DEFAULT_URL)]
+    pub url: String, // RPC 地址
+    #[arg(long, global = true)]
+    pub keypair: Option<PathBuf>, // 签名密钥，默认 ~/.config/solana/id.json
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)] // 子命令
+pub enum Command {
+    /// Protocol configuration (governance)
+    Config(ConfigArgs),
+    /// Governance council (M-of-N multisig acting as governance authority)
+    #[command(subcommand)]
+    Council(CouncilCommand),
+    /// Bonding curves
+    #[command(subcommand)]
+    Curve(CurveCommand),
+    /// Trade on a bonding curve
+    #[command(subcommand)]
+    Trade(TradeCommand),
+    /// Creator fees
+    #[command(subcommand)]
+    Fees(FeesCommand),
+    /// Migrate a completed curve to its DEX pool, initializing the pool first if needed
+    Migrate { mint: Pubkey },
+}
+
+#[derive(Debug, Args)] // 配置命令参数
+pub struct ConfigArgs {
+    /// Propose the governance instructions to the council instead of signing them directly
+    #[arg(long, global = true)]
+    pub via_council: bool,
+    #[command(subcommand)]
+    pub command: ConfigCommand,
+}
+
+#[derive(Debug, Subcommand)] // 配置子命令
+pub enum ConfigCommand {
+    /// Create the protocol config with the signer as governance authority
+    Init {
+        #[arg(long)]
+        treasury: Option<Pubkey>, // 国库，默认签名者
+    },
+    /// Print the protocol config
+    Show,
+    /// Queue timelocked changes to config fields in one transaction; omitted fields keep their current value
+    Update(ConfigUpdate),
+    /// List queued config changes and when each becomes executable
+    Pending,
+    /// Apply a queued config change once its timelock has elapsed
+    Execute { id: u64 },
+    /// Withdraw a queued config change
+    Cancel { id: u64 },
+    /// Pause instruction families (takes effect immediately)
+    Pause(PauseFamilies),
+    /// Resume instruction families
+    Unpause(PauseFamilies),
+    /// Set the guardian, who may pause but not unpause or change anything else
+    SetGuardian { guardian: Pubkey },
+    /// Pause instruction families as the guardian; signed by the guardian
+    GuardianPause(PauseFamilies),
+    /// Halt trading and migration on one bonding curve
+    FreezeCurve { mint: Pubkey },
+    /// Resume trading on a frozen bonding curve
+    UnfreezeCurve { mint: Pubkey },
+    /// Halt a bonding curve and let holders redeem pro rata for its real SOL reserves
+    StartUnwind { mint: Pubkey },
+    /// End an emergency unwind
+    StopUnwind { mint: Pubkey },
+    /// Nominate a new governance authority; it takes over once it runs `accept-governance`
+    ProposeGovernance { new_authority: Pubkey },
+    /// Accept the governance authority nominated for the signer
+    AcceptGovernance,
+    /// Withdraw a governance nomination that has not been accepted yet
+    CancelGovernanceProposal,
+}
+
+#[derive(Debug, Args)] // 暂停的指令族
+pub struct PauseFamilies {
+    /// Instruction family to (un)pause, repeatable; all families when omitted
+    #[arg(long = "family", value_enum)]
+    pub families: Vec<PauseFamily>,
+}
+impl PauseFamilies {
+    pub fn mask(&self) -> u8 {
+        // 函数：对应的暂停位
+        if self.families.is_empty() {
+            return pumpfun::PAUSE_ALL;
+        }
+        self.families.iter().fold(0, |mask, family| mask | family.flag())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)] // 指令族
+pub enum PauseFamily {
+    Create,
+    Buy,
+    Sell,
+    Claim,
+    Migrate,
+}
+impl PauseFamily {
+    pub fn flag(self) -> u8 {
+        match self {
+            PauseFamily::Create => pumpfun::PAUSE_CREATE,
+            PauseFamily::Buy => pumpfun::PAUSE_BUY,
+            PauseFamily::Sell => pumpfun::PAUSE_SELL,
+            PauseFamily::Claim => pumpfun::PAUSE_CLAIM,
+            PauseFamily::Migrate => pumpfun::PAUSE_MIGRATE,
+        }
+    }
+}
+
+#[derive(Debug, Args)] // 配置更新参数
+pub struct ConfigUpdate {
+    #[arg(long)]
+    pub treasury: Option<Pubkey>,
+    #[arg(long)]
+    pub creation_fee_sol: Option<u64>,
+    #[arg(long)]
+    pub total_trade_fee_bps: Option<u64>,
+    #[arg(long)]
+    pub creator_fee_bps_share: Option<u64>,
+    #[arg(long)]
+    pub migration_threshold_sol: Option<u64>,
+    #[arg(long)]
+    pub dex_lp_fee_bps: Option<u64>,
+    #[arg(long)]
+    pub timelock_secs: Option<i64>,
+}
+
+#[derive(Debug, Subcommand)] // 委员会子命令
+pub enum CouncilCommand {
+    /// Create the governance council; signed by the current governance authority
+    Create(CouncilMembers),
+    /// Print the council and its open proposals
+    Show,
+    /// Propose replacing the council members and threshold
+    UpdateMembers(CouncilMembers),
+    /// Approve a proposal
+    Approve { id: u64 },
+    /// Execute a proposal that has enough approvals
+    Execute { id: u64 },
+    /// Withdraw a proposal the signer created
+    Cancel { id: u64 },
+}
+
+#[derive(Debug, Args)] // 委员会成员参数
+pub struct CouncilMembers {
+    #[arg(long = "member", required = true)]
+    pub members: Vec<Pubkey>, // 成员，可重复
+    #[arg(long)]
+    pub threshold: u8, // 执行提案所需的批准数
+}
+
+#[derive(Debug, Subcommand)] // 曲线子命令
+pub enum CurveCommand {
+    /// Create a token and its bonding curve
+    Create {
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        symbol: String,
+        #[arg(long)]
+        uri: String,
+        #[arg(long, default_value_t = 0)]
+        preset: u8, // 曲线预设编号
+        #[arg(long)]
+        mint_keypair: Option<PathBuf>, // 铸币密钥，默认随机生成
+    },
+    /// Print a bonding curve
+    Show { mint: Pubkey },
+    /// List all bonding curves
+    List,
+    /// Nominate a new creator for a curve; it takes over once it runs `accept-creator`
+    TransferCreator { mint: Pubkey, new_creator: Pubkey },
+    /// Accept the creator role nominated for the signer
+    AcceptCreator { mint: Pubkey },
+    /// Withdraw a creator nomination that has not been accepted yet
+    CancelCreatorTransfer { mint: Pubkey },
+}
+
+#[derive(Debug, Subcommand)] // 交易子命令
+pub enum TradeCommand {
+    /// Buy with a SOL budget (lamports, fees included)
+    Buy {
+        mint: Pubkey,
+        #[arg(long)]
+        sol: u64,
+        #[command(flatten)]
+        options: TradeOptions,
+    },
+    /// Sell an exact token amount (smallest units)
+    Sell {
+        mint: Pubkey,
+        #[arg(long)]
+        tokens: u64,
+        #[command(flatten)]
+        options: TradeOptions,
+    },
+    /// Redeem tokens pro rata for SOL while the curve is being unwound
+    Redeem {
+        mint: Pubkey,
+        #[arg(long)]
+        tokens: u64,
+    },
+}
+
+#[derive(Debug, Args)] // 交易选项
+pub struct TradeOptions {
+    #[arg(long, default_value_t = 100)]
+    pub slippage_bps: u64, // 允许的滑点
+    #[arg(long, default_value_t = 60)]
+    pub deadline_secs: i64, // 交易有效期
+    #[arg(long)]
+    pub quote_only: bool, // 只打印报价，不发送交易
+}
+
+#[derive(Debug, Subcommand)] // 费用子命令
+pub enum FeesCommand {
+    /// Claim the signer's creator fees on a curve; on a vesting curve only the released part
+    Claim {
+        mint: Pubkey,
+        #[arg(long)]
+        max_amount: Option<u64>, // 释放计划下最多领取的金额，默认全部已释放部分
+    },
+    /// Release the curve's future creator fees linearly instead of as lump claims (irreversible)
+    Vest {
+        mint: Pubkey,
+        #[arg(long)]
+        cliff_secs: i64,
+        #[arg(long)]
+        duration_secs: i64,
+    },
+    /// Print a curve's creator fee vesting schedule and what is claimable now
+    Vesting { mint: Pubkey },
+    /// Send the curve's creator fees to another account, e.g. a multisig; the signer stays creator
+    SetRecipient { mint: Pubkey, recipient: Pubkey },
+    /// Split the curve's creator fees among recipients by bps weights; an existing split is paid out first
+    SetSplit {
+        mint: Pubkey,
+        #[arg(long = "recipient", required = true, value_parser = parse_share)]
+        recipients: Vec<(Pubkey, u16)>, // 接收者和比例（PUBKEY:BPS），可重复，合计 10000
+    },
+    /// Print a curve's fee split recipients, weights and what each has claimed
+    Split { mint: Pubkey },
+    /// Claim the signer's share of a curve's fee split
+    ClaimShare { mint: Pubkey },
+}
+
+pub fn default_keypair_path() -> PathBuf {
+    // 函数：Solana CLI 默认密钥路径
+    let home = std::env::var_os("HOME").unwrap_or_default();
+    PathBuf::from(home).join(".config/solana/id.json")
+}
+
+pub fn load_keypair(path: Option<PathBuf>) -> Result<Keypair> {
+    // 函数：读取密钥文件
+    let path = path.unwrap_or_else(default_keypair_path);
+    read_keypair_file(&path).map_err(|e| anyhow!("failed to read keypair {}: {e}", path.display()))
+}
+
+pub struct App<'a> {
+    // 结构：一次命令执行所需的上下文
+    pub rpc: &'a mut dyn Rpc,   // 集群接口
+    pub payer: &'a Keypair,     // 签名并支付
+    pub out: &'a mut dyn Write, // 输出
+}
+
+fn protocol_error(error: anchor_lang::error::Error) -> anyhow::Error {
+    // 函数：程序端报价错误转换
+    anyhow!("{error}")
+}
+
+fn parse_share(value: &str) -> Result<(Pubkey, u16), String> {
+    // 函数：解析分账接收者参数 PUBKEY:BPS
+    let (recipient, bps) = value.split_once(':').ok_or("expected PUBKEY:BPS")?;
+    let recipient = recipient.parse().map_err(|e| format!("invalid recipient {recipient}: {e}"))?;
+    let bps = bps.parse().map_err(|e| format!("invalid bps {bps}: {e}"))?;
+    Ok((recipient, bps))
+}
+
+fn with_slippage(amount: u64, slippage_bps: u64) -> u64 {
+    // 函数：按滑点下调最小输出
+    (amount as u128 * 10_000u128.saturating_sub(slippage_bps as u128) / 10_000) as u64
+}
+
+fn deadline(secs: i64) -> i64 {
+    // 函数：当前时间 + 有效期
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default();
+    now as i64 + secs
+}
+
+impl App<'_> {
+    pub fn run(&mut self, command: Command) -> Result<()> {
+        // 函数：执行子命令
+        match command {
+            Command::Config(args) => self.config(args),
+            Command::Council(command) => self.council(command),
+            Command::Curve(command) => self.curve(command),
+            Command::Trade(command) => self.trade(command),
+            Command::Fees(command) => self.fees(command),
+            Command::Migrate { mint } => self.migrate(mint),
+        }
+    }
+
+    fn config(&mut self, args: ConfigArgs) -> Result<()> {
+        // 函数：配置命令；治理操作先转换为委员会提案内容，再直接签名发送或提交给委员会
+        let actions = match args.command {
+            ConfigCommand::Init { treasury } => {
+                let instruction = ix::InitializeConfig {
+                    authority: self.payer.pubkey(),
+                    treasury: treasury.unwrap_or(self.payer.pubkey()),
+                }
+                .instruction();
+                return self.send(&[instruction], &[]);
+            }
+            ConfigCommand::Show => {
+                let config = self.protocol_config()?;
+                writeln!(self.out, "protocol_config {}", pda::protocol_config().0)?;
+                writeln!(self.out, "{config:#?}")?;
+                return Ok(());
+            }
+            ConfigCommand::Pending => {
+                let mut pending = self
+                    .rpc
+                    .program_accounts(&ID, PendingConfigChange::DISCRIMINATOR)?
+                    .into_iter()
+                    .map(|(_, data)| accounts::pending_config_change(&data).map_err(protocol_error))
+                    .collect::<Result<Vec<_>>>()?;
+                pending.sort_by_key(|pending| pending.id);
+                for pending in pending {
+                    writeln!(
+                        self.out,
+                        "#{} executable_at={} {:?}",
+                        pending.id, pending.executable_at, pending.change
+                    )?;
+                }
+                return Ok(());
+            }
+            ConfigCommand::Update(update) => {
+                let config = self.protocol_config()?;
+                let mut changes = Vec::new();
+                if let Some(treasury) = update.treasury {
+                    changes.push(ConfigChange::Treasury { treasury });
+                }
+                if update.total_trade_fee_bps.is_some()
+                    || update.creator_fee_bps_share.is_some()
+                    || update.dex_lp_fee_bps.is_some()
+                {
+                    changes.push(ConfigChange::Fees {
+                        total_trade_fee_bps: update.total_trade_fee_bps.unwrap_or(config.total_trade_fee_bps),
+                        creator_fee_bps_share: update.creator_fee_bps_share.unwrap_or(config.creator_fee_bps_share),
+                        dex_lp_fee_bps: update.dex_lp_fee_bps.unwrap_or(config.dex_lp_fee_bps),
+                    });
+                }
+                if let Some(creation_fee_sol) = update.creation_fee_sol {
+                    changes.push(ConfigChange::CreationFee { creation_fee_sol });
+                }
+                if let Some(migration_threshold_sol) = update.migration_threshold_sol {
+                    changes.push(ConfigChange::MigrationThreshold {
+                        migration_threshold_sol,
+                    });
+                }
+                if let Some(delay_secs) = update.timelock_secs {
+                    changes.push(ConfigChange::TimelockDelay { delay_secs });
+                }
+                if changes.is_empty() {
+                    bail!("nothing to update");
+                }
+                writeln!(self.out, "timelock {}s", config.config_timelock_secs)?;
+                changes
+                    .into_iter()
+                    .map(|change| CouncilAction::QueueConfigChange { change })
+                    .collect()
+            }
+            ConfigCommand::Execute { id } => vec![CouncilAction::ExecuteConfigChange { id }],
+            ConfigCommand::Cancel { id } => vec![CouncilAction::CancelConfigChange { id }],
+            ConfigCommand::Pause(families) => {
+                let paused = self.protocol_config()?.paused | families.mask();
+                vec![CouncilAction::SetPaused { paused }]
+            }
+            ConfigCommand::Unpause(families) => {
+                let paused = self.protocol_config()?.paused & !families.mask();
+                vec![CouncilAction::SetPaused { paused }]
+            }
+            ConfigCommand::SetGuardian { guardian } => vec![CouncilAction::SetGuardian { guardian }],
+            ConfigCommand::GuardianPause(families) => {
+                let guardian = ix::GuardianPause {
+                    guardian: self.payer.pubkey(),
+                };
+                return self.send(&[guardian.instruction(families.mask())], &[]);
+            }
+            ConfigCommand::FreezeCurve { mint } => vec![CouncilAction::FreezeCurve { mint, frozen: true }],
+            ConfigCommand::UnfreezeCurve { mint } => vec![CouncilAction::FreezeCurve { mint, frozen: false }],
+            ConfigCommand::StartUnwind { mint } => vec![CouncilAction::EmergencyUnwind { mint, unwinding: true }],
+            ConfigCommand::StopUnwind { mint } => vec![CouncilAction::EmergencyUnwind { mint, unwinding: false }],
+            ConfigCommand::ProposeGovernance { new_authority } => {
+                vec![CouncilAction::ProposeGovernance { new_authority }]
+            }
+            ConfigCommand::AcceptGovernance => vec![CouncilAction::AcceptGovernance],
+            ConfigCommand::CancelGovernanceProposal => vec![CouncilAction::CancelGovernanceProposal],
+        };
+        if args.via_council {
+            return self.propose(actions);
+        }
+        let governance = ix::Governance {
+            governance_authority: self.payer.pubkey(),
+        };
+        let mut queue_id = self.protocol_config()?.next_config_change_id;
+        let mut instructions = Vec::new();
+        for action in &actions {
+            // 同一笔交易中排队的变更占用连续编号
+            instructions.extend(self.governance_instruction(governance, action, queue_id)?);
+            if matches!(action, CouncilAction::QueueConfigChange { .. }) {
+                queue_id += 1;
+            }
+        }
+        self.send(&instructions, &[])
+    }
+
+    fn council(&mut self, command: CouncilCommand) -> Result<()> {
+        let member = ix::Council {
+            member: self.payer.pubkey(),
+        };
+        match command {
+            CouncilCommand::Create(CouncilMembers { members, threshold }) => {
+                let governance = ix::Governance {
+                    governance_authority: self.payer.pubkey(),
+                };
+                writeln!(self.out, "council_authority {}", pda::council_authority().0)?;
+                self.send(&[governance.create_governance_council(members, threshold)], &[])
+            }
+            CouncilCommand::Show => {
+                let council = self.governance_council()?;
+                writeln!(self.out, "governance_council {}", pda::governance_council().0)?;
+                writeln!(self.out, "council_authority {}", pda::council_authority().0)?;
+                writeln!(self.out, "{council:#?}")?;
+                let mut proposals = self
+                    .rpc
+                    .program_accounts(&ID, CouncilProposal::DISCRIMINATOR)?
+                    .into_iter()
+                    .map(|(_, data)| accounts::council_proposal(&data).map_err(protocol_error))
+                    .collect::<Result<Vec<_>>>()?;
+                proposals.sort_by_key(|proposal| proposal.id);
+                for proposal in proposals {
+                    let stale = if proposal.generation == council.generation {
+                        ""
+                    } else {
+                        " (stale)" // 委员会成员已变更，提案不能再执行
+                    };
+                    writeln!(
+                        self.out,
+                        "#{} approvals={}/{}{stale} proposer={} {:?}",
+                        proposal.id,
+                        proposal.approval_count(),
+                        council.threshold,
+                        proposal.proposer,
+                        proposal.action
+                    )?;
+                }
+                Ok(())
+            }
+            CouncilCommand::UpdateMembers(CouncilMembers { members, threshold }) => {
+                let mut padded = [Pubkey::default(); MAX_COUNCIL_MEMBERS];
+                if members.len() > MAX_COUNCIL_MEMBERS {
+                    bail!("a council has at most {MAX_COUNCIL_MEMBERS} members");
+                }
+                padded[..members.len()].copy_from_slice(&members);
+                self.propose(vec![CouncilAction::UpdateCouncil {
+                    members: padded,
+                    member_count: members.len() as u8,
+                    threshold,
+                }])
+            }
+            CouncilCommand::Approve { id } => self.send(&[member.approve(id)], &[]),
+            CouncilCommand::Execute { id } => {
+                let proposal = self.council_proposal(id)?;
+                let queue_id = self.protocol_config()?.next_config_change_id;
+                let instruction = self.governance_instruction(ix::Council::governance(), &proposal.action, queue_id)?;
+                self.send(&[member.execute(id, proposal.proposer, instruction.as_ref())], &[])
+            }
+            CouncilCommand::Cancel { id } => self.send(&[member.cancel(id)], &[]),
+        }
+    }
+
+    fn propose(&mut self, actions: Vec<CouncilAction>) -> Result<()> {
+        // 函数：把治理操作逐项提交为委员会提案，编号连续
+        let member = ix::Council {
+            member: self.payer.pubkey(),
+        };
+        let first_id = self.governance_council()?.next_proposal_id;
+        let mut instructions = Vec::new();
+        for (id, action) in (first_id..).zip(actions) {
+            writeln!(self.out, "proposal #{id} {action:?}")?;
+            instructions.push(member.propose(id, action));
+        }
+        self.send(&instructions, &[])
+    }
+
+    fn governance_instruction(
+        &mut self,
+        governance: ix::Governance,
+        action: &CouncilAction,
+        queue_id: u64, // 排队变更使用的编号
+    ) -> Result<Option<Instruction>> {
+        // 函数：委员会提案内容对应的治理指令（成员变更没有对应指令）
+        let instruction = match *action {
+            CouncilAction::QueueConfigChange { change } => {
+                writeln!(self.out, "queue #{queue_id} {change:?}")?;
+                governance.queue_config_change(queue_id, change)
+            }
+            CouncilAction::ExecuteConfigChange { id } => {
+                let pending = self.pending_config_change(id)?;
+                governance.execute_config_change(id, &pending.change)
+            }
+            CouncilAction::CancelConfigChange { id } => {
+                self.pending_config_change(id)?;
+                governance.cancel_config_change(id)
+            }
+            CouncilAction::SetPaused { paused } => governance.set_paused(paused),
+            CouncilAction::SetGuardian { guardian } => governance.set_guardian(guardian),
+            CouncilAction::FreezeCurve { mint, frozen } => governance.freeze_curve(mint, frozen),
+            CouncilAction::EmergencyUnwind { mint, unwinding } => governance.emergency_unwind(mint, unwinding),
+            CouncilAction::SetCurvePreset { index, preset } => governance.set_curve_preset(index, preset),
+            CouncilAction::ProposeGovernance { new_authority } => governance.propose_governance(new_authority),
+            CouncilAction::AcceptGovernance => ix::AcceptGovernance {
+                pending_governance: governance.governance_authority,
+            }
+            .instruction(),
+            CouncilAction::CancelGovernanceProposal => governance.cancel_governance_proposal(),
+            CouncilAction::UpdateCouncil { .. } => return Ok(None),
+        };
+        Ok(Some(instruction))
+    }
+
+    fn curve(&mut self, command: CurveCommand) -> Result<()> {
+        match command {
+            CurveCommand::Create {
+                name,
+                symbol,
+                uri,
+                preset,
+                mint_keypair,
+            } => {
+                let mint = match mint_keypair {
+                    Some(path) => load_keypair(Some(path))?,
+                    None => Keypair::new(),
+                };
+                let config = self.protocol_config()?;
+                let instruction = ix::Create {
+                    creator: self.payer.pubkey(),
+                    treasury: config.treasury,
+                    token_mint: mint.pubkey(),
+                }
+                .instruction(name, symbol, uri, preset);
+                writeln!(self.out, "mint {}", mint.pubkey())?;
+                self.send(&[instruction], &[&mint])
+            }
+            CurveCommand::Show { mint } => {
+                let curve = self.bonding_curve(&mint)?;
+                let config = self.protocol_config()?;
+                let (price_num, price_den) = curve.spot_price().map_err(protocol_error)?;
+                writeln!(self.out, "bonding_curve {}", pda::bonding_curve(&mint).0)?;
+                writeln!(self.out, "{curve:#?}")?;
+                writeln!(self.out, "spot_price {price_num}/{price_den} lamports per unit")?;
+                writeln!(
+                    self.out,
+                    "progress {}/{} lamports",
+                    curve.real_sol_reserves, config.migration_threshold_sol
+                )?;
+                Ok(())
+            }
+            CurveCommand::List => {
+                let curves = self.rpc.program_accounts(&ID, BondingCurve::DISCRIMINATOR)?;
+                for (address, data) in curves {
+                    let curve = accounts::bonding_curve(&data).map_err(protocol_error)?;
+                    writeln!(
+                        self.out,
+                        "{address} mint={} creator={} real_sol={} completed={} migrated={}",
+                        curve.token_mint,
+                        curve.creator,
+                        curve.real_sol_reserves,
+                        curve.is_completed,
+                        curve.is_migrated()
+                    )?;
+                }
+                Ok(())
+            }
+            CurveCommand::TransferCreator { mint, new_creator } => {
+                let creator = ix::Creator {
+                    creator: self.payer.pubkey(),
+                    token_mint: mint,
+                };
+                self.send(&[creator.transfer_creator(new_creator)], &[])
+            }
+            CurveCommand::AcceptCreator { mint } => {
+                let accept = ix::AcceptCreator {
+                    pending_creator: self.payer.pubkey(),
+                    token_mint: mint,
+                };
+                self.send(&[accept.instruction()], &[])
+            }
+            CurveCommand::CancelCreatorTransfer { mint } => {
+                let creator = ix::Creator {
+                    creator: self.payer.pubkey(),
+                    token_mint: mint,
+                };
+                self.send(&[creator.cancel_creator_transfer()], &[])
+            }
+        }
+    }
+
+    fn trade(&mut self, command: TradeCommand) -> Result<()> {
+        let config = self.protocol_config()?;
+        match command {
+            TradeCommand::Buy { mint, sol, options } => {
+                let curve = self.bonding_curve(&mint)?;
+                let quote = curve.quote_buy(&config, sol).map_err(protocol_error)?;
+                let min_tokens_out = with_slippage(quote.tokens_out, options.slippage_bps);
+                writeln!(self.out, "{quote:#?}")?;
+                writeln!(self.out, "min_tokens_out {min_tokens_out}")?;
+                if options.quote_only {
+                    return Ok(());
+                }
+                let instruction = ix::Buy {
+                    buyer: self.payer.pubkey(),
+                    treasury: config.treasury,
+                    token_mint: mint,
+                }
+                .buy(sol, min_tokens_out, deadline(options.deadline_secs));
+                self.send(&[instruction], &[])
+            }
+            TradeCommand::Sell { mint, tokens, options } => {
+                let curve = self.bonding_curve(&mint)?;
+                let quote = curve.quote_sell(&config, tokens).map_err(protocol_error)?;
+                let min_sol_out = with_slippage(quote.sol_out, options.slippage_bps);
+                writeln!(self.out, "{quote:#?}")?;
+                writeln!(self.out, "min_sol_out {min_sol_out}")?;
+                if options.quote_only {
+                    return Ok(());
+                }
+                let instruction = ix::Sell {
+                    seller: self.payer.pubkey(),
+                    treasury: config.treasury,
+                    token_mint: mint,
+                }
+                .sell(tokens, min_sol_out, deadline(options.deadline_secs));
+                self.send(&[instruction], &[])
+            }
+            TradeCommand::Redeem { mint, tokens } => {
+                let curve = self.bonding_curve(&mint)?;
+                if !curve.is_unwinding {
+                    bail!("curve {mint} is not being unwound");
+                }
+                let sol_out = curve.get_redeem_output(tokens).map_err(protocol_error)?;
+                writeln!(self.out, "sol_out {sol_out}")?;
+                let instruction = ix::EmergencyRedeem {
+                    holder: self.payer.pubkey(),
+                    token_mint: mint,
+                }
+                .instruction(tokens);
+                self.send(&[instruction], &[])
+            }
+        }
+    }
+
+    fn fees(&mut self, command: FeesCommand) -> Result<()> {
+        let creator = self.payer.pubkey();
+        match command {
+            FeesCommand::Claim { mint, max_amount } => {
+                let curve = self.bonding_curve(&mint)?;
+                writeln!(self.out, "recipient {}", curve.creator_fee_recipient)?;
+                let instruction = if curve.has_creator_vesting {
+                    ix::CreatorFeeVesting {
+                        creator,
+                        token_mint: mint,
+                    }
+                    .claim(curve.creator_fee_recipient, max_amount.unwrap_or(u64::MAX))
+                } else {
+                    if max_amount.is_some() {
+                        bail!("--max-amount only applies to curves with a vesting schedule");
+                    }
+                    let claim = ix::ClaimCreatorFees {
+                        creator,
+                        creator_fee_recipient: curve.creator_fee_recipient,
+                        token_mint: mint,
+                    };
+                    if curve.has_fee_split {
+                        // 分账曲线：同一条指令按比例付给全部接收者
+                        claim.distribute(self.fee_split(&mint)?.recipients())
+                    } else {
+                        claim.instruction()
+                    }
+                };
+                self.send(&[instruction], &[])
+            }
+            FeesCommand::Vest {
+                mint,
+                cliff_secs,
+                duration_secs,
+            } => {
+                let instruction = ix::CreatorFeeVesting {
+                    creator,
+                    token_mint: mint,
+                }
+                .create(cliff_secs, duration_secs);
+                self.send(&[instruction], &[])
+            }
+            FeesCommand::Vesting { mint } => {
+                let data = self
+                    .rpc
+                    .account_data(&pda::creator_vesting(&mint).0)?
+                    .with_context(|| format!("no vesting schedule for mint {mint}"))?;
+                let mut vesting = accounts::creator_vesting(&data).map_err(protocol_error)?;
+                writeln!(self.out, "{vesting:#?}")?;
+                writeln!(self.out, "unclaimed {}", vesting.unclaimed())?;
+                // 曲线上尚未汇入的费用在下次领取时汇入，同样按计划释放
+                let pending = self.bonding_curve(&mint)?.creator_fees_owed;
+                vesting.total_accrued += pending;
+                writeln!(self.out, "pending {pending}")?;
+                writeln!(self.out, "claimable {}", vesting.claimable(deadline(0)).map_err(protocol_error)?)?;
+                Ok(())
+            }
+            FeesCommand::SetRecipient { mint, recipient } => {
+                let creator = ix::Creator {
+                    creator,
+                    token_mint: mint,
+                };
+                self.send(&[creator.set_creator_fee_recipient(recipient)], &[])
+            }
+            FeesCommand::SetSplit { mint, recipients } => {
+                let split = ix::CreatorFeeSplit {
+                    creator,
+                    token_mint: mint,
+                };
+                let (recipients, shares_bps) = recipients.into_iter().unzip();
+                let instruction = if self.bonding_curve(&mint)?.has_fee_split {
+                    split.update(self.fee_split(&mint)?.recipients(), recipients, shares_bps)
+                } else {
+                    split.create(recipients, shares_bps)
+                };
+                self.send(&[instruction], &[])
+            }
+            FeesCommand::Split { mint } => {
+                let split = self.fee_split(&mint)?;
+                for (i, recipient) in split.recipients().iter().enumerate() {
+                    writeln!(self.out, "{recipient} {} bps claimed {}", split.shares_bps[i], split.claimed[i])?;
+                }
+                // 曲线上尚未汇入的费用在下次领取时汇入，按比例分配
+                writeln!(self.out, "pending {}", self.bonding_curve(&mint)?.creator_fees_owed)?;
+                Ok(())
+            }
+            FeesCommand::ClaimShare { mint } => {
+                let claim = ix::ClaimFeeSplitShare {
+                    recipient: creator,
+                    token_mint: mint,
+                };
+                self.send(&[claim.instruction()], &[])
+            }
+        }
+    }
+
+    fn migrate(&mut self, mint: Pubkey) -> Result<()> {
+        let curve = self.bonding_curve(&mint)?;
+        if curve.is_migrated() {
+            bail!("curve for {mint} has already migrated");
+        }
+        let mut instructions = Vec::new();
+        if self.rpc.account_data(&pda::dex_pool(&mint).0)?.is_none() {
+            // DEX 池尚未初始化：同一笔交易中先初始化
+            instructions.push(
+                ix::InitializeDexPool {
+                    payer: self.payer.pubkey(),
+                    token_mint: mint,
+                }
+                .instruction(),
+            );
+        }
+        instructions.push(
+            ix::CompleteAndMigrate {
+                payer: self.payer.pubkey(),
+                token_mint: mint,
+            }
+            .instruction(),
+        );
+        self.send(&instructions, &[])
+    }
+
+    fn pending_config_change(&self, id: u64) -> Result<PendingConfigChange> {
+        // 函数：读取并解码排队中的配置变更
+        let address = pda::pending_config_change(id).0;
+        let data = self
+            .rpc
+            .account_data(&address)?
+            .with_context(|| format!("no queued config change #{id} at {address}"))?;
+        accounts::pending_config_change(&data).map_err(protocol_error)
+    }
+
+    fn governance_council(&self) -> Result<GovernanceCouncil> {
+        // 函数：读取并解码治理委员会
+        let address = pda::governance_council().0;
+        let data = self
+            .rpc
+            .account_data(&address)?
+            .with_context(|| format!("governance council {address} does not exist"))?;
+        accounts::governance_council(&data).map_err(protocol_error)
+    }
+
+    fn council_proposal(&self, id: u64) -> Result<CouncilProposal> {
+        // 函数：读取并解码委员会提案
+        let address = pda::council_proposal(id).0;
+        let data = self
+            .rpc
+            .account_data(&address)?
+            .with_context(|| format!("no council proposal #{id} at {address}"))?;
+        accounts::council_proposal(&data).map_err(protocol_error)
+    }
+
+    fn protocol_config(&self) -> Result<ProtocolConfig> {
+        // 函数：读取并解码协议配置
+        let address = pda::protocol_config().0;
+        let data = self
+            .rpc
+            .account_data(&address)?
+            .with_context(|| format!("protocol config {address} does not exist"))?;
+        accounts::protocol_config(&data).map_err(protocol_error)
+    }
+
+    fn bonding_curve(&self, mint: &Pubkey) -> Result<BondingCurve> {
+        // 函数：读取并解码绑定曲线
+        let data = self
+            .rpc
+            .account_data(&pda::bonding_curve(mint).0)?
+            .with_context(|| format!("no bonding curve for mint {mint}"))?;
+        accounts::bonding_curve(&data).map_err(protocol_error)
+    }
+
+    fn fee_split(&self, mint: &Pubkey) -> Result<FeeSplit> {
+        // 函数：读取并解码创作者费用分账账户
+        let data = self
+            .rpc
+            .account_data(&pda::fee_split(mint).0)?
+            .with_context(|| format!("no fee split for mint {mint}"))?;
+        accounts::fee_split(&data).map_err(protocol_error)
+    }
+
+    fn send(&mut self, instructions: &[Instruction], extra_signers: &[&Keypair]) -> Result<()> {
+        // 函数：签名发送交易，打印签名和解码后的事件
+        let mut signers = vec![self.payer];
+        signers.extend_from_slice(extra_signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &signers,
+            self.rpc.latest_blockhash()?,
+        );
+        let confirmed = self.rpc.send_transaction(&transaction)?;
+        writeln!(self.out, "signature {}", confirmed.signature)?;
+        for event in events::from_logs(&confirmed.logs) {
+            writeln!(self.out, "{event}")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,15 @@
+use clap::Parser;
+use pumpfun_cli::{load_keypair, rpc::HttpRpc, App, Cli};
+
+fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+    let payer = load_keypair(cli.keypair)?;
+    let mut rpc = HttpRpc::new(cli.url);
+    let mut stdout = std::io::stdout();
+    App {
+        rpc: &mut rpc,
+        payer: &payer,
+        out: &mut stdout,
+    }
+    .run(cli.command)
+}
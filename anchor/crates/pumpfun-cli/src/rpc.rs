@@ -0,0 +1,128 @@
+//! CLI 与集群之间的最小接口。正式使用 [`HttpRpc`]（JSON-RPC），测试里换成内存中的验证器替身。
+
+use std::{thread, time::Duration};
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::{json, Value};
+use solana_transaction::Transaction;
+
+pub type Hash = anchor_lang::solana_program::hash::Hash;
+
+#[derive(Debug, Clone, Default)] // 已确认交易
+pub struct Confirmed {
+    pub signature: String, // 交易签名
+    pub logs: Vec<String>, // 程序日志（事件从中解码）
+}
+
+pub trait Rpc {
+    // 接口：读取账户、获取区块哈希、发送交易
+    fn account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>>; // 账户数据，不存在时为 None
+    fn program_accounts(&self, program_id: &Pubkey, discriminator: &[u8]) -> Result<Vec<(Pubkey, Vec<u8>)>>; // 按判别符筛选程序账户
+    fn latest_blockhash(&self) -> Result<Hash>; // 最新区块哈希
+    fn send_transaction(&mut self, transaction: &Transaction) -> Result<Confirmed>; // 发送并等待确认
+}
+
+pub struct HttpRpc {
+    url: String,         // RPC 地址
+    agent: ureq::Agent, // HTTP 客户端
+}
+
+impl HttpRpc {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            agent: ureq::AgentBuilder::new().timeout(Duration::from_secs(30)).build(),
+        }
+    }
+
+    fn call(&self, method: &str, params: Value) -> Result<Value> {
+        // 函数：发送一次 JSON-RPC 请求，返回 result 字段
+        let response: Value = self
+            .agent
+            .post(&self.url)
+            .send_json(json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }))
+            .with_context(|| format!("{method} request to {} failed", self.url))?
+            .into_json()?;
+        if let Some(error) = response.get("error") {
+            bail!("{method} failed: {error}");
+        }
+        Ok(response["result"].clone())
+    }
+}
+
+fn decode_data(value: &Value) -> Result<Vec<u8>> {
+    // 函数：解码 ["<base64>", "base64"] 形式的账户数据
+    let encoded = value[0].as_str().ok_or_else(|| anyhow!("unexpected account data encoding"))?;
+    Ok(STANDARD.decode(encoded)?)
+}
+
+impl Rpc for HttpRpc {
+    fn account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>> {
+        let result = self.call(
+            "getAccountInfo",
+            json!([address.to_string(), { "encoding": "base64", "commitment": "confirmed" }]),
+        )?;
+        match &result["value"] {
+            Value::Null => Ok(None),
+            account => Ok(Some(decode_data(&account["data"])?)),
+        }
+    }
+
+    fn program_accounts(&self, program_id: &Pubkey, discriminator: &[u8]) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+        let filter = json!({ "memcmp": { "offset": 0, "bytes": bs58::encode(discriminator).into_string() } });
+        let result = self.call(
+            "getProgramAccounts",
+            json!([program_id.to_string(), { "encoding": "base64", "commitment": "confirmed", "filters": [filter] }]),
+        )?;
+        let entries = result.as_array().ok_or_else(|| anyhow!("unexpected getProgramAccounts response"))?;
+        entries
+            .iter()
+            .map(|entry| {
+                let address = entry["pubkey"].as_str().unwrap_or_default().parse()?;
+                Ok((address, decode_data(&entry["account"]["data"])?))
+            })
+            .collect()
+    }
+
+    fn latest_blockhash(&self) -> Result<Hash> {
+        let result = self.call("getLatestBlockhash", json!([{ "commitment": "confirmed" }]))?;
+        let blockhash = result["value"]["blockhash"]
+            .as_str()
+            .ok_or_else(|| anyhow!("unexpected getLatestBlockhash response"))?;
+        Ok(blockhash.parse()?)
+    }
+
+    fn send_transaction(&mut self, transaction: &Transaction) -> Result<Confirmed> {
+        let wire = STANDARD.encode(bincode::serialize(transaction)?);
+        let signature = self
+            .call(
+                "sendTransaction",
+                json!([wire, { "encoding": "base64", "preflightCommitment": "confirmed" }]),
+            )?
+            .as_str()
+            .ok_or_else(|| anyhow!("unexpected sendTransaction response"))?
+            .to_string();
+        for _ in 0..60 {
+            // 轮询直到交易确认（约 30 秒）
+            let result = self.call(
+                "getTransaction",
+                json!([signature, { "encoding": "json", "commitment": "confirmed", "maxSupportedTransactionVersion": 0 }]),
+            )?;
+            if !result.is_null() {
+                let meta = &result["meta"];
+                if !meta["err"].is_null() {
+                    bail!("transaction {signature} failed: {}", meta["err"]);
+                }
+                let logs = meta["logMessages"]
+                    .as_array()
+                    .map(|lines| lines.iter().filter_map(|line| line.as_str().map(String::from)).collect())
+                    .unwrap_or_default();
+                return Ok(Confirmed { signature, logs });
+            }
+            thread::sleep(Duration::from_millis(500));
+        }
+        bail!("transaction {signature} was not confirmed in time")
+    }
+}
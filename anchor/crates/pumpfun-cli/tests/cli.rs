@@ -0,0 +1,423 @@
+//! 用内存中的验证器替身驱动 CLI：账户从表中读取，发送的交易被记录下来而不执行。
+
+use std::collections::HashMap;
+
+use anchor_lang::{prelude::Pubkey, AccountSerialize, AnchorDeserialize, Discriminator, Event};
+use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use clap::Parser;
+use pumpfun::{
+    BondingCurve, BuyEvent, ConfigChange, CouncilAction, CouncilProposal, CurveKind, CurvePreset, FeeSplit,
+    GovernanceCouncil, PendingConfigChange, ProtocolConfig, MAX_FEE_SPLIT_RECIPIENTS,
+};
+use pumpfun_cli::{
+    rpc::{Confirmed, Hash, Rpc},
+    App, Cli,
+};
+use pumpfun_client::pda;
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+#[derive(Default)]
+struct StandIn {
+    accounts: HashMap<Pubkey, Vec<u8>>, // 账户数据
+    logs: Vec<String>,                  // 每笔交易返回的日志
+    sent: Vec<Transaction>,             // 已发送的交易
+}
+
+impl StandIn {
+    fn store<T: AccountSerialize>(&mut self, address: Pubkey, account: &T) {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        self.accounts.insert(address, data);
+    }
+}
+
+impl Rpc for StandIn {
+    fn account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>> {
+        Ok(self.accounts.get(address).cloned())
+    }
+
+    fn program_accounts(&self, _program_id: &Pubkey, discriminator: &[u8]) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+        Ok(self
+            .accounts
+            .iter()
+            .filter(|(_, data)| data.starts_with(discriminator))
+            .map(|(address, data)| (*address, data.clone()))
+            .collect())
+    }
+
+    fn latest_blockhash(&self) -> Result<Hash> {
+        Ok(Hash::default())
+    }
+
+    fn send_transaction(&mut self, transaction: &Transaction) -> Result<Confirmed> {
+        self.sent.push(transaction.clone());
+        Ok(Confirmed {
+            signature: transaction.signatures[0].to_string(),
+            logs: self.logs.clone(),
+        })
+    }
+}
+
+fn config(governance: Pubkey) -> ProtocolConfig {
+    let mut curve_presets = [CurvePreset::default(); 4];
+    curve_presets[0] = CurvePreset {
+        virtual_sol_reserves: 1_000_000_000,
+        virtual_token_reserves: 100_000_000_000,
+        total_supply: 1_000_000_000_000_000,
+        decimals: 6,
+        curve_supply_bps: 9000,
+        curve_kind: CurveKind::ConstantProduct,
+    };
+    ProtocolConfig {
+        governance_authority: governance,
+        treasury: Pubkey::new_unique(),
+        creation_fee_sol: 1_000_000_000,
+        total_trade_fee_bps: 30,
+        creator_fee_bps_share: 10,
+        migration_threshold_sol: 50_000_000_000,
+        paused: 0,
+        bump: 255,
+        dex_lp_fee_bps: 30,
+        curve_presets,
+        curve_preset_count: 1,
+        pending_governance: Pubkey::default(),
+        config_timelock_secs: 86_400,
+        next_config_change_id: 3,
+        guardian: Pubkey::default(),
+    }
+}
+
+fn curve(mint: Pubkey) -> BondingCurve {
+    let creator = Pubkey::new_unique();
+    BondingCurve {
+        creator,
+        token_mint: mint,
+        token_vault: pda::token_vault(&mint),
+        virtual_sol_reserves: 1_000_000_000,
+        virtual_token_reserves: 100_000_000_000,
+        real_sol_reserves: 0,
+        is_completed: false,
+        dex_pool: Pubkey::default(),
+        creator_fees_owed: 0,
+        bump: 255,
+        initial_virtual_sol_reserves: 1_000_000_000,
+        initial_virtual_token_reserves: 100_000_000_000,
+        total_supply: 1_000_000_000_000_000,
+        decimals: 6,
+        real_token_reserves: 900_000_000_000_000,
+        curve_kind: CurveKind::ConstantProduct,
+        is_frozen: false,
+        is_unwinding: false,
+        has_creator_vesting: false,
+        pending_creator: Pubkey::default(),
+        creator_fee_recipient: creator,
+        has_fee_split: false,
+        redeemed_tokens: 0,
+        redeemed_sol: 0,
+    }
+}
+
+fn setup() -> (StandIn, Keypair, Pubkey) {
+    let payer = Keypair::new();
+    let mint = Pubkey::new_unique();
+    let mut rpc = StandIn::default();
+    rpc.store(pda::protocol_config().0, &config(payer.pubkey()));
+    rpc.store(pda::bonding_curve(&mint).0, &curve(mint));
+    (rpc, payer, mint)
+}
+
+fn run(rpc: &mut StandIn, payer: &Keypair, args: &[&str]) -> String {
+    let cli = Cli::try_parse_from(std::iter::once("pumpfun-cli").chain(args.iter().copied())).unwrap();
+    let mut out = Vec::new();
+    App {
+        rpc,
+        payer,
+        out: &mut out,
+    }
+    .run(cli.command)
+    .unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn config_show_prints_decoded_config() {
+    let (mut rpc, payer, _) = setup();
+    let out = run(&mut rpc, &payer, &["config", "show"]);
+    assert!(out.contains(&pda::protocol_config().0.to_string()));
+    assert!(out.contains("total_trade_fee_bps: 30"));
+    assert!(rpc.sent.is_empty());
+}
+
+#[test]
+fn config_pause_sends_set_paused() {
+    let (mut rpc, payer, _) = setup();
+    run(&mut rpc, &payer, &["config", "pause"]);
+    let data = &rpc.sent[0].message.instructions[0].data;
+    let data = data.strip_prefix(pumpfun::instruction::SetPaused::DISCRIMINATOR).unwrap();
+    assert_eq!(data, [pumpfun::PAUSE_ALL]);
+}
+
+#[test]
+fn config_unpause_clears_only_the_named_families() {
+    let (mut rpc, payer, _) = setup();
+    let mut paused = config(payer.pubkey());
+    paused.paused = pumpfun::PAUSE_ALL;
+    rpc.store(pda::protocol_config().0, &paused);
+    run(&mut rpc, &payer, &["config", "unpause", "--family", "sell", "--family", "claim"]);
+    let data = &rpc.sent[0].message.instructions[0].data;
+    let data = data.strip_prefix(pumpfun::instruction::SetPaused::DISCRIMINATOR).unwrap();
+    assert_eq!(data, [pumpfun::PAUSE_CREATE | pumpfun::PAUSE_BUY | pumpfun::PAUSE_MIGRATE]);
+}
+
+#[test]
+fn config_update_queues_one_change_per_changed_field_group() {
+    let (mut rpc, payer, _) = setup();
+    run(&mut rpc, &payer, &["config", "update", "--creator-fee-bps-share", "20", "--creation-fee-sol", "0"]);
+    let instructions = &rpc.sent[0].message.instructions;
+    let keys = &rpc.sent[0].message.account_keys;
+    assert_eq!(instructions.len(), 2);
+    let changes: Vec<ConfigChange> = instructions
+        .iter()
+        .map(|ix| {
+            let data = ix.data.strip_prefix(pumpfun::instruction::QueueConfigChange::DISCRIMINATOR).unwrap();
+            ConfigChange::deserialize(&mut &data[..]).unwrap()
+        })
+        .collect();
+    assert_eq!(
+        changes,
+        [
+            ConfigChange::Fees {
+                total_trade_fee_bps: 30, // 未指定的费用保持当前值
+                creator_fee_bps_share: 20,
+                dex_lp_fee_bps: 30,
+            },
+            ConfigChange::CreationFee { creation_fee_sol: 0 },
+        ]
+    );
+    // 连续编号，从配置中的 next_config_change_id 开始
+    assert_eq!(keys[instructions[0].accounts[2] as usize], pda::pending_config_change(3).0);
+    assert_eq!(keys[instructions[1].accounts[2] as usize], pda::pending_config_change(4).0);
+}
+
+#[test]
+fn config_execute_passes_new_treasury_for_treasury_change() {
+    let (mut rpc, payer, _) = setup();
+    let treasury = Pubkey::new_unique();
+    let pending = PendingConfigChange {
+        id: 7,
+        change: ConfigChange::Treasury { treasury },
+        queued_at: 0,
+        executable_at: 86_400,
+        bump: 255,
+    };
+    rpc.store(pda::pending_config_change(7).0, &pending);
+    let out = run(&mut rpc, &payer, &["config", "pending"]);
+    assert!(out.contains("#7 executable_at=86400 Treasury"));
+    run(&mut rpc, &payer, &["config", "execute", "7"]);
+    let message = &rpc.sent[0].message;
+    let accounts: Vec<Pubkey> = message.instructions[0]
+        .accounts
+        .iter()
+        .map(|&index| message.account_keys[index as usize])
+        .collect();
+    assert_eq!(accounts[2..], [pda::pending_config_change(7).0, treasury]);
+}
+
+#[test]
+fn config_propose_governance_sends_nominee() {
+    let (mut rpc, payer, _) = setup();
+    let nominee = Pubkey::new_unique();
+    run(&mut rpc, &payer, &["config", "propose-governance", &nominee.to_string()]);
+    let data = &rpc.sent[0].message.instructions[0].data;
+    let data = data
+        .strip_prefix(pumpfun::instruction::ProposeGovernance::DISCRIMINATOR)
+        .unwrap();
+    assert_eq!(Pubkey::deserialize(&mut &data[..]).unwrap(), nominee);
+}
+
+fn council(members: &[Pubkey]) -> GovernanceCouncil {
+    let mut padded = [Pubkey::default(); pumpfun::MAX_COUNCIL_MEMBERS];
+    padded[..members.len()].copy_from_slice(members);
+    GovernanceCouncil {
+        members: padded,
+        member_count: members.len() as u8,
+        threshold: 2,
+        generation: 0,
+        next_proposal_id: 5,
+        bump: 255,
+        authority_bump: 255,
+    }
+}
+
+#[test]
+fn config_via_council_proposes_instead_of_signing() {
+    let (mut rpc, payer, _) = setup();
+    rpc.store(pda::governance_council().0, &council(&[payer.pubkey(), Pubkey::new_unique()]));
+    run(&mut rpc, &payer, &["config", "pause", "--via-council"]);
+    let message = &rpc.sent[0].message;
+    let data = message.instructions[0]
+        .data
+        .strip_prefix(pumpfun::instruction::CreateCouncilProposal::DISCRIMINATOR)
+        .unwrap();
+    let action = CouncilAction::deserialize(&mut &data[..]).unwrap();
+    assert_eq!(action, CouncilAction::SetPaused { paused: pumpfun::PAUSE_ALL });
+    assert!(message.account_keys.contains(&pda::council_proposal(5).0));
+}
+
+#[test]
+fn council_execute_appends_governance_accounts() {
+    let (mut rpc, payer, _) = setup();
+    let proposer = Pubkey::new_unique();
+    rpc.store(pda::governance_council().0, &council(&[payer.pubkey(), proposer]));
+    let proposal = CouncilProposal {
+        id: 5,
+        proposer,
+        action: CouncilAction::SetPaused { paused: pumpfun::PAUSE_ALL },
+        approvals: 0b11,
+        generation: 0,
+        bump: 255,
+    };
+    rpc.store(pda::council_proposal(5).0, &proposal);
+    let out = run(&mut rpc, &payer, &["council", "show"]);
+    assert!(out.contains("#5 approvals=2/2"));
+    run(&mut rpc, &payer, &["council", "execute", "5"]);
+    let message = &rpc.sent[0].message;
+    let accounts: Vec<Pubkey> = message.instructions[0]
+        .accounts
+        .iter()
+        .map(|&index| message.account_keys[index as usize])
+        .collect();
+    // 提案执行的账户之后是 set_paused 的账户：委员会权限和配置
+    assert_eq!(accounts[6..], [pda::council_authority().0, pda::protocol_config().0]);
+    assert!(!message.is_signer(message.account_keys.iter().position(|k| *k == pda::council_authority().0).unwrap()));
+}
+
+#[test]
+fn trade_buy_quote_only_matches_program_quote_and_sends_nothing() {
+    let (mut rpc, payer, mint) = setup();
+    let out = run(&mut rpc, &payer, &["trade", "buy", &mint.to_string(), "--sol", "100000000", "--quote-only"]);
+    let quote = curve(mint).quote_buy(&config(payer.pubkey()), 100_000_000).unwrap();
+    assert!(out.contains(&format!("tokens_out: {}", quote.tokens_out)));
+    assert!(out.contains(&format!("min_tokens_out {}", quote.tokens_out * 99 / 100)));
+    assert!(rpc.sent.is_empty());
+}
+
+#[test]
+fn trade_buy_prints_events_from_logs() {
+    let (mut rpc, payer, mint) = setup();
+    let event = BuyEvent {
+        mint,
+        buyer: payer.pubkey(),
+        sol_in: 100_000_000,
+        tokens_out: 42,
+        sol_refunded: 0,
+        creator_fee: 1,
+        treasury_fee: 2,
+    };
+    rpc.logs = vec![format!("Program data: {}", STANDARD.encode(event.data()))];
+    let out = run(&mut rpc, &payer, &["trade", "buy", &mint.to_string(), "--sol", "100000000"]);
+    assert_eq!(rpc.sent.len(), 1);
+    assert!(out.contains("BuyEvent"));
+    assert!(out.contains("tokens_out: 42"));
+}
+
+#[test]
+fn trade_redeem_pays_the_pro_rata_share_of_real_reserves() {
+    let (mut rpc, payer, mint) = setup();
+    let mut unwinding = curve(mint);
+    unwinding.virtual_token_reserves = 80_000_000_000; // 已售出 200 亿
+    unwinding.real_sol_reserves = 3_000_000_000;
+    unwinding.is_unwinding = true;
+    rpc.store(pda::bonding_curve(&mint).0, &unwinding);
+    let out = run(&mut rpc, &payer, &["trade", "redeem", &mint.to_string(), "--tokens", "10000000000"]);
+    assert!(out.contains("sol_out 1500000000"));
+    let data = &rpc.sent[0].message.instructions[0].data;
+    assert!(data.starts_with(pumpfun::instruction::EmergencyRedeem::DISCRIMINATOR));
+}
+
+#[test]
+fn fees_claim_on_a_vesting_curve_claims_the_released_part() {
+    let (mut rpc, payer, mint) = setup();
+    let mut vesting = curve(mint);
+    vesting.creator = payer.pubkey();
+    vesting.has_creator_vesting = true;
+    vesting.creator_fee_recipient = Pubkey::new_unique(); // 例如多签
+    rpc.store(pda::bonding_curve(&mint).0, &vesting);
+    run(&mut rpc, &payer, &["fees", "claim", &mint.to_string(), "--max-amount", "7"]);
+    let message = &rpc.sent[0].message;
+    let data = message.instructions[0]
+        .data
+        .strip_prefix(pumpfun::instruction::ClaimVestedCreatorFees::DISCRIMINATOR)
+        .unwrap();
+    assert_eq!(data, 7u64.to_le_bytes());
+    assert!(message.account_keys.contains(&pda::creator_vesting(&mint).0));
+    assert!(message.account_keys.contains(&vesting.creator_fee_recipient));
+}
+
+#[test]
+fn fees_set_split_on_a_split_curve_pays_out_the_current_recipients_first() {
+    let (mut rpc, payer, mint) = setup();
+    let mut split_curve = curve(mint);
+    split_curve.creator = payer.pubkey();
+    split_curve.has_fee_split = true;
+    split_curve.creator_fee_recipient = pda::fee_split(&mint).0;
+    rpc.store(pda::bonding_curve(&mint).0, &split_curve);
+    let (old, new) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut recipients = [Pubkey::default(); MAX_FEE_SPLIT_RECIPIENTS];
+    recipients[0] = old;
+    let mut shares_bps = [0; MAX_FEE_SPLIT_RECIPIENTS];
+    shares_bps[0] = 10_000;
+    let split = FeeSplit {
+        token_mint: mint,
+        recipients,
+        shares_bps,
+        claimed: [0; MAX_FEE_SPLIT_RECIPIENTS],
+        recipient_count: 1,
+        total_claimed: 0,
+        bump: 255,
+    };
+    rpc.store(pda::fee_split(&mint).0, &split);
+    let (old_share, new_share) = (format!("{old}:2500"), format!("{new}:7500"));
+    let args = ["fees", "set-split", &mint.to_string(), "--recipient", &old_share, "--recipient", &new_share];
+    run(&mut rpc, &payer, &args);
+    let message = &rpc.sent[0].message;
+    let instruction = &message.instructions[0];
+    let data = instruction
+        .data
+        .strip_prefix(pumpfun::instruction::UpdateFeeSplit::DISCRIMINATOR)
+        .unwrap();
+    let update = pumpfun::instruction::UpdateFeeSplit::try_from_slice(data).unwrap();
+    assert_eq!((update.recipients, update.shares_bps), (vec![old, new], vec![2_500, 7_500]));
+    // 旧接收者作为剩余账户跟在上下文账户之后
+    let last = *instruction.accounts.last().unwrap() as usize;
+    assert_eq!(message.account_keys[last], old);
+}
+
+#[test]
+fn curve_list_shows_every_curve() {
+    let (mut rpc, payer, mint) = setup();
+    let other = Pubkey::new_unique();
+    rpc.store(pda::bonding_curve(&other).0, &curve(other));
+    let out = run(&mut rpc, &payer, &["curve", "list"]);
+    assert_eq!(out.lines().count(), 2);
+    assert!(out.contains(&format!("mint={mint}")));
+    assert!(out.contains(&format!("mint={other}")));
+}
+
+#[test]
+fn migrate_initializes_missing_dex_pool_first() {
+    let (mut rpc, payer, mint) = setup();
+    run(&mut rpc, &payer, &["migrate", &mint.to_string()]);
+    let message = &rpc.sent[0].message;
+    let discriminators: Vec<&[u8]> = message.instructions.iter().map(|ix| &ix.data[..8]).collect();
+    assert_eq!(
+        discriminators,
+        [
+            pumpfun::instruction::InitializeDexPool::DISCRIMINATOR,
+            pumpfun::instruction::CompleteAndMigrate::DISCRIMINATOR
+        ]
+    );
+}
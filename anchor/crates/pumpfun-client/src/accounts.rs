@@ -0,0 +1,51 @@
+use anchor_lang::{AccountDeserialize, Result};
+
+use crate::{
+    BondingCurve, CouncilProposal, CreatorVesting, DexPool, FeeSplit, GovernanceCouncil, PendingConfigChange,
+    ProtocolConfig,
+};
+
+pub fn decode<T: AccountDeserialize>(data: &[u8]) -> Result<T> {
+    // 函数：按 Anchor 判别符解码账户数据，判别符不匹配时报错
+    T::try_deserialize(&mut &data[..])
+}
+
+pub fn protocol_config(data: &[u8]) -> Result<ProtocolConfig> {
+    // 函数：解码协议配置
+    decode(data)
+}
+
+pub fn pending_config_change(data: &[u8]) -> Result<PendingConfigChange> {
+    // 函数：解码排队中的配置变更
+    decode(data)
+}
+
+pub fn governance_council(data: &[u8]) -> Result<GovernanceCouncil> {
+    // 函数：解码治理委员会
+    decode(data)
+}
+
+pub fn council_proposal(data: &[u8]) -> Result<CouncilProposal> {
+    // 函数：解码委员会提案
+    decode(data)
+}
+
+pub fn bonding_curve(data: &[u8]) -> Result<BondingCurve> {
+    // 函数：解码绑定曲线
+    decode(data)
+}
+
+pub fn creator_vesting(data: &[u8]) -> Result<CreatorVesting> {
+    // 函数：解码创作者费用释放账户
+    decode(data)
+}
+
+pub fn fee_split(data: &[u8]) -> Result<FeeSplit> {
+    // 函数：解码创作者费用分账账户
+    decode(data)
+}
+
+pub fn dex_pool(data: &[u8]) -> Result<DexPool> {
+    // 函数：解码 DEX 池
+    decode(data)
+}
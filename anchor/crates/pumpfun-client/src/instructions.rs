@@ -0,0 +1,741 @@
+//! 指令构造：每个 `#[derive(Accounts)]` 上下文对应一个结构体，只需填写无法派生的账户，
+//! PDA、关联代币账户和程序账户都在这里补齐。
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::associated_token;
+use pumpfun::{accounts as ix_accounts, instruction as ix_data};
+
+use crate::{pda, ConfigChange, CouncilAction, CurvePreset, ID, TOKEN_PROGRAM_ID};
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    // 函数：组装指令
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+#[derive(Debug, Clone, Copy)] // 初始化配置
+pub struct InitializeConfig {
+    pub authority: Pubkey, // 治理权限（签名并支付）
+    pub treasury: Pubkey,  // 国库
+}
+impl InitializeConfig {
+    pub fn instruction(&self) -> Instruction {
+        build(
+            ix_accounts::InitializeConfig {
+                authority: self.authority,
+                protocol_config: pda::protocol_config().0,
+                treasury: self.treasury,
+                system_program: system_program::ID,
+            },
+            ix_data::InitializeConfig {},
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy)] // 治理操作（排队配置变更、暂停、设置守护者、冻结和清退曲线、设置曲线预设、提名新治理权限、创建治理委员会）
+pub struct Governance {
+    pub governance_authority: Pubkey, // 治理权限（签名）
+}
+impl Governance {
+    pub fn queue_config_change(&self, id: u64, change: ConfigChange) -> Instruction {
+        // 函数：排队配置变更；`id` 必须等于配置中的 next_config_change_id
+        build(
+            ix_accounts::QueueConfigChange {
+                governance_authority: self.governance_authority,
+                protocol_config: pda::protocol_config().0,
+                pending_change: pda::pending_config_change(id).0,
+                system_program: system_program::ID,
+            },
+            ix_data::QueueConfigChange { change },
+        )
+    }
+
+    pub fn execute_config_change(&self, id: u64, change: &ConfigChange) -> Instruction {
+        // 函数：执行已排队的变更；国库变更需要传入新国库账户
+        let new_treasury = match change {
+            ConfigChange::Treasury { treasury } => Some(*treasury),
+            _ => None,
+        };
+        build(
+            ix_accounts::ExecuteConfigChange {
+                governance_authority: self.governance_authority,
+                protocol_config: pda::protocol_config().0,
+                pending_change: pda::pending_config_change(id).0,
+                new_treasury,
+            },
+            ix_data::ExecuteConfigChange { id },
+        )
+    }
+
+    pub fn cancel_config_change(&self, id: u64) -> Instruction {
+        build(
+            ix_accounts::CancelConfigChange {
+                governance_authority: self.governance_authority,
+                protocol_config: pda::protocol_config().0,
+                pending_change: pda::pending_config_change(id).0,
+            },
+            ix_data::CancelConfigChange { id },
+        )
+    }
+
+    pub fn set_paused(&self, paused: u8) -> Instruction {
+        // 函数：设置暂停位（`pumpfun::PAUSE_*` 的组合，0 表示全部恢复）
+        build(self.config_accounts(), ix_data::SetPaused { paused })
+    }
+
+    pub fn set_guardian(&self, guardian: Pubkey) -> Instruction {
+        build(self.config_accounts(), ix_data::SetGuardian { guardian })
+    }
+
+    pub fn freeze_curve(&self, mint: Pubkey, frozen: bool) -> Instruction {
+        build(self.curve_accounts(&mint), ix_data::FreezeCurve { mint, frozen })
+    }
+
+    pub fn emergency_unwind(&self, mint: Pubkey, unwinding: bool) -> Instruction {
+        build(self.curve_accounts(&mint), ix_data::EmergencyUnwind { mint, unwinding })
+    }
+
+    pub fn set_curve_preset(&self, index: u8, preset: CurvePreset) -> Instruction {
+        build(
+            ix_accounts::SetCurvePreset {
+                governance_authority: self.governance_authority,
+                protocol_config: pda::protocol_config().0,
+            },
+            ix_data::SetCurvePreset { index, preset },
+        )
+    }
+
+    pub fn propose_governance(&self, new_authority: Pubkey) -> Instruction {
+        build(self.proposal_accounts(), ix_data::ProposeGovernance { new_authority })
+    }
+
+    pub fn cancel_governance_proposal(&self) -> Instruction {
+        build(self.proposal_accounts(), ix_data::CancelGovernanceProposal {})
+    }
+
+    pub fn create_governance_council(&self, members: Vec<Pubkey>, threshold: u8) -> Instruction {
+        build(
+            ix_accounts::CreateGovernanceCouncil {
+                governance_authority: self.governance_authority,
+                protocol_config: pda::protocol_config().0,
+                council: pda::governance_council().0,
+                council_authority: pda::council_authority().0,
+                system_program: system_program::ID,
+            },
+            ix_data::CreateGovernanceCouncil { members, threshold },
+        )
+    }
+
+    fn config_accounts(&self) -> ix_accounts::SetConfig {
+        ix_accounts::SetConfig {
+            governance_authority: self.governance_authority,
+            protocol_config: pda::protocol_config().0,
+        }
+    }
+
+    fn curve_accounts(&self, mint: &Pubkey) -> ix_accounts::CurveGovernance {
+        ix_accounts::CurveGovernance {
+            governance_authority: self.governance_authority,
+            protocol_config: pda::protocol_config().0,
+            bonding_curve: pda::bonding_curve(mint).0,
+        }
+    }
+
+    fn proposal_accounts(&self) -> ix_accounts::GovernanceProposal {
+        ix_accounts::GovernanceProposal {
+            governance_authority: self.governance_authority,
+            protocol_config: pda::protocol_config().0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)] // 接受治理权限
+pub struct AcceptGovernance {
+    pub pending_governance: Pubkey, // 被提名的新治理权限（签名）
+}
+impl AcceptGovernance {
+    pub fn instruction(&self) -> Instruction {
+        build(
+            ix_accounts::AcceptGovernance {
+                pending_governance: self.pending_governance,
+                protocol_config: pda::protocol_config().0,
+            },
+            ix_data::AcceptGovernance {},
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy)] // 守护者暂停（只能增加暂停位）
+pub struct GuardianPause {
+    pub guardian: Pubkey, // 守护者（签名）
+}
+impl GuardianPause {
+    pub fn instruction(&self, families: u8) -> Instruction {
+        build(
+            ix_accounts::GuardianPause {
+                guardian: self.guardian,
+                protocol_config: pda::protocol_config().0,
+            },
+            ix_data::GuardianPause { families },
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy)] // 治理委员会成员操作（提案、批准、执行、撤回）
+pub struct Council {
+    pub member: Pubkey, // 委员会成员（签名）
+}
+impl Council {
+    pub fn governance() -> Governance {
+        // 函数：以委员会权限 PDA 作为治理权限的指令构造，用于生成提案执行时调用的治理指令
+        Governance {
+            governance_authority: pda::council_authority().0,
+        }
+    }
+
+    pub fn propose(&self, id: u64, action: CouncilAction) -> Instruction {
+        // 函数：提出提案；`id` 必须等于委员会的 next_proposal_id
+        build(
+            ix_accounts::CreateCouncilProposal {
+                proposer: self.member,
+                council: pda::governance_council().0,
+                proposal: pda::council_proposal(id).0,
+                system_program: system_program::ID,
+            },
+            ix_data::CreateCouncilProposal { action },
+        )
+    }
+
+    pub fn approve(&self, id: u64) -> Instruction {
+        build(
+            ix_accounts::ApproveCouncilProposal {
+                member: self.member,
+                council: pda::governance_council().0,
+                proposal: pda::council_proposal(id).0,
+            },
+            ix_data::ApproveCouncilProposal { id },
+        )
+    }
+
+    pub fn execute(&self, id: u64, proposer: Pubkey, governance_instruction: Option<&Instruction>) -> Instruction {
+        // 函数：执行提案；`governance_instruction` 是提案对应的治理指令（由 [`Council::governance`] 构造），
+        // 其账户作为剩余账户传入，委员会权限的签名由程序补上；成员变更提案传 None
+        let mut instruction = build(
+            ix_accounts::ExecuteCouncilProposal {
+                member: self.member,
+                council: pda::governance_council().0,
+                proposal: pda::council_proposal(id).0,
+                proposer,
+                council_authority: pda::council_authority().0,
+                pumpfun_program: ID,
+            },
+            ix_data::ExecuteCouncilProposal { id },
+        );
+        if let Some(governance_instruction) = governance_instruction {
+            instruction
+                .accounts
+                .extend(governance_instruction.accounts.iter().map(|meta| AccountMeta {
+                    is_signer: false,
+                    ..meta.clone()
+                }));
+        }
+        instruction
+    }
+
+    pub fn cancel(&self, id: u64) -> Instruction {
+        build(
+            ix_accounts::CancelCouncilProposal {
+                proposer: self.member,
+                proposal: pda::council_proposal(id).0,
+            },
+            ix_data::CancelCouncilProposal { id },
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy)] // 创建代币和曲线
+pub struct Create {
+    pub creator: Pubkey,    // 创作者（签名并支付）
+    pub treasury: Pubkey,   // 国库（收取创建费用）
+    pub token_mint: Pubkey, // 新铸币（签名）
+}
+impl Create {
+    pub fn instruction(&self, name: String, symbol: String, uri: String, preset_index: u8) -> Instruction {
+        let mint = &self.token_mint;
+        build(
+            ix_accounts::Create {
+                creator: self.creator,
+                protocol_config: pda::protocol_config().0,
+                treasury: self.treasury,
+                token_mint: self.token_mint,
+                bonding_curve: pda::bonding_curve(mint).0,
+                token_vault: pda::token_vault(mint),
+                migration_vault: pda::migration_vault(mint).0,
+                system_program: system_program::ID,
+                token_program: TOKEN_PROGRAM_ID,
+                associated_token_program: associated_token::ID,
+            },
+            ix_data::Create {
+                name,
+                symbol,
+                uri,
+                preset_index,
+            },
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy)] // 更新元数据
+pub struct UpdateTokenMetadata {
+    pub creator: Pubkey,    // 创作者（签名并补足租金）
+    pub token_mint: Pubkey, // 铸币
+}
+impl UpdateTokenMetadata {
+    pub fn instruction(&self, field: String, value: String) -> Instruction {
+        build(
+            ix_accounts::UpdateTokenMetadata {
+                creator: self.creator,
+                bonding_curve: pda::bonding_curve(&self.token_mint).0,
+                token_mint: self.token_mint,
+                system_program: system_program::ID,
+                token_program: TOKEN_PROGRAM_ID,
+            },
+            ix_data::UpdateTokenMetadata { field, value },
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy)] // 在曲线上买入
+pub struct Buy {
+    pub buyer: Pubkey,      // 买家（签名并支付）
+    pub treasury: Pubkey,   // 国库（配置中的 treasury）
+    pub token_mint: Pubkey, // 铸币
+}
+impl Buy {
+    fn accounts(&self) -> ix_accounts::Buy {
+        let mint = &self.token_mint;
+        ix_accounts::Buy {
+            buyer: self.buyer,
+            protocol_config: pda::protocol_config().0,
+            treasury: self.treasury,
+            bonding_curve: pda::bonding_curve(mint).0,
+            token_mint: self.token_mint,
+            token_vault: pda::token_vault(mint),
+            buyer_token_account: pda::token_account(&self.buyer, mint),
+            system_program: system_program::ID,
+            token_program: TOKEN_PROGRAM_ID,
+            associated_token_program: associated_token::ID,
+        }
+    }
+
+    pub fn buy(&self, total_sol_in: u64, min_tokens_out: u64, deadline: i64) -> Instruction {
+        build(
+            self.accounts(),
+            ix_data::Buy {
+                total_sol_in,
+                min_tokens_out,
+                deadline,
+            },
+        )
+    }
+
+    pub fn buy_exact_tokens_out(&self, tokens_out: u64, max_sol_in: u64, deadline: i64) -> Instruction {
+        build(
+            self.accounts(),
+            ix_data::BuyExactTokensOut {
+                tokens_out,
+                max_sol_in,
+                deadline,
+            },
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy)] // 在曲线上卖出
+pub struct Sell {
+    pub seller: Pubkey,     // 卖家（签名）
+    pub treasury: Pubkey,   // 国库（配置中的 treasury）
+    pub token_mint: Pubkey, // 铸币
+}
+impl Sell {
+    fn accounts(&self) -> ix_accounts::Sell {
+        let mint = &self.token_mint;
+        ix_accounts::Sell {
+            seller: self.seller,
+            protocol_config: pda::protocol_config().0,
+            treasury: self.treasury,
+            bonding_curve: pda::bonding_curve(mint).0,
+            token_mint: self.token_mint,
+            token_vault: pda::token_vault(mint),
+            seller_token_account: pda::token_account(&self.seller, mint),
+            system_program: system_program::ID,
+            token_program: TOKEN_PROGRAM_ID,
+            associated_token_program: associated_token::ID,
+        }
+    }
+
+    pub fn sell(&self, token_amount: u64, min_sol_out: u64, deadline: i64) -> Instruction {
+        build(
+            self.accounts(),
+            ix_data::Sell {
+                token_amount,
+                min_sol_out,
+                deadline,
+            },
+        )
+    }
+
+    pub fn sell_for_exact_sol(&self, sol_out: u64, max_tokens_in: u64, deadline: i64) -> Instruction {
+        build(
+            self.accounts(),
+            ix_data::SellForExactSol {
+                sol_out,
+                max_tokens_in,
+                deadline,
+            },
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy)] // 紧急清退期间按比例赎回
+pub struct EmergencyRedeem {
+    pub holder: Pubkey,     // 持有人（签名）
+    pub token_mint: Pubkey, // 铸币
+}
+impl EmergencyRedeem {
+    pub fn instruction(&self, token_amount: u64) -> Instruction {
+        let mint = &self.token_mint;
+        build(
+            ix_accounts::EmergencyRedeem {
+                holder: self.holder,
+                bonding_curve: pda::bonding_curve(mint).0,
+                token_mint: self.token_mint,
+                token_vault: pda::token_vault(mint),
+                holder_token_account: pda::token_account(&self.holder, mint),
+                token_program: TOKEN_PROGRAM_ID,
+            },
+            ix_data::EmergencyRedeem { token_amount },
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy)] // 领取创作者费用
+pub struct ClaimCreatorFees {
+    pub creator: Pubkey,               // 创作者（签名）
+    pub creator_fee_recipient: Pubkey, // 曲线记录的费用接收者
+    pub token_mint: Pubkey,            // 铸币
+}
+impl ClaimCreatorFees {
+    pub fn instruction(&self) -> Instruction {
+        build(
+            ix_accounts::ClaimCreatorFees {
+                creator: self.creator,
+                creator_fee_recipient: self.creator_fee_recipient,
+                protocol_config: pda::protocol_config().0,
+                bonding_curve: pda::bonding_curve(&self.token_mint).0,
+                system_program: system_program::ID,
+            },
+            ix_data::ClaimCreatorFees {},
+        )
+    }
+
+    pub fn distribute(&self, recipients: &[Pubkey]) -> Instruction {
+        // 函数：开启分账的曲线领取后直接按比例付给全部接收者；`recipients` 是分账账户记录的接收者（按顺序）
+        let mut instruction = self.instruction();
+        instruction
+            .accounts
+            .extend(recipients.iter().map(|recipient| AccountMeta::new(*recipient, false)));
+        instruction
+    }
+}
+
+#[derive(Debug, Clone, Copy)] // 创作者操作（提名新创作者、撤回提名、设置费用接收者）
+pub struct Creator {
+    pub creator: Pubkey,    // 当前创作者（签名）
+    pub token_mint: Pubkey, // 铸币
+}
+impl Creator {
+    pub fn transfer_creator(&self, new_creator: Pubkey) -> Instruction {
+        build(self.accounts(), ix_data::TransferCreator { new_creator })
+    }
+
+    pub fn cancel_creator_transfer(&self) -> Instruction {
+        build(self.accounts(), ix_data::CancelCreatorTransfer {})
+    }
+
+    pub fn set_creator_fee_recipient(&self, recipient: Pubkey) -> Instruction {
+        build(self.accounts(), ix_data::SetCreatorFeeRecipient { recipient })
+    }
+
+    fn accounts(&self) -> ix_accounts::CreatorAuthority {
+        ix_accounts::CreatorAuthority {
+            creator: self.creator,
+            bonding_curve: pda::bonding_curve(&self.token_mint).0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)] // 接受创作者权限
+pub struct AcceptCreator {
+    pub pending_creator: Pubkey, // 被提名的新创作者（签名）
+    pub token_mint: Pubkey,      // 铸币
+}
+impl AcceptCreator {
+    pub fn instruction(&self) -> Instruction {
+        build(
+            ix_accounts::AcceptCreator {
+                pending_creator: self.pending_creator,
+                bonding_curve: pda::bonding_curve(&self.token_mint).0,
+            },
+            ix_data::AcceptCreator {},
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy)] // 创作者费用释放计划（开启、领取已释放部分）
+pub struct CreatorFeeVesting {
+    pub creator: Pubkey,    // 创作者（签名）
+    pub token_mint: Pubkey, // 铸币
+}
+impl CreatorFeeVesting {
+    pub fn create(&self, cliff_secs: i64, duration_secs: i64) -> Instruction {
+        build(
+            ix_accounts::CreateCreatorVesting {
+                creator: self.creator,
+                bonding_curve: pda::bonding_curve(&self.token_mint).0,
+                creator_vesting: pda::creator_vesting(&self.token_mint).0,
+                system_program: system_program::ID,
+            },
+            ix_data::CreateCreatorVesting {
+                cliff_secs,
+                duration_secs,
+            },
+        )
+    }
+
+    pub fn claim(&self, creator_fee_recipient: Pubkey, max_amount: u64) -> Instruction {
+        // 函数：领取已释放未领取的费用付给曲线记录的费用接收者，最多 max_amount（传 u64::MAX 领取全部）
+        build(
+            ix_accounts::ClaimVestedCreatorFees {
+                creator: self.creator,
+                creator_fee_recipient,
+                protocol_config: pda::protocol_config().0,
+                bonding_curve: pda::bonding_curve(&self.token_mint).0,
+                creator_vesting: pda::creator_vesting(&self.token_mint).0,
+            },
+            ix_data::ClaimVestedCreatorFees { max_amount },
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy)] // 创作者费用分账（开启、更换比例）
+pub struct CreatorFeeSplit {
+    pub creator: Pubkey,    // 创作者（签名）
+    pub token_mint: Pubkey, // 铸币
+}
+impl CreatorFeeSplit {
+    pub fn create(&self, recipients: Vec<Pubkey>, shares_bps: Vec<u16>) -> Instruction {
+        build(
+            ix_accounts::CreateFeeSplit {
+                creator: self.creator,
+                bonding_curve: pda::bonding_curve(&self.token_mint).0,
+                fee_split: pda::fee_split(&self.token_mint).0,
+                system_program: system_program::ID,
+            },
+            ix_data::CreateFeeSplit { recipients, shares_bps },
+        )
+    }
+
+    pub fn update(&self, current_recipients: &[Pubkey], recipients: Vec<Pubkey>, shares_bps: Vec<u16>) -> Instruction {
+        // 函数：更换比例；`current_recipients` 是当前的接收者（按顺序），更换前按旧比例付清
+        let mut instruction = build(
+            ix_accounts::UpdateFeeSplit {
+                creator: self.creator,
+                protocol_config: pda::protocol_config().0,
+                bonding_curve: pda::bonding_curve(&self.token_mint).0,
+                fee_split: pda::fee_split(&self.token_mint).0,
+            },
+            ix_data::UpdateFeeSplit { recipients, shares_bps },
+        );
+        instruction
+            .accounts
+            .extend(current_recipients.iter().map(|recipient| AccountMeta::new(*recipient, false)));
+        instruction
+    }
+}
+
+#[derive(Debug, Clone, Copy)] // 分账接收者领取自己的份额
+pub struct ClaimFeeSplitShare {
+    pub recipient: Pubkey,  // 分账接收者（签名）
+    pub token_mint: Pubkey, // 铸币
+}
+impl ClaimFeeSplitShare {
+    pub fn instruction(&self) -> Instruction {
+        build(
+            ix_accounts::ClaimFeeSplitShare {
+                recipient: self.recipient,
+                protocol_config: pda::protocol_config().0,
+                bonding_curve: pda::bonding_curve(&self.token_mint).0,
+                fee_split: pda::fee_split(&self.token_mint).0,
+            },
+            ix_data::ClaimFeeSplitShare {},
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy)] // 初始化 DEX 池（迁移前调用）
+pub struct InitializeDexPool {
+    pub payer: Pubkey,      // 支付者（签名）
+    pub token_mint: Pubkey, // 铸币
+}
+impl InitializeDexPool {
+    pub fn instruction(&self) -> Instruction {
+        let mint = &self.token_mint;
+        build(
+            ix_accounts::InitializeDexPool {
+                payer: self.payer,
+                token_mint: self.token_mint,
+                dex_pool: pda::dex_pool(mint).0,
+                dex_sol_vault: pda::dex_sol_vault(mint).0,
+                lp_mint: pda::lp_mint(mint).0,
+                dex_token_vault: pda::dex_token_vault(mint),
+                lp_vault: pda::lp_vault(mint),
+                system_program: system_program::ID,
+                token_program: TOKEN_PROGRAM_ID,
+                associated_token_program: associated_token::ID,
+            },
+            ix_data::InitializeDexPool {},
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy)] // 完成曲线并迁移到 DEX（任何人都可以调用）
+pub struct CompleteAndMigrate {
+    pub payer: Pubkey,      // 调用者（签名，收取金库租金）
+    pub token_mint: Pubkey, // 铸币
+}
+impl CompleteAndMigrate {
+    pub fn instruction(&self) -> Instruction {
+        let mint = &self.token_mint;
+        build(
+            ix_accounts::CompleteAndMigrate {
+                payer: self.payer,
+                protocol_config: pda::protocol_config().0,
+                bonding_curve: pda::bonding_curve(mint).0,
+                token_mint: self.token_mint,
+                token_vault: pda::token_vault(mint),
+                migration_vault: pda::migration_vault(mint).0,
+                dex_pool: pda::dex_pool(mint).0,
+                dex_sol_vault: pda::dex_sol_vault(mint).0,
+                dex_token_vault: pda::dex_token_vault(mint),
+                lp_mint: pda::lp_mint(mint).0,
+                lp_vault: pda::lp_vault(mint),
+                system_program: system_program::ID,
+                token_program: TOKEN_PROGRAM_ID,
+                associated_token_program: associated_token::ID,
+            },
+            ix_data::CompleteAndMigrate {},
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy)] // 迁移后在 DEX 池中兑换
+pub struct DexSwap {
+    pub user: Pubkey,       // 交易者（签名）
+    pub token_mint: Pubkey, // 铸币
+}
+impl DexSwap {
+    fn accounts(&self) -> ix_accounts::DexSwap {
+        let mint = &self.token_mint;
+        ix_accounts::DexSwap {
+            user: self.user,
+            protocol_config: pda::protocol_config().0,
+            token_mint: self.token_mint,
+            dex_pool: pda::dex_pool(mint).0,
+            dex_sol_vault: pda::dex_sol_vault(mint).0,
+            dex_token_vault: pda::dex_token_vault(mint),
+            user_token_account: pda::token_account(&self.user, mint),
+            system_program: system_program::ID,
+            token_program: TOKEN_PROGRAM_ID,
+            associated_token_program: associated_token::ID,
+        }
+    }
+
+    pub fn sol_for_tokens(&self, sol_in: u64, min_tokens_out: u64, deadline: i64) -> Instruction {
+        build(
+            self.accounts(),
+            ix_data::DexSwapSolForTokens {
+                sol_in,
+                min_tokens_out,
+                deadline,
+            },
+        )
+    }
+
+    pub fn tokens_for_sol(&self, tokens_in: u64, min_sol_out: u64, deadline: i64) -> Instruction {
+        build(
+            self.accounts(),
+            ix_data::DexSwapTokensForSol {
+                tokens_in,
+                min_sol_out,
+                deadline,
+            },
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy)] // 迁移后注入或移除 DEX 流动性
+pub struct DexLiquidity {
+    pub user: Pubkey,       // 流动性提供者（签名）
+    pub token_mint: Pubkey, // 铸币
+}
+impl DexLiquidity {
+    fn accounts(&self) -> ix_accounts::DexLiquidity {
+        let mint = &self.token_mint;
+        let lp_mint = pda::lp_mint(mint).0;
+        ix_accounts::DexLiquidity {
+            user: self.user,
+            protocol_config: pda::protocol_config().0,
+            token_mint: self.token_mint,
+            dex_pool: pda::dex_pool(mint).0,
+            dex_sol_vault: pda::dex_sol_vault(mint).0,
+            dex_token_vault: pda::dex_token_vault(mint),
+            lp_mint,
+            lp_vault: pda::lp_vault(mint),
+            user_token_account: pda::token_account(&self.user, mint),
+            user_lp_account: pda::token_account(&self.user, &lp_mint),
+            system_program: system_program::ID,
+            token_program: TOKEN_PROGRAM_ID,
+            associated_token_program: associated_token::ID,
+        }
+    }
+
+    pub fn add_liquidity(&self, max_sol_in: u64, max_tokens_in: u64, min_lp_out: u64, deadline: i64) -> Instruction {
+        build(
+            self.accounts(),
+            ix_data::AddLiquidity {
+                max_sol_in,
+                max_tokens_in,
+                min_lp_out,
+                deadline,
+            },
+        )
+    }
+
+    pub fn remove_liquidity(&self, lp_amount: u64, min_sol_out: u64, min_tokens_out: u64, deadline: i64) -> Instruction {
+        build(
+            self.accounts(),
+            ix_data::RemoveLiquidity {
+                lp_amount,
+                min_sol_out,
+                min_tokens_out,
+                deadline,
+            },
+        )
+    }
+}
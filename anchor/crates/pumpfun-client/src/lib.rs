@@ -0,0 +1,17 @@
+//! pumpfun 程序的 Rust 客户端：PDA 派生、指令构造和账户解码。
+//!
+//! 账户列表直接复用程序导出的 `#[derive(Accounts)]` 布局（`pumpfun::accounts`），
+//! 指令数据复用 `pumpfun::instruction`，因此程序改动账户顺序时这里不需要同步修改。
+//! 报价请用 [`pumpfun_math`]，链上链下结果一致。
+
+pub mod accounts; // 账户解码
+pub mod instructions; // 指令构造
+pub mod pda; // PDA 派生
+
+pub use pumpfun::{
+    BondingCurve, ConfigChange, CouncilAction, CouncilProposal, CreatorVesting, CurveKind, CurvePreset, DexPool,
+    FeeSplit, GovernanceCouncil, PendingConfigChange, ProtocolConfig, ID,
+};
+pub use pumpfun_math;
+
+pub use anchor_spl::token_2022::ID as TOKEN_PROGRAM_ID; // 程序只支持 Token-2022
@@ -0,0 +1,84 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+
+use crate::{ID, TOKEN_PROGRAM_ID};
+
+pub fn protocol_config() -> (Pubkey, u8) {
+    // 函数：协议配置 PDA
+    Pubkey::find_program_address(&[b"protocol_config"], &ID)
+}
+
+pub fn pending_config_change(id: u64) -> (Pubkey, u8) {
+    // 函数：排队中的配置变更 PDA
+    Pubkey::find_program_address(&[b"pending_config_change", &id.to_le_bytes()], &ID)
+}
+
+pub fn governance_council() -> (Pubkey, u8) {
+    // 函数：治理委员会 PDA
+    Pubkey::find_program_address(&[b"governance_council"], &ID)
+}
+
+pub fn council_authority() -> (Pubkey, u8) {
+    // 函数：委员会权限 PDA（委员会接管治理后即为配置中的 governance_authority）
+    Pubkey::find_program_address(&[b"council_authority"], &ID)
+}
+
+pub fn council_proposal(id: u64) -> (Pubkey, u8) {
+    // 函数：委员会提案 PDA
+    Pubkey::find_program_address(&[b"council_proposal", &id.to_le_bytes()], &ID)
+}
+
+pub fn bonding_curve(mint: &Pubkey) -> (Pubkey, u8) {
+    // 函数：绑定曲线 PDA
+    Pubkey::find_program_address(&[b"bonding_curve", mint.as_ref()], &ID)
+}
+
+pub fn creator_vesting(mint: &Pubkey) -> (Pubkey, u8) {
+    // 函数：创作者费用释放账户 PDA
+    Pubkey::find_program_address(&[b"creator_vesting", mint.as_ref()], &ID)
+}
+
+pub fn fee_split(mint: &Pubkey) -> (Pubkey, u8) {
+    // 函数：创作者费用分账账户 PDA
+    Pubkey::find_program_address(&[b"fee_split", mint.as_ref()], &ID)
+}
+
+pub fn migration_vault(mint: &Pubkey) -> (Pubkey, u8) {
+    // 函数：迁移储备金库 PDA
+    Pubkey::find_program_address(&[b"migration_vault", mint.as_ref()], &ID)
+}
+
+pub fn dex_pool(mint: &Pubkey) -> (Pubkey, u8) {
+    // 函数：DEX 池 PDA
+    Pubkey::find_program_address(&[b"dex_pool", mint.as_ref()], &ID)
+}
+
+pub fn dex_sol_vault(mint: &Pubkey) -> (Pubkey, u8) {
+    // 函数：DEX SOL 金库 PDA
+    Pubkey::find_program_address(&[b"dex_sol_vault", mint.as_ref()], &ID)
+}
+
+pub fn lp_mint(mint: &Pubkey) -> (Pubkey, u8) {
+    // 函数：LP 铸币 PDA
+    Pubkey::find_program_address(&[b"lp_mint", mint.as_ref()], &ID)
+}
+
+pub fn token_account(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    // 函数：Token-2022 关联代币账户
+    get_associated_token_address_with_program_id(owner, mint, &TOKEN_PROGRAM_ID)
+}
+
+pub fn token_vault(mint: &Pubkey) -> Pubkey {
+    // 函数：曲线代币金库（曲线 PDA 的关联代币账户）
+    token_account(&bonding_curve(mint).0, mint)
+}
+
+pub fn dex_token_vault(mint: &Pubkey) -> Pubkey {
+    // 函数：DEX 代币金库（池子 PDA 的关联代币账户）
+    token_account(&dex_pool(mint).0, mint)
+}
+
+pub fn lp_vault(mint: &Pubkey) -> Pubkey {
+    // 函数：池子自有的 LP 金库（锁定流动性）
+    token_account(&dex_pool(mint).0, &lp_mint(mint).0)
+}
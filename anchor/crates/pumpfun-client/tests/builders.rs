@@ -0,0 +1,362 @@
+//! PDA 派生和指令构造测试：种子与程序账户约束一致，账户顺序、签名和可写标记与程序上下文逐项一致。
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::AccountMeta;
+use anchor_lang::{system_program, Discriminator};
+use anchor_spl::associated_token;
+use pumpfun::instruction as ix_data;
+use pumpfun_client::instructions as ix;
+use pumpfun_client::{pda, ConfigChange, ID, TOKEN_PROGRAM_ID};
+
+fn signer(pubkey: Pubkey) -> AccountMeta {
+    // 函数：可写签名者
+    AccountMeta::new(pubkey, true)
+}
+
+fn readonly_signer(pubkey: Pubkey) -> AccountMeta {
+    // 函数：只读签名者
+    AccountMeta::new_readonly(pubkey, true)
+}
+
+fn writable(pubkey: Pubkey) -> AccountMeta {
+    // 函数：可写非签名账户
+    AccountMeta::new(pubkey, false)
+}
+
+fn readonly(pubkey: Pubkey) -> AccountMeta {
+    // 函数：只读非签名账户
+    AccountMeta::new_readonly(pubkey, false)
+}
+
+fn ata(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    // 函数：按关联代币程序的种子独立派生 Token-2022 关联代币账户
+    Pubkey::find_program_address(&[owner.as_ref(), TOKEN_PROGRAM_ID.as_ref(), mint.as_ref()], &associated_token::ID).0
+}
+
+fn seeded(seeds: &[&[u8]]) -> Pubkey {
+    // 函数：按种子派生本程序的 PDA
+    Pubkey::find_program_address(seeds, &ID).0
+}
+
+#[test]
+fn pda_helpers_use_the_program_seeds() {
+    let mint = Pubkey::new_unique();
+    assert_eq!(pda::protocol_config().0, seeded(&[b"protocol_config"]));
+    assert_eq!(pda::pending_config_change(7).0, seeded(&[b"pending_config_change", &7u64.to_le_bytes()]));
+    assert_eq!(pda::governance_council().0, seeded(&[b"governance_council"]));
+    assert_eq!(pda::council_authority().0, seeded(&[b"council_authority"]));
+    assert_eq!(pda::council_proposal(3).0, seeded(&[b"council_proposal", &3u64.to_le_bytes()]));
+    assert_eq!(pda::bonding_curve(&mint).0, seeded(&[b"bonding_curve", mint.as_ref()]));
+    assert_eq!(pda::creator_vesting(&mint).0, seeded(&[b"creator_vesting", mint.as_ref()]));
+    assert_eq!(pda::fee_split(&mint).0, seeded(&[b"fee_split", mint.as_ref()]));
+    assert_eq!(pda::migration_vault(&mint).0, seeded(&[b"migration_vault", mint.as_ref()]));
+    assert_eq!(pda::dex_pool(&mint).0, seeded(&[b"dex_pool", mint.as_ref()]));
+    assert_eq!(pda::dex_sol_vault(&mint).0, seeded(&[b"dex_sol_vault", mint.as_ref()]));
+    assert_eq!(pda::lp_mint(&mint).0, seeded(&[b"lp_mint", mint.as_ref()]));
+}
+
+#[test]
+fn pda_helpers_return_the_canonical_bump() {
+    let mint = Pubkey::new_unique();
+    let (address, bump) = pda::bonding_curve(&mint);
+    assert_eq!(Pubkey::create_program_address(&[b"bonding_curve", mint.as_ref(), &[bump]], &ID), Ok(address));
+    let (address, bump) = pda::pending_config_change(0);
+    assert_eq!(
+        Pubkey::create_program_address(&[b"pending_config_change", &0u64.to_le_bytes(), &[bump]], &ID),
+        Ok(address)
+    );
+    // 不同 id 的变更和提案落在不同地址
+    assert_ne!(pda::pending_config_change(0).0, pda::pending_config_change(1).0);
+    assert_ne!(pda::council_proposal(0).0, pda::council_proposal(1).0);
+}
+
+#[test]
+fn token_accounts_are_token_2022_associated_accounts() {
+    let mint = Pubkey::new_unique();
+    let owner = Pubkey::new_unique();
+    let curve = pda::bonding_curve(&mint).0;
+    let pool = pda::dex_pool(&mint).0;
+    assert_eq!(pda::token_account(&owner, &mint), ata(&owner, &mint));
+    assert_eq!(pda::token_vault(&mint), ata(&curve, &mint));
+    assert_eq!(pda::dex_token_vault(&mint), ata(&pool, &mint));
+    assert_eq!(pda::lp_vault(&mint), ata(&pool, &pda::lp_mint(&mint).0));
+}
+
+#[test]
+fn initialize_config_accounts() {
+    let authority = Pubkey::new_unique();
+    let treasury = Pubkey::new_unique();
+    let instruction = ix::InitializeConfig { authority, treasury }.instruction();
+    assert_eq!(instruction.program_id, ID);
+    assert_eq!(
+        instruction.accounts,
+        vec![
+            signer(authority),
+            writable(pda::protocol_config().0),
+            writable(treasury),
+            readonly(system_program::ID),
+        ]
+    );
+    assert_eq!(instruction.data, ix_data::InitializeConfig::DISCRIMINATOR);
+}
+
+#[test]
+fn create_accounts_require_the_mint_signature() {
+    let creator = Pubkey::new_unique();
+    let treasury = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let instruction = ix::Create {
+        creator,
+        treasury,
+        token_mint: mint,
+    }
+    .instruction("Name".to_string(), "SYM".to_string(), "https://example.com".to_string(), 2);
+    assert_eq!(
+        instruction.accounts,
+        vec![
+            signer(creator),
+            readonly(pda::protocol_config().0),
+            writable(treasury),
+            signer(mint),
+            writable(pda::bonding_curve(&mint).0),
+            writable(ata(&pda::bonding_curve(&mint).0, &mint)),
+            writable(pda::migration_vault(&mint).0),
+            readonly(system_program::ID),
+            readonly(TOKEN_PROGRAM_ID),
+            readonly(associated_token::ID),
+        ]
+    );
+    assert!(instruction.data.starts_with(ix_data::Create::DISCRIMINATOR));
+    assert_eq!(instruction.data.last(), Some(&2)); // preset_index 在最后
+}
+
+#[test]
+fn buy_accounts_and_data() {
+    let buyer = Pubkey::new_unique();
+    let treasury = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let builder = ix::Buy {
+        buyer,
+        treasury,
+        token_mint: mint,
+    };
+    let expected = vec![
+        signer(buyer),
+        readonly(pda::protocol_config().0),
+        writable(treasury),
+        writable(pda::bonding_curve(&mint).0),
+        writable(mint),
+        writable(ata(&pda::bonding_curve(&mint).0, &mint)),
+        writable(ata(&buyer, &mint)),
+        readonly(system_program::ID),
+        readonly(TOKEN_PROGRAM_ID),
+        readonly(associated_token::ID),
+    ];
+
+    let instruction = builder.buy(1_000, 2_000, -3);
+    assert_eq!(instruction.accounts, expected);
+    let mut data = ix_data::Buy::DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&1_000u64.to_le_bytes());
+    data.extend_from_slice(&2_000u64.to_le_bytes());
+    data.extend_from_slice(&(-3i64).to_le_bytes());
+    assert_eq!(instruction.data, data);
+
+    let instruction = builder.buy_exact_tokens_out(5, 6, 7);
+    assert_eq!(instruction.accounts, expected);
+    assert!(instruction.data.starts_with(ix_data::BuyExactTokensOut::DISCRIMINATOR));
+}
+
+#[test]
+fn sell_accounts() {
+    let seller = Pubkey::new_unique();
+    let treasury = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let builder = ix::Sell {
+        seller,
+        treasury,
+        token_mint: mint,
+    };
+    let expected = vec![
+        signer(seller),
+        readonly(pda::protocol_config().0),
+        writable(treasury),
+        writable(pda::bonding_curve(&mint).0),
+        writable(mint),
+        writable(ata(&pda::bonding_curve(&mint).0, &mint)),
+        writable(ata(&seller, &mint)),
+        readonly(system_program::ID),
+        readonly(TOKEN_PROGRAM_ID),
+        readonly(associated_token::ID),
+    ];
+    assert_eq!(builder.sell(1, 2, 3).accounts, expected);
+    assert_eq!(builder.sell_for_exact_sol(1, 2, 3).accounts, expected);
+    assert!(builder.sell_for_exact_sol(1, 2, 3).data.starts_with(ix_data::SellForExactSol::DISCRIMINATOR));
+}
+
+#[test]
+fn complete_and_migrate_accounts() {
+    let payer = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let curve = pda::bonding_curve(&mint).0;
+    let pool = pda::dex_pool(&mint).0;
+    let lp_mint = pda::lp_mint(&mint).0;
+    let instruction = ix::CompleteAndMigrate { payer, token_mint: mint }.instruction();
+    assert_eq!(
+        instruction.accounts,
+        vec![
+            signer(payer),
+            readonly(pda::protocol_config().0),
+            writable(curve),
+            writable(mint),
+            writable(ata(&curve, &mint)),
+            writable(pda::migration_vault(&mint).0),
+            writable(pool),
+            writable(pda::dex_sol_vault(&mint).0),
+            writable(ata(&pool, &mint)),
+            writable(lp_mint),
+            writable(ata(&pool, &lp_mint)),
+            readonly(system_program::ID),
+            readonly(TOKEN_PROGRAM_ID),
+            readonly(associated_token::ID),
+        ]
+    );
+}
+
+#[test]
+fn dex_liquidity_accounts() {
+    let user = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let pool = pda::dex_pool(&mint).0;
+    let lp_mint = pda::lp_mint(&mint).0;
+    let builder = ix::DexLiquidity { user, token_mint: mint };
+    let expected = vec![
+        signer(user),
+        readonly(pda::protocol_config().0),
+        readonly(mint),
+        writable(pool),
+        writable(pda::dex_sol_vault(&mint).0),
+        writable(ata(&pool, &mint)),
+        writable(lp_mint),
+        writable(ata(&pool, &lp_mint)),
+        writable(ata(&user, &mint)),
+        writable(ata(&user, &lp_mint)),
+        readonly(system_program::ID),
+        readonly(TOKEN_PROGRAM_ID),
+        readonly(associated_token::ID),
+    ];
+    assert_eq!(builder.add_liquidity(1, 2, 3, 4).accounts, expected);
+    assert_eq!(builder.remove_liquidity(1, 2, 3, 4).accounts, expected);
+}
+
+#[test]
+fn governance_accounts_follow_the_change_id() {
+    let authority = Pubkey::new_unique();
+    let builder = ix::Governance {
+        governance_authority: authority,
+    };
+    let instruction = builder.queue_config_change(4, ConfigChange::MigrationThreshold { migration_threshold_sol: 1 });
+    assert_eq!(
+        instruction.accounts,
+        vec![
+            signer(authority),
+            writable(pda::protocol_config().0),
+            writable(pda::pending_config_change(4).0),
+            readonly(system_program::ID),
+        ]
+    );
+
+    // 非国库变更：可选的新国库账户以程序 ID 占位
+    let instruction = builder.execute_config_change(4, &ConfigChange::MigrationThreshold { migration_threshold_sol: 1 });
+    assert_eq!(
+        instruction.accounts,
+        vec![
+            signer(authority),
+            writable(pda::protocol_config().0),
+            writable(pda::pending_config_change(4).0),
+            readonly(ID),
+        ]
+    );
+    let treasury = Pubkey::new_unique();
+    let instruction = builder.execute_config_change(4, &ConfigChange::Treasury { treasury });
+    assert_eq!(instruction.accounts[3], readonly(treasury));
+
+    assert_eq!(
+        builder.set_paused(1).accounts,
+        vec![readonly_signer(authority), writable(pda::protocol_config().0)]
+    );
+    let mint = Pubkey::new_unique();
+    assert_eq!(
+        builder.emergency_unwind(mint, true).accounts,
+        vec![
+            readonly_signer(authority),
+            readonly(pda::protocol_config().0),
+            writable(pda::bonding_curve(&mint).0),
+        ]
+    );
+}
+
+#[test]
+fn council_execute_appends_the_governance_accounts_without_signers() {
+    let member = Pubkey::new_unique();
+    let proposer = Pubkey::new_unique();
+    let council_authority = pda::council_authority().0;
+    let governance_instruction = ix::Council::governance().set_paused(1);
+    assert_eq!(governance_instruction.accounts[0], readonly_signer(council_authority));
+
+    let instruction = ix::Council { member }.execute(2, proposer, Some(&governance_instruction));
+    assert_eq!(
+        instruction.accounts,
+        vec![
+            readonly_signer(member),
+            writable(pda::governance_council().0),
+            writable(pda::council_proposal(2).0),
+            writable(proposer),
+            writable(council_authority),
+            readonly(ID),
+            // 剩余账户：委员会权限的签名由程序补上
+            readonly(council_authority),
+            writable(pda::protocol_config().0),
+        ]
+    );
+    assert_eq!(ix::Council { member }.execute(2, proposer, None).accounts.len(), 6);
+}
+
+#[test]
+fn fee_recipients_are_appended_as_writable_remaining_accounts() {
+    let creator = Pubkey::new_unique();
+    let fee_recipient = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let recipients = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+    let instruction = ix::ClaimCreatorFees {
+        creator,
+        creator_fee_recipient: fee_recipient,
+        token_mint: mint,
+    }
+    .distribute(&recipients);
+    assert_eq!(
+        instruction.accounts,
+        vec![
+            readonly_signer(creator),
+            writable(fee_recipient),
+            readonly(pda::protocol_config().0),
+            writable(pda::bonding_curve(&mint).0),
+            readonly(system_program::ID),
+            writable(recipients[0]),
+            writable(recipients[1]),
+        ]
+    );
+
+    let instruction = ix::CreatorFeeSplit { creator, token_mint: mint }.update(&recipients, vec![creator], vec![10_000]);
+    assert_eq!(
+        instruction.accounts,
+        vec![
+            readonly_signer(creator),
+            readonly(pda::protocol_config().0),
+            writable(pda::bonding_curve(&mint).0),
+            writable(pda::fee_split(&mint).0),
+            writable(recipients[0]),
+            writable(recipients[1]),
+        ]
+    );
+}
@@ -0,0 +1,57 @@
+use crate::{isqrt, to_u64, MathError, Result, BPS_DENOMINATOR};
+
+pub fn constant_product_out(
+    // 函数：x*y=k 恒定乘积兑换，LP费用从输入中扣除并留在池中
+    amount_in: u128,   // 输入数量
+    reserve_in: u128,  // 输入侧储备
+    reserve_out: u128, // 输出侧储备
+    lp_fee_bps: u64,   // LP费用bps
+) -> Result<(u128, u128)> {
+    // 返回：(输出数量, LP费用)
+    let lp_fee = amount_in // LP费用：输入 * bps / 10000
+        .checked_mul(lp_fee_bps as u128)
+        .ok_or(MathError::Overflow)?
+        / BPS_DENOMINATOR;
+    let amount_in_after_fee = amount_in.checked_sub(lp_fee).ok_or(MathError::Overflow)?; // 扣费后输入
+    let amount_out = reserve_out // 输出 = y * dx / (x + dx)，向下取整，偏向池子
+        .checked_mul(amount_in_after_fee)
+        .ok_or(MathError::Overflow)?
+        .checked_div(reserve_in.checked_add(amount_in_after_fee).ok_or(MathError::Overflow)?)
+        .ok_or(MathError::Overflow)?;
+    Ok((amount_out, lp_fee))
+}
+
+pub fn initial_lp_amount(sol_amount: u128, token_amount: u128) -> Result<u64> {
+    // 函数：首次注入的LP数量 = sqrt(sol * token)
+    let product = sol_amount.checked_mul(token_amount).ok_or(MathError::Overflow)?;
+    to_u64(isqrt(product))
+}
+
+pub fn proportional_amount(amount: u128, numerator: u128, denominator: u128, round_up: bool) -> Result<u128> {
+    // 函数：amount * numerator / denominator，可选择向上取整（偏向池子）
+    let product = amount.checked_mul(numerator).ok_or(MathError::Overflow)?;
+    if denominator == 0 {
+        return Err(MathError::Overflow);
+    }
+    if round_up {
+        Ok(product.div_ceil(denominator))
+    } else {
+        Ok(product / denominator)
+    }
+}
+
+pub fn migration_amounts(
+    // 函数：按曲线最终价格 price_num/price_den（lamports/最小代币单位）计算注入DEX的代币，返回 (注入代币, 销毁的储备代币)
+    sol_available: u128,  // 曲线真实SOL储备，全部注入DEX
+    reserve_tokens: u128, // 迁移储备代币
+    price_num: u128,      // 最终价格分子
+    price_den: u128,      // 最终价格分母
+) -> Result<(u128, u128)> {
+    // 全部SOL按最终价格配对的代币（向下取整，池子价格不低于曲线价格）；不增发，最多注入整个储备
+    let tokens_to_pool = sol_available
+        .checked_mul(price_den)
+        .and_then(|v| v.checked_div(price_num))
+        .ok_or(MathError::Overflow)?
+        .min(reserve_tokens);
+    Ok((tokens_to_pool, reserve_tokens - tokens_to_pool)) // 多出的储备代币销毁
+}
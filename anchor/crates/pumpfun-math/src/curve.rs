@@ -0,0 +1,331 @@
+use crate::{isqrt, to_u64, MathError, Result, PRICE_SCALE};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)] // 曲线形状
+pub enum Curve {
+    #[default]
+    ConstantProduct, // 虚拟储备恒定乘积 x * y = k
+    Linear {
+        base_price: u64, // 起始价格（lamports/最小代币单位 * PRICE_SCALE）
+        slope: u64,      // 每售出一个最小代币单位的价格增量（同上单位）
+    },
+    Exponential {
+        base_price: u64,        // 起始价格（lamports/最小代币单位 * PRICE_SCALE）
+        doubling_interval: u64, // 每售出多少最小代币单位价格翻倍（区间内线性插值）
+    },
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)] // 定价所需的曲线状态（与链上 BondingCurve 对应字段一致）
+pub struct CurveState {
+    pub curve: Curve,                        // 曲线形状
+    pub virtual_sol_reserves: u64,           // 虚拟SOL储备
+    pub virtual_token_reserves: u64,         // 虚拟代币储备
+    pub initial_virtual_token_reserves: u64, // 创建时的虚拟代币储备
+    pub real_sol_reserves: u64,              // 真实SOL储备
+    pub real_token_reserves: u64,            // 曲线剩余可售代币
+}
+
+impl CurveState {
+    // 实现：曲线状态
+    pub fn tokens_sold(&self) -> Result<u64> {
+        // 函数：曲线已售出的代币（线性/指数曲线的价格自变量）
+        self.initial_virtual_token_reserves
+            .checked_sub(self.virtual_token_reserves)
+            .ok_or(MathError::ReserveUnderflow)
+    }
+
+    pub fn buy_output(&self, sol_in: u64) -> Result<u64> {
+        // 函数：计算购买输出，按曲线类型分派，均向下取整（偏向曲线）
+        let sol_in = sol_in as u128;
+        let sold = self.tokens_sold()? as u128;
+        let tokens_out = match self.curve {
+            Curve::ConstantProduct => constant_product_buy(
+                self.virtual_sol_reserves as u128,
+                self.virtual_token_reserves as u128,
+                sol_in,
+            )?,
+            Curve::Linear { base_price, slope } => linear_buy(base_price as u128, slope as u128, sold, sol_in)?,
+            Curve::Exponential {
+                base_price,
+                doubling_interval,
+            } => exponential_buy(base_price as u128, doubling_interval as u128, sold, sol_in)?,
+        };
+        to_u64(tokens_out)
+    }
+
+    pub fn sell_output(&self, tokens_in: u64) -> Result<u64> {
+        // 函数：计算出售输出，按曲线类型分派，均向下取整（偏向曲线）；同一区间的买入向上取整，因此往返交易无利可图
+        let tokens_in = tokens_in as u128;
+        let sold = self.tokens_sold()? as u128;
+        let sol_out = match self.curve {
+            Curve::ConstantProduct => constant_product_sell(
+                self.virtual_sol_reserves as u128,
+                self.virtual_token_reserves as u128,
+                tokens_in,
+            )?,
+            Curve::Linear { base_price, slope } => {
+                let start = sold.checked_sub(tokens_in).ok_or(MathError::ReserveUnderflow)?; // 卖出区间 [s - n, s)
+                linear_cost_numerator(base_price as u128, slope as u128, start, tokens_in)? / (2 * PRICE_SCALE)
+            }
+            Curve::Exponential {
+                base_price,
+                doubling_interval,
+            } => {
+                let start = sold.checked_sub(tokens_in).ok_or(MathError::ReserveUnderflow)?; // 卖出区间 [s - n, s)
+                exponential_cost_numerator(base_price as u128, doubling_interval as u128, start, tokens_in)?
+                    .checked_div(2 * PRICE_SCALE * doubling_interval as u128)
+                    .ok_or(MathError::Overflow)? // 翻倍区间为零时除以零
+            }
+        };
+        to_u64(sol_out)
+    }
+
+    pub fn buy_cost(&self, tokens_out: u64) -> Result<u64> {
+        // 函数：买入精确数量代币所需的SOL（不含费用），向上取整（偏向曲线）
+        let tokens_out = tokens_out as u128;
+        let sold = self.tokens_sold()? as u128;
+        let cost = match self.curve {
+            Curve::ConstantProduct => {
+                // 新x = ceil(k / (y - n))，所需SOL = 新x - x
+                let x = self.virtual_sol_reserves as u128;
+                let y = self.virtual_token_reserves as u128;
+                let new_y = y
+                    .checked_sub(tokens_out)
+                    .filter(|v| *v > 0)
+                    .ok_or(MathError::ReserveUnderflow)?;
+                let k = x.checked_mul(y).ok_or(MathError::Overflow)?;
+                k.div_ceil(new_y) - x
+            }
+            Curve::Linear { base_price, slope } => {
+                linear_cost_numerator(base_price as u128, slope as u128, sold, tokens_out)?.div_ceil(2 * PRICE_SCALE)
+            }
+            Curve::Exponential {
+                base_price,
+                doubling_interval,
+            } => {
+                let denominator = Some(2 * PRICE_SCALE * doubling_interval as u128)
+                    .filter(|v| *v > 0)
+                    .ok_or(MathError::Overflow)?; // 翻倍区间为零时除以零
+                exponential_cost_numerator(base_price as u128, doubling_interval as u128, sold, tokens_out)?
+                    .div_ceil(denominator)
+            }
+        };
+        to_u64(cost)
+    }
+
+    pub fn sell_input(&self, sol_out: u64) -> Result<u64> {
+        // 函数：卖出得到至少 sol_out（不含费用）所需的最少代币，向上取整（偏向曲线）
+        if let Curve::ConstantProduct = self.curve {
+            // y + t >= ceil(k / (x - sol))
+            let x = self.virtual_sol_reserves as u128;
+            let y = self.virtual_token_reserves as u128;
+            let new_x = x
+                .checked_sub(sol_out as u128)
+                .filter(|v| *v > 0)
+                .ok_or(MathError::ReserveUnderflow)?;
+            let new_y = x.checked_mul(y).ok_or(MathError::Overflow)?.div_ceil(new_x);
+            return to_u64(new_y - y);
+        }
+        // 线性/指数曲线：卖出收益随卖出数量单调递增，二分查找最小数量
+        let (mut low, mut high) = (0u64, self.tokens_sold()?);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.sell_output(mid)? >= sol_out {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+        Ok(low)
+    }
+
+    pub fn spot_price(&self) -> Result<(u128, u128)> {
+        // 函数：当前边际价格（lamports/最小代币单位），返回 (分子, 分母)
+        let sold = self.tokens_sold()? as u128;
+        match self.curve {
+            Curve::ConstantProduct => Ok((self.virtual_sol_reserves as u128, self.virtual_token_reserves as u128)),
+            Curve::Linear { base_price, slope } => Ok((
+                (slope as u128)
+                    .checked_mul(sold)
+                    .and_then(|v| v.checked_add(base_price as u128))
+                    .ok_or(MathError::Overflow)?,
+                PRICE_SCALE,
+            )),
+            Curve::Exponential {
+                base_price,
+                doubling_interval,
+            } => {
+                let interval = doubling_interval as u128;
+                let segment = sold.checked_div(interval).ok_or(MathError::Overflow)?; // 翻倍区间为零时除以零
+                let offset = sold.checked_rem(interval).ok_or(MathError::Overflow)?;
+                // 区间起始价格 * (1 + 区间内偏移 / 区间长度)
+                let price = segment_price(base_price as u128, segment)?
+                    .checked_mul(interval + offset)
+                    .ok_or(MathError::Overflow)?;
+                Ok((price, interval * PRICE_SCALE))
+            }
+        }
+    }
+
+    pub fn redeem_output(&self, tokens_in: u64) -> Result<u64> {
+        // 函数：紧急清退时按比例兑付：真实SOL储备 * 代币 / 已售出代币，向下取整（偏向曲线）
+        let sold = self.tokens_sold()?;
+        if tokens_in > sold {
+            return Err(MathError::ReserveUnderflow); // 流通量之外的代币
+        }
+        if sold == 0 {
+            return Ok(0);
+        }
+        to_u64((self.real_sol_reserves as u128) * (tokens_in as u128) / (sold as u128))
+    }
+
+    pub fn apply_buy(&mut self, sol_in: u64, tokens_out: u64) -> Result<()> {
+        // 函数：更新购买状态
+        self.real_token_reserves = self
+            .real_token_reserves
+            .checked_sub(tokens_out)
+            .ok_or(MathError::ReserveUnderflow)?; // 更新可售代币
+        self.real_sol_reserves = self.real_sol_reserves.checked_add(sol_in).ok_or(MathError::Overflow)?; // 更新真实SOL
+        self.virtual_sol_reserves = self.virtual_sol_reserves.checked_add(sol_in).ok_or(MathError::Overflow)?; // 更新虚拟SOL
+        self.virtual_token_reserves = self
+            .virtual_token_reserves
+            .checked_sub(tokens_out)
+            .ok_or(MathError::ReserveUnderflow)?; // 更新虚拟代币
+        Ok(())
+    }
+
+    pub fn apply_sell(&mut self, tokens_in: u64, sol_out: u64) -> Result<()> {
+        // 函数：更新出售状态
+        self.real_token_reserves = self.real_token_reserves.checked_add(tokens_in).ok_or(MathError::Overflow)?; // 更新可售代币
+        self.real_sol_reserves = self
+            .real_sol_reserves
+            .checked_sub(sol_out)
+            .ok_or(MathError::ReserveUnderflow)?;
+        self.virtual_sol_reserves = self
+            .virtual_sol_reserves
+            .checked_sub(sol_out)
+            .ok_or(MathError::ReserveUnderflow)?;
+        self.virtual_token_reserves = self
+            .virtual_token_reserves
+            .checked_add(tokens_in)
+            .ok_or(MathError::Overflow)?;
+        Ok(())
+    }
+}
+
+pub fn constant_product_buy(virtual_sol: u128, virtual_token: u128, sol_in: u128) -> Result<u128> {
+    // 函数：恒定乘积曲线买入，输出 = y - ceil(k / (x + 输入))，向下取整，偏向曲线
+    let k = virtual_sol.checked_mul(virtual_token).ok_or(MathError::Overflow)?; // k = x * y
+    let new_x = virtual_sol.checked_add(sol_in).ok_or(MathError::Overflow)?; // 新x = x + 输入
+    if new_x == 0 {
+        return Err(MathError::Overflow); // 除以零
+    }
+    let new_y = k.div_ceil(new_x); // 新y = ceil(k / 新x)，保证 k 不减
+    virtual_token.checked_sub(new_y).ok_or(MathError::ReserveUnderflow) // 输出 = y - 新y
+}
+
+pub fn constant_product_sell(virtual_sol: u128, virtual_token: u128, tokens_in: u128) -> Result<u128> {
+    // 函数：恒定乘积曲线卖出，输出 = x - ceil(k / (y + 输入))，偏向曲线
+    if virtual_sol == 0 || virtual_token == 0 {
+        // 如果储备为0，返回0
+        return Ok(0);
+    }
+    let k = virtual_sol.checked_mul(virtual_token).ok_or(MathError::Overflow)?;
+    let new_y = virtual_token.checked_add(tokens_in).ok_or(MathError::Overflow)?;
+    let new_x = k.div_ceil(new_y);
+    Ok(virtual_sol.saturating_sub(new_x)) // 输出 = x - 新x
+}
+
+pub fn linear_cost_numerator(base_price: u128, slope: u128, tokens_sold: u128, amount: u128) -> Result<u128> {
+    // 函数：线性曲线 price(s) = base + slope * s 在 [s, s + amount) 上的积分，乘以 2 * PRICE_SCALE 后为精确整数
+    let twice_base = base_price
+        .checked_mul(2)
+        .and_then(|v| v.checked_mul(amount))
+        .ok_or(MathError::Overflow)?; // 2 * base * n
+    let span = tokens_sold
+        .checked_mul(2)
+        .and_then(|v| v.checked_add(amount))
+        .ok_or(MathError::Overflow)?; // 2s + n
+    slope
+        .checked_mul(amount)
+        .and_then(|v| v.checked_mul(span))
+        .and_then(|v| v.checked_add(twice_base))
+        .ok_or(MathError::Overflow) // + slope * n * (2s + n)
+}
+
+pub fn linear_buy(base_price: u128, slope: u128, tokens_sold: u128, sol_in: u128) -> Result<u128> {
+    // 函数：线性曲线买入，求满足 cost(n) <= 输入 的最大 n
+    let budget = sol_in.checked_mul(2 * PRICE_SCALE).ok_or(MathError::Overflow)?; // 预算（与代价分子同单位）
+    if slope == 0 {
+        // 价格恒定：n = 预算 / (2 * base)
+        let twice_base = base_price.checked_mul(2).ok_or(MathError::Overflow)?;
+        return budget.checked_div(twice_base).ok_or(MathError::Overflow);
+    }
+    // slope * n^2 + 2 * (base + slope * s) * n <= 预算，取整数根
+    let b = slope
+        .checked_mul(tokens_sold)
+        .and_then(|v| v.checked_add(base_price))
+        .ok_or(MathError::Overflow)?; // b = base + slope * s（当前价格）
+    let discriminant = b
+        .checked_mul(b)
+        .and_then(|v| v.checked_add(slope.checked_mul(budget)?))
+        .ok_or(MathError::Overflow)?; // b^2 + slope * 预算
+    Ok((isqrt(discriminant) - b) / slope) // n = floor((sqrt(判别式) - b) / slope)
+}
+
+pub fn segment_price(base_price: u128, segment: u128) -> Result<u128> {
+    // 函数：指数曲线第 segment 个翻倍区间的起始价格 = base * 2^segment
+    u32::try_from(segment)
+        .ok()
+        .and_then(|shift| 1u128.checked_shl(shift))
+        .and_then(|multiplier| base_price.checked_mul(multiplier))
+        .ok_or(MathError::Overflow)
+}
+
+pub fn exponential_cost_numerator(base_price: u128, doubling_interval: u128, tokens_sold: u128, amount: u128) -> Result<u128> {
+    // 函数：指数曲线（每 doubling_interval 个代币价格翻倍，区间内线性插值）在 [s, s + amount) 上的积分，
+    // 乘以 2 * doubling_interval * PRICE_SCALE 后为精确整数
+    let mut numerator = 0u128;
+    let mut position = tokens_sold; // 当前位置
+    let end = tokens_sold.checked_add(amount).ok_or(MathError::Overflow)?; // 结束位置
+    while position < end {
+        let offset = position.checked_rem(doubling_interval).ok_or(MathError::Overflow)?; // 区间内偏移（区间为零时除以零）
+        let segment_end = (doubling_interval - offset).min(end - position) + offset; // 本区间内的结束偏移
+        let price = segment_price(base_price, position / doubling_interval)?; // 区间起始价格
+        numerator = price
+            .checked_mul(segment_end - offset)
+            .and_then(|v| v.checked_mul(2 * doubling_interval + offset + segment_end))
+            .and_then(|v| v.checked_add(numerator))
+            .ok_or(MathError::Overflow)?; // P_k * (b - a) * (2D + a + b)
+        position += segment_end - offset;
+    }
+    Ok(numerator)
+}
+
+pub fn exponential_buy(base_price: u128, doubling_interval: u128, tokens_sold: u128, sol_in: u128) -> Result<u128> {
+    // 函数：指数曲线买入，逐区间消耗预算，求满足 cost(n) <= 输入 的最大 n
+    let mut budget = sol_in
+        .checked_mul(2 * PRICE_SCALE)
+        .and_then(|v| v.checked_mul(doubling_interval))
+        .ok_or(MathError::Overflow)?; // 预算（与代价分子同单位）
+    let mut position = tokens_sold;
+    loop {
+        let offset = position.checked_rem(doubling_interval).ok_or(MathError::Overflow)?; // 区间内偏移（区间为零时除以零）
+        let rest_of_segment = doubling_interval - offset; // 本区间剩余代币
+        let segment_cost = exponential_cost_numerator(base_price, doubling_interval, position, rest_of_segment)?;
+        if segment_cost <= budget {
+            // 预算足够买完本区间
+            budget -= segment_cost;
+            position += rest_of_segment;
+            continue;
+        }
+        // 区间内：m^2 + (2D + 2a) * m <= 预算 / P_k，取整数根
+        let c = budget / segment_price(base_price, position / doubling_interval)?; // m 为整数，向下取整不改变解集
+        let b = 2 * (doubling_interval + offset);
+        let discriminant = b
+            .checked_mul(b)
+            .and_then(|v| v.checked_add(c.checked_mul(4)?))
+            .ok_or(MathError::Overflow)?;
+        let m = (isqrt(discriminant) - b) / 2;
+        return Ok(position + m - tokens_sold);
+    }
+}
@@ -0,0 +1,43 @@
+use crate::{MathError, Result, BPS_DENOMINATOR};
+
+pub fn calculate_fees(
+    // 函数：计算费用
+    amount: u128,                    // 输入金额
+    total_bps: u64,                  // 总基点（bps）
+    creator_share_of_total_bps: u64, // 创作者在总bps中的份额
+) -> Result<(u128, u128)> {
+    // 返回：(创作者费用, 国库费用)
+    if total_bps == 0 || amount == 0 {
+        // 如果bps为0或金额为0，返回0
+        return Ok((0, 0));
+    }
+    let total_fee = amount // 计算总费用：金额 * bps / 10000
+        .checked_mul(total_bps as u128)
+        .ok_or(MathError::Overflow)?
+        / BPS_DENOMINATOR;
+    if total_fee == 0 {
+        // 如果总费用为0，返回(0, 0)
+        return Ok((0, 0));
+    }
+    let creator_fee = total_fee // 计算创作者费用：总费用 * 份额 / 总bps
+        .checked_mul(creator_share_of_total_bps as u128)
+        .ok_or(MathError::Overflow)?
+        / total_bps as u128;
+    let treasury_fee = total_fee.checked_sub(creator_fee).ok_or(MathError::Overflow)?; // 计算国库费用：总费用 - 创作者费用
+    Ok((creator_fee, treasury_fee)) // 返回费用对
+}
+
+pub fn gross_amount_for_net(net_amount: u128, total_bps: u64) -> Result<u128> {
+    // 函数：反推扣费前金额，使 gross - fee(gross) 至少为 net（向上取整，偏向曲线）
+    if total_bps == 0 {
+        return Ok(net_amount);
+    }
+    let denominator = BPS_DENOMINATOR.saturating_sub(total_bps as u128); // 10000 - bps
+    if denominator == 0 {
+        return Err(MathError::Overflow); // 费用为 100% 时不存在满足条件的金额
+    }
+    Ok(net_amount
+        .checked_mul(BPS_DENOMINATOR)
+        .ok_or(MathError::Overflow)?
+        .div_ceil(denominator))
+}
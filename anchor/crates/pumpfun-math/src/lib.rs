@@ -0,0 +1,52 @@
+//! pumpfun 的整数数学：绑定曲线、交易费用、DEX 恒定乘积和迁移定价。
+//!
+//! 链上程序直接调用这里的函数，链下服务用同一份实现报价，两边结果逐 lamport 一致。
+//! 不依赖 `std` 和 Anchor，所有检查运算失败都返回 [`MathError`] 而不是 panic。
+#![no_std]
+
+pub mod amm; // DEX 恒定乘积和 LP 数学
+pub mod curve; // 绑定曲线定价
+pub mod fees; // 交易费用
+pub mod quote; // 买入、卖出和迁移报价
+
+pub use curve::{Curve, CurveState};
+pub use quote::{BuyQuote, FeeParams, MigrationQuote, SellQuote};
+
+pub const PRICE_SCALE: u128 = 1_000_000_000; // 线性/指数曲线的价格精度：价格以 lamports/最小代币单位 * 10^9 表示
+pub const BPS_DENOMINATOR: u128 = 10_000; // 基点分母
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)] // 数学错误
+pub enum MathError {
+    Overflow,         // 算术溢出（或除以零）
+    ReserveUnderflow, // 储备被扣到零以下
+}
+
+impl core::fmt::Display for MathError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            MathError::Overflow => f.write_str("arithmetic overflow in curve or fee math"),
+            MathError::ReserveUnderflow => f.write_str("the trade would take a reserve below zero"),
+        }
+    }
+}
+
+pub type Result<T> = core::result::Result<T, MathError>; // 数学结果
+
+pub fn isqrt(value: u128) -> u128 {
+    // 函数：整数平方根（向下取整，牛顿迭代）
+    if value < 2 {
+        return value;
+    }
+    let mut x = value;
+    let mut y = x.div_ceil(2);
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+    x
+}
+
+pub(crate) fn to_u64(value: u128) -> Result<u64> {
+    // 函数：u128 收窄为 u64，超出范围视为溢出
+    u64::try_from(value).map_err(|_| MathError::Overflow)
+}
@@ -0,0 +1,155 @@
+use crate::amm::{initial_lp_amount, migration_amounts};
+use crate::fees::{calculate_fees, gross_amount_for_net};
+use crate::{to_u64, CurveState, MathError, Result};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)] // 交易费用参数（与链上 ProtocolConfig 对应字段一致）
+pub struct FeeParams {
+    pub total_trade_fee_bps: u64,   // 总交易费用bps
+    pub creator_fee_bps_share: u64, // 创作者在总bps中的份额
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)] // 买入报价
+pub struct BuyQuote {
+    pub sol_in: u64,       // 实际扣款（含费用）
+    pub sol_to_curve: u64, // 进入曲线储备的SOL（不含费用）
+    pub creator_fee: u64,  // 创作者费用
+    pub treasury_fee: u64, // 国库费用
+    pub tokens_out: u64,   // 输出代币
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)] // 卖出报价
+pub struct SellQuote {
+    pub tokens_in: u64,      // 卖出代币
+    pub sol_from_curve: u64, // 曲线储备付出的SOL（含费用）
+    pub creator_fee: u64,    // 创作者费用
+    pub treasury_fee: u64,   // 国库费用
+    pub sol_out: u64,        // 卖家实际收到的SOL
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)] // 迁移报价
+pub struct MigrationQuote {
+    pub sol_to_pool: u64,           // 注入DEX的SOL（全部真实SOL储备）
+    pub tokens_to_pool: u64,        // 注入DEX的代币
+    pub reserve_tokens_burned: u64, // 多余储备代币销毁
+    pub lp_tokens: u64,             // 铸造并锁定的初始LP
+}
+
+fn fees_u64(amount: u128, fees: &FeeParams) -> Result<(u64, u64)> {
+    // 函数：计算费用并收窄为 u64，返回 (创作者费用, 国库费用)
+    let (creator_fee, treasury_fee) = calculate_fees(amount, fees.total_trade_fee_bps, fees.creator_fee_bps_share)?;
+    Ok((to_u64(creator_fee)?, to_u64(treasury_fee)?))
+}
+
+pub fn quote_buy(state: &CurveState, fees: &FeeParams, migration_threshold_sol: u64, total_sol_in: u64) -> Result<BuyQuote> {
+    // 函数：用 total_sol_in（含费用）买入；曲线部分超过迁移阈值时截断到恰好填满阈值，并按截断后的金额重新计算费用
+    let remaining_to_threshold = migration_threshold_sol.saturating_sub(state.real_sol_reserves); // 距离迁移阈值还差的SOL
+    let (mut creator_fee, mut treasury_fee) = fees_u64(total_sol_in as u128, fees)?;
+    let mut sol_to_curve = total_sol_in // 曲线金额：总输入 - 费用
+        .checked_sub(treasury_fee)
+        .and_then(|v| v.checked_sub(creator_fee))
+        .ok_or(MathError::Overflow)?;
+    if sol_to_curve > remaining_to_threshold {
+        sol_to_curve = remaining_to_threshold;
+        let gross_sol_in = gross_amount_for_net(sol_to_curve as u128, fees.total_trade_fee_bps)?.min(total_sol_in as u128);
+        (creator_fee, treasury_fee) = fees_u64(gross_sol_in, fees)?;
+    }
+    let tokens_out = state.buy_output(sol_to_curve)?; // 输出代币
+    let sol_in = sol_to_curve
+        .checked_add(creator_fee)
+        .and_then(|v| v.checked_add(treasury_fee))
+        .ok_or(MathError::Overflow)?; // 实际扣款
+    Ok(BuyQuote {
+        sol_in,
+        sol_to_curve,
+        creator_fee,
+        treasury_fee,
+        tokens_out,
+    })
+}
+
+pub fn quote_buy_exact_tokens_out(state: &CurveState, fees: &FeeParams, tokens_out: u64) -> Result<BuyQuote> {
+    // 函数：买入精确数量代币；曲线成本和含费总额均向上取整，取整余量留在曲线
+    let curve_cost = state.buy_cost(tokens_out)?; // 曲线所需SOL
+    let gross_sol_in = gross_amount_for_net(curve_cost as u128, fees.total_trade_fee_bps)?; // 含费用总额
+    let (creator_fee, treasury_fee) = fees_u64(gross_sol_in, fees)?;
+    let sol_in = to_u64(gross_sol_in)?;
+    let sol_to_curve = sol_in
+        .checked_sub(creator_fee)
+        .and_then(|v| v.checked_sub(treasury_fee))
+        .ok_or(MathError::Overflow)?; // 不少于曲线所需
+    Ok(BuyQuote {
+        sol_in,
+        sol_to_curve,
+        creator_fee,
+        treasury_fee,
+        tokens_out,
+    })
+}
+
+pub fn quote_sell(state: &CurveState, fees: &FeeParams, tokens_in: u64) -> Result<SellQuote> {
+    // 函数：卖出 tokens_in，费用从曲线付出的SOL中扣除
+    let sol_from_curve = state.sell_output(tokens_in)?; // 曲线付出的SOL
+    let (creator_fee, treasury_fee) = fees_u64(sol_from_curve as u128, fees)?;
+    let sol_out = sol_from_curve
+        .checked_sub(creator_fee)
+        .and_then(|v| v.checked_sub(treasury_fee))
+        .ok_or(MathError::Overflow)?; // 净输出
+    Ok(SellQuote {
+        tokens_in,
+        sol_from_curve,
+        creator_fee,
+        treasury_fee,
+        sol_out,
+    })
+}
+
+pub fn quote_sell_for_exact_sol(state: &CurveState, fees: &FeeParams, sol_out: u64) -> Result<SellQuote> {
+    // 函数：卖出代币换取精确的 sol_out（扣费后）；所需代币向上取整，取整余量留在曲线
+    let gross_upper = gross_amount_for_net(sol_out as u128, fees.total_trade_fee_bps)?; // 含费用总额
+    let (creator_fee, treasury_fee) = fees_u64(gross_upper, fees)?;
+    let sol_from_curve = sol_out
+        .checked_add(creator_fee)
+        .and_then(|v| v.checked_add(treasury_fee))
+        .ok_or(MathError::Overflow)?; // 曲线需付出的SOL
+    let tokens_in = state.sell_input(sol_from_curve)?; // 所需代币
+    Ok(SellQuote {
+        tokens_in,
+        sol_from_curve,
+        creator_fee,
+        treasury_fee,
+        sol_out,
+    })
+}
+
+pub fn quote_migration(state: &CurveState, reserve_tokens: u64) -> Result<MigrationQuote> {
+    // 函数：以曲线最终价格把全部真实SOL储备注入DEX，代币侧用迁移储备配对，保证价格连续
+    let (price_num, price_den) = state.spot_price()?;
+    let (tokens_to_pool, reserve_tokens_burned) = migration_amounts(
+        state.real_sol_reserves as u128,
+        reserve_tokens as u128,
+        price_num,
+        price_den,
+    )?;
+    let tokens_to_pool = to_u64(tokens_to_pool)?;
+    Ok(MigrationQuote {
+        sol_to_pool: state.real_sol_reserves,
+        tokens_to_pool,
+        reserve_tokens_burned: to_u64(reserve_tokens_burned)?,
+        lp_tokens: initial_lp_amount(state.real_sol_reserves as u128, tokens_to_pool as u128)?,
+    })
+}
+
+pub fn migration_reserve_needed(state: &CurveState, migration_threshold_sol: u64) -> Result<u64> {
+    // 函数：从全新曲线买到完成（达到迁移阈值或售罄可售代币），按最终价格配对全部真实SOL所需的迁移储备代币
+    let mut completed = *state;
+    // 售罄全部可售代币的成本；算不出（溢出或超出虚拟储备）说明在阈值前不可能售罄
+    match state.buy_cost(state.real_token_reserves) {
+        Ok(sell_out_cost) if sell_out_cost <= migration_threshold_sol => {
+            completed.apply_buy(sell_out_cost, state.real_token_reserves)?
+        }
+        _ => completed.apply_buy(migration_threshold_sol, state.buy_output(migration_threshold_sol)?)?,
+    }
+    let (price_num, price_den) = completed.spot_price()?;
+    let (tokens_to_pool, _) = migration_amounts(completed.real_sol_reserves as u128, u128::MAX, price_num, price_den)?;
+    to_u64(tokens_to_pool)
+}
@@ -0,0 +1,76 @@
+//! 每个 `MathError` 至少一个用例：越界的输入返回错误而不是 panic，调用方据此区分算术失败和滑点失败。
+
+use pumpfun_math::fees::{calculate_fees, gross_amount_for_net};
+use pumpfun_math::{Curve, CurveState, MathError};
+
+fn state(curve: Curve, sold: u64, real_sol: u64) -> CurveState {
+    // 函数：已售出 sold 个代币、持有 real_sol 真实SOL的曲线
+    CurveState {
+        curve,
+        virtual_sol_reserves: 1_000_000_000 + real_sol,
+        virtual_token_reserves: 1_000_000_000 - sold,
+        initial_virtual_token_reserves: 1_000_000_000,
+        real_sol_reserves: real_sol,
+        real_token_reserves: 800_000_000 - sold,
+    }
+}
+
+fn linear() -> Curve {
+    Curve::Linear {
+        base_price: 1_000_000_000,
+        slope: 1_000,
+    }
+}
+
+#[test]
+fn overflow() {
+    assert_eq!(calculate_fees(u128::MAX, 30, 10), Err(MathError::Overflow));
+    assert_eq!(gross_amount_for_net(u128::MAX, 30), Err(MathError::Overflow));
+
+    let mut full = state(Curve::ConstantProduct, 0, u64::MAX - 1_000_000_000);
+    assert_eq!(full.apply_buy(1, 0), Err(MathError::Overflow)); // 虚拟SOL溢出
+    let mut full = state(Curve::ConstantProduct, 0, 0);
+    full.real_token_reserves = u64::MAX;
+    assert_eq!(full.apply_sell(1, 0), Err(MathError::Overflow));
+
+    // 指数曲线翻倍段数过大，价格超出 u128
+    let exponential = Curve::Exponential {
+        base_price: 1_000_000_000,
+        doubling_interval: 1,
+    };
+    assert_eq!(state(exponential, 200, 0).buy_cost(1), Err(MathError::Overflow));
+
+    // 翻倍区间为零（链下调用不经过预设检查）：除以零返回错误
+    let degenerate = state(
+        Curve::Exponential {
+            base_price: 1_000_000_000,
+            doubling_interval: 0,
+        },
+        10,
+        0,
+    );
+    assert_eq!(degenerate.spot_price(), Err(MathError::Overflow));
+    assert_eq!(degenerate.buy_output(1_000), Err(MathError::Overflow));
+    assert_eq!(degenerate.buy_cost(0), Err(MathError::Overflow));
+    assert_eq!(degenerate.buy_cost(5), Err(MathError::Overflow));
+    assert_eq!(degenerate.sell_output(0), Err(MathError::Overflow));
+    assert_eq!(degenerate.sell_output(5), Err(MathError::Overflow));
+}
+
+#[test]
+fn reserve_underflow() {
+    let mut inverted = state(Curve::ConstantProduct, 0, 0);
+    inverted.virtual_token_reserves += 1; // 虚拟代币储备高于初始值
+    assert_eq!(inverted.tokens_sold(), Err(MathError::ReserveUnderflow));
+
+    assert_eq!(state(linear(), 10, 0).sell_output(11), Err(MathError::ReserveUnderflow)); // 卖出超过已售
+    assert_eq!(state(linear(), 10, 100).redeem_output(11), Err(MathError::ReserveUnderflow));
+    assert_eq!(
+        state(Curve::ConstantProduct, 0, 0).buy_cost(1_000_000_000),
+        Err(MathError::ReserveUnderflow)
+    ); // 买空虚拟代币储备
+
+    let mut curve = state(Curve::ConstantProduct, 0, 100);
+    assert_eq!(curve.apply_buy(0, 800_000_001), Err(MathError::ReserveUnderflow));
+    assert_eq!(curve.apply_sell(0, 101), Err(MathError::ReserveUnderflow));
+}
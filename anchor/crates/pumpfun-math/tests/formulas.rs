@@ -0,0 +1,176 @@
+//! 曲线、费用和迁移公式的精确值测试：期望值按积分公式手算，包括取整方向和指数曲线跨越翻倍区间。
+
+use pumpfun_math::amm::migration_amounts;
+use pumpfun_math::curve::{constant_product_buy, constant_product_sell};
+use pumpfun_math::fees::{calculate_fees, gross_amount_for_net};
+use pumpfun_math::quote::migration_reserve_needed;
+use pumpfun_math::{Curve, CurveState, MathError};
+
+const SUPPLY: u64 = 1_000_000_000; // 初始虚拟代币储备，远大于测试区间
+
+fn state_at(curve: Curve, sold: u64) -> CurveState {
+    // 函数：已售出 sold 个代币的线性/指数曲线
+    CurveState {
+        curve,
+        virtual_sol_reserves: 0,
+        virtual_token_reserves: SUPPLY - sold,
+        initial_virtual_token_reserves: SUPPLY,
+        real_sol_reserves: 0,
+        real_token_reserves: SUPPLY - sold,
+    }
+}
+
+fn linear() -> Curve {
+    // 函数：起始价格 1 lamport/单位，每售出一个单位涨 0.001 lamport
+    Curve::Linear {
+        base_price: 1_000_000_000,
+        slope: 1_000_000,
+    }
+}
+
+fn exponential() -> Curve {
+    // 函数：起始价格 1 lamport/单位，每售出 1000 个单位价格翻倍
+    Curve::Exponential {
+        base_price: 1_000_000_000,
+        doubling_interval: 1_000,
+    }
+}
+
+#[test]
+fn linear_buy_cost_is_the_area_under_the_price_line_rounded_up() {
+    // [0, 1000) 的面积 = 1000 + 1000² * 0.001 / 2 = 1500，整除
+    assert_eq!(state_at(linear(), 0).buy_cost(1_000), Ok(1_500));
+    // [0, 1001) 的面积 = 1502.0005，向上取整
+    assert_eq!(state_at(linear(), 0).buy_cost(1_001), Ok(1_503));
+    // 价格 3 + 0.0007s，[250, 290) 的面积 = 127.56
+    let curve = Curve::Linear {
+        base_price: 3_000_000_000,
+        slope: 700_000,
+    };
+    assert_eq!(state_at(curve, 250).buy_cost(40), Ok(128));
+}
+
+#[test]
+fn linear_sell_output_is_the_same_area_rounded_down() {
+    assert_eq!(state_at(linear(), 1_000).sell_output(1_000), Ok(1_500));
+    assert_eq!(state_at(linear(), 1_001).sell_output(1_001), Ok(1_502));
+    let curve = Curve::Linear {
+        base_price: 3_000_000_000,
+        slope: 700_000,
+    };
+    assert_eq!(state_at(curve, 290).sell_output(40), Ok(127));
+}
+
+#[test]
+fn linear_buy_output_is_the_largest_amount_the_budget_covers() {
+    let state = state_at(linear(), 0);
+    assert_eq!(state.buy_output(1_500), Ok(1_000));
+    assert_eq!(state.buy_output(1_502), Ok(1_000)); // 第 1001 个单位要 1502.0005
+    assert_eq!(state.buy_output(1_503), Ok(1_001));
+}
+
+#[test]
+fn linear_curve_with_zero_slope_is_a_fixed_price() {
+    let curve = Curve::Linear {
+        base_price: 2_500_000_000,
+        slope: 0,
+    };
+    assert_eq!(state_at(curve, 0).buy_output(10), Ok(4));
+    assert_eq!(state_at(curve, 0).buy_cost(4), Ok(10));
+    assert_eq!(state_at(curve, 0).buy_cost(5), Ok(13)); // 12.5 向上取整
+    assert_eq!(state_at(curve, 5).sell_output(5), Ok(12));
+}
+
+#[test]
+fn exponential_buy_cost_sums_each_doubling_segment() {
+    let state = state_at(exponential(), 0);
+    // 第 0 段内价格 1 + a/1000：[0, 500) = 500 + 125
+    assert_eq!(state.buy_cost(500), Ok(625));
+    // 第 0 段 1500，第 1 段价格翻倍 2 * 1500
+    assert_eq!(state.buy_cost(2_000), Ok(4_500));
+    // 第 2 段第一个单位 4 * (1 + 1/2000) = 4.002，向上取整
+    assert_eq!(state.buy_cost(2_001), Ok(4_505));
+}
+
+#[test]
+fn exponential_sell_output_rounds_down_across_segments() {
+    // [1500, 2000) 位于第 1 段：2 * (500 + (1000² - 500²) / 2000) = 1750
+    assert_eq!(state_at(exponential(), 2_000).sell_output(500), Ok(1_750));
+    assert_eq!(state_at(exponential(), 2_000).sell_output(2_000), Ok(4_500));
+    // [0, 2001) = 4504.002
+    assert_eq!(state_at(exponential(), 2_001).sell_output(2_001), Ok(4_504));
+}
+
+#[test]
+fn exponential_buy_output_spends_the_budget_segment_by_segment() {
+    let state = state_at(exponential(), 0);
+    assert_eq!(state.buy_output(625), Ok(500));
+    assert_eq!(state.buy_output(2_750), Ok(1_500)); // 1500 + 2 * 625
+    assert_eq!(state.buy_output(2_749), Ok(1_499)); // 1499 个单位要 2747.001
+    assert_eq!(state.buy_output(4_500), Ok(2_000));
+    assert_eq!(state.buy_output(4_504), Ok(2_000)); // 第 2001 个单位要 4504.002
+    // 从第 1 段中间开始：[1500, 2000) 花 1750
+    assert_eq!(state_at(exponential(), 1_500).buy_output(1_750), Ok(500));
+}
+
+#[test]
+fn constant_product_rounds_the_new_reserve_up() {
+    // k = 30 * 1000：买入 10 SOL 后代币储备 ceil(30000 / 40) = 750
+    assert_eq!(constant_product_buy(30, 1_000, 10), Ok(250));
+    // 卖出 250 代币后 SOL 储备 ceil(30000 / 1250) = 24
+    assert_eq!(constant_product_sell(30, 1_000, 250), Ok(6));
+    // 买入 7 SOL 后代币储备 ceil(30000 / 37) = 811
+    assert_eq!(constant_product_buy(30, 1_000, 7), Ok(189));
+}
+
+#[test]
+fn calculate_fees_splits_the_total_by_creator_share() {
+    // 10000 * 30bps = 30，创作者占 10/30
+    assert_eq!(calculate_fees(10_000, 30, 10), Ok((10, 20)));
+    // 12345 * 100bps = 123（向下取整），创作者 123 * 25 / 100 = 30
+    assert_eq!(calculate_fees(12_345, 100, 25), Ok((30, 93)));
+    assert_eq!(calculate_fees(333, 30, 10), Ok((0, 0))); // 总费用 0.999 取整为 0
+}
+
+#[test]
+fn gross_amount_for_net_rounds_up() {
+    assert_eq!(gross_amount_for_net(9_970, 30), Ok(10_000)); // 9970 / 0.997 整除
+    assert_eq!(gross_amount_for_net(1_000, 30), Ok(1_004)); // 1003.009... 向上取整
+    assert_eq!(gross_amount_for_net(1_000, 5_000), Ok(2_000));
+    assert_eq!(gross_amount_for_net(1_000, 0), Ok(1_000));
+    assert_eq!(gross_amount_for_net(1_000, 10_000), Err(MathError::Overflow)); // 100% 费用
+}
+
+#[test]
+fn migration_amounts_burns_surplus_reserve() {
+    // 价格 1/3 lamport/单位：100 lamports 配 300 个代币，500 个储备烧掉 200
+    assert_eq!(migration_amounts(100, 500, 1, 3), Ok((300, 200)));
+}
+
+#[test]
+fn migration_amounts_never_exceeds_the_reserve() {
+    // 储备只有 200 个：全部注入，不增发
+    assert_eq!(migration_amounts(100, 200, 1, 3), Ok((200, 0)));
+}
+
+#[test]
+fn migration_amounts_rounds_pool_tokens_down() {
+    // 价格 3 lamports/单位：10 lamports 配 floor(10/3) = 3 个代币，储备正好够
+    assert_eq!(migration_amounts(10, 3, 3, 1), Ok((3, 0)));
+    assert_eq!(migration_amounts(10, 5, 3, 1), Ok((3, 2)));
+    assert_eq!(migration_amounts(10, 3, 0, 1), Err(MathError::Overflow)); // 零价格
+}
+
+#[test]
+fn migration_reserve_needed_pairs_the_sol_raised_at_completion() {
+    // 固定价格 2.5 lamports/单位，阈值 1000：卖出 400 个后完成，需要 400 个储备
+    let fixed = Curve::Linear {
+        base_price: 2_500_000_000,
+        slope: 0,
+    };
+    assert_eq!(migration_reserve_needed(&state_at(fixed, 0), 1_000), Ok(400));
+    // 可售代币只有 100 个（成本 250）：售罄时完成，只需配对 250 lamports
+    let mut small = state_at(fixed, 0);
+    small.real_token_reserves = 100;
+    assert_eq!(migration_reserve_needed(&small, 1_000), Ok(100));
+}
@@ -0,0 +1,215 @@
+//! 曲线和费用数学的性质测试：取整只能偏向曲线，费用不超过配置的基点且分配后不丢不增。
+
+use proptest::prelude::*;
+use pumpfun_math::fees::{calculate_fees, gross_amount_for_net};
+use pumpfun_math::quote::{quote_buy, quote_buy_exact_tokens_out, quote_sell, quote_sell_for_exact_sol};
+use pumpfun_math::{Curve, CurveState, FeeParams, BPS_DENOMINATOR};
+
+const NO_THRESHOLD: u64 = u64::MAX; // 不触发迁移阈值截断
+
+fn fresh_state(curve: Curve, virtual_sol: u64, supply: u64) -> CurveState {
+    // 函数：刚创建的曲线
+    CurveState {
+        curve,
+        virtual_sol_reserves: virtual_sol,
+        virtual_token_reserves: supply,
+        initial_virtual_token_reserves: supply,
+        real_sol_reserves: 0,
+        real_token_reserves: supply,
+    }
+}
+
+fn curve_shape() -> impl Strategy<Value = (Curve, u64)> {
+    // 策略：三种曲线形状，附带对应的虚拟SOL储备（线性/指数曲线不使用）
+    prop_oneof![
+        (1_000_000u64..1_000_000_000_000).prop_map(|virtual_sol| (Curve::ConstantProduct, virtual_sol)),
+        (1u64..1_000_000_000_000, 0u64..1_000_000).prop_map(|(base_price, slope)| (Curve::Linear { base_price, slope }, 0)),
+        (1u64..1_000_000_000, 1_000u64..1_000_000_000_000).prop_map(|(base_price, doubling_interval)| {
+            (
+                Curve::Exponential {
+                    base_price,
+                    doubling_interval,
+                },
+                0,
+            )
+        }),
+    ]
+}
+
+fn curve_state() -> impl Strategy<Value = CurveState> {
+    // 策略：任意曲线形状，先以任意金额买入一次，使曲线停在任意位置
+    (curve_shape(), 1_000_000_000u64..10_000_000_000_000_000, 0u64..100_000_000_000).prop_filter_map(
+        "opening buy overflows",
+        |((curve, virtual_sol), supply, opening_sol)| {
+            let mut state = fresh_state(curve, virtual_sol, supply);
+            let tokens_out = state.buy_output(opening_sol).ok()?;
+            state.apply_buy(opening_sol, tokens_out).ok()?;
+            Some(state)
+        },
+    )
+}
+
+fn fee_params() -> impl Strategy<Value = FeeParams> {
+    // 策略：总费用不超过 100%，创作者份额不超过总费用
+    (0u64..=BPS_DENOMINATOR as u64)
+        .prop_flat_map(|total| (Just(total), 0..=total))
+        .prop_map(|(total_trade_fee_bps, creator_fee_bps_share)| FeeParams {
+            total_trade_fee_bps,
+            creator_fee_bps_share,
+        })
+}
+
+fn k(state: &CurveState) -> u128 {
+    state.virtual_sol_reserves as u128 * state.virtual_token_reserves as u128
+}
+
+#[derive(Debug, Clone, Copy)] // 对曲线的一次操作
+enum Trade {
+    Buy(u64),             // 以给定SOL（含费用）买入
+    BuyExactTokens(u64),  // 买入精确数量代币
+    Sell(u64),            // 卖出给定代币
+    SellForExactSol(u64), // 卖出换取精确SOL
+}
+
+fn trades() -> impl Strategy<Value = Vec<Trade>> {
+    prop::collection::vec(
+        prop_oneof![
+            (0u64..100_000_000_000).prop_map(Trade::Buy),
+            (0u64..1_000_000_000_000_000).prop_map(Trade::BuyExactTokens),
+            (0u64..1_000_000_000_000_000).prop_map(Trade::Sell),
+            (0u64..100_000_000_000).prop_map(Trade::SellForExactSol),
+        ],
+        1..32,
+    )
+}
+
+fn apply(state: &mut CurveState, fees: &FeeParams, trade: Trade) -> bool {
+    // 函数：按链上处理顺序报价并更新状态，报价失败（程序会拒绝该交易）时返回 false 且不改变状态
+    let mut next = *state;
+    let applied = match trade {
+        Trade::Buy(sol) => quote_buy(state, fees, NO_THRESHOLD, sol).and_then(|q| next.apply_buy(q.sol_to_curve, q.tokens_out)),
+        Trade::BuyExactTokens(tokens) => {
+            quote_buy_exact_tokens_out(state, fees, tokens).and_then(|q| next.apply_buy(q.sol_to_curve, q.tokens_out))
+        }
+        Trade::Sell(tokens) => quote_sell(state, fees, tokens).and_then(|q| next.apply_sell(q.tokens_in, q.sol_from_curve)),
+        Trade::SellForExactSol(sol) => {
+            quote_sell_for_exact_sol(state, fees, sol).and_then(|q| next.apply_sell(q.tokens_in, q.sol_from_curve))
+        }
+    };
+    if applied.is_ok() && next.real_token_reserves <= next.initial_virtual_token_reserves {
+        *state = next;
+        true
+    } else {
+        false
+    }
+}
+
+proptest! {
+    #[test]
+    fn buy_then_sell_never_returns_more_sol_than_went_in(state in curve_state(), fees in fee_params(), sol_in in 0u64..100_000_000_000) {
+        let Ok(buy) = quote_buy(&state, &fees, NO_THRESHOLD, sol_in) else { return Ok(()) };
+        let mut after = state;
+        prop_assume!(after.apply_buy(buy.sol_to_curve, buy.tokens_out).is_ok());
+        let sell = quote_sell(&after, &fees, buy.tokens_out).unwrap();
+        prop_assert!(sell.sol_from_curve <= buy.sol_to_curve);
+        prop_assert!(sell.sol_out <= buy.sol_in);
+    }
+
+    #[test]
+    fn exact_buy_then_exact_sell_never_returns_more_sol_than_went_in(state in curve_state(), fees in fee_params(), tokens_out in 0u64..1_000_000_000_000_000) {
+        let Ok(buy) = quote_buy_exact_tokens_out(&state, &fees, tokens_out) else { return Ok(()) };
+        let mut after = state;
+        prop_assume!(after.apply_buy(buy.sol_to_curve, buy.tokens_out).is_ok());
+        let sell = quote_sell(&after, &fees, buy.tokens_out).unwrap();
+        prop_assert!(sell.sol_from_curve <= buy.sol_to_curve);
+        prop_assert!(sell.sol_out <= buy.sol_in);
+        // 换回付出的全部SOL所需的代币不少于买到的代币
+        if let Ok(exact) = quote_sell_for_exact_sol(&after, &fees, buy.sol_in) {
+            prop_assert!(exact.tokens_in >= buy.tokens_out);
+        }
+    }
+
+    #[test]
+    fn constant_product_k_never_decreases(
+        virtual_sol in 1_000_000u64..1_000_000_000_000,
+        supply in 1_000_000_000u64..10_000_000_000_000_000,
+        fees in fee_params(),
+        trades in trades(),
+    ) {
+        let mut state = fresh_state(Curve::ConstantProduct, virtual_sol, supply);
+        for trade in trades {
+            let before = k(&state);
+            if apply(&mut state, &fees, trade) {
+                prop_assert!(k(&state) >= before, "{trade:?} decreased k");
+            }
+        }
+    }
+
+    #[test]
+    fn curve_can_always_buy_back_every_sold_token(state in curve_state(), fees in fee_params(), trades in trades()) {
+        let mut state = state;
+        for trade in trades {
+            apply(&mut state, &fees, trade);
+            // 一次卖出所有已售代币得到的SOL不超过真实SOL储备
+            if let Ok(sol_out) = state.sell_output(state.tokens_sold().unwrap()) {
+                prop_assert!(sol_out <= state.real_sol_reserves, "{trade:?} left the curve insolvent");
+            }
+        }
+    }
+
+    #[test]
+    fn pro_rata_redemption_drains_exactly_the_real_reserves(state in curve_state(), fees in fee_params(), trades in trades(), share_bps in 0u64..=BPS_DENOMINATOR as u64) {
+        let mut state = state;
+        for trade in trades {
+            apply(&mut state, &fees, trade);
+        }
+        let reserves = state.real_sol_reserves;
+        let sold = state.tokens_sold().unwrap();
+        let first = (sold as u128 * share_bps as u128 / BPS_DENOMINATOR) as u64;
+        let first_out = state.redeem_output(first).unwrap();
+        state.apply_sell(first, first_out).unwrap();
+        // 先兑付的持有人不会多拿，最后一批正好取走剩余储备
+        prop_assert_eq!(state.redeem_output(sold - first).unwrap(), reserves - first_out);
+    }
+
+    #[test]
+    fn fees_never_exceed_total_bps(amount in any::<u64>(), fees in fee_params()) {
+        let (creator_fee, treasury_fee) =
+            calculate_fees(amount as u128, fees.total_trade_fee_bps, fees.creator_fee_bps_share).unwrap();
+        prop_assert!((creator_fee + treasury_fee) * BPS_DENOMINATOR <= amount as u128 * fees.total_trade_fee_bps as u128);
+        prop_assert!(creator_fee * BPS_DENOMINATOR <= amount as u128 * fees.creator_fee_bps_share as u128);
+    }
+
+    #[test]
+    fn creator_and_treasury_fees_add_up_to_total_fee(amount in any::<u64>(), fees in fee_params()) {
+        let (creator_fee, treasury_fee) =
+            calculate_fees(amount as u128, fees.total_trade_fee_bps, fees.creator_fee_bps_share).unwrap();
+        prop_assert_eq!(creator_fee + treasury_fee, amount as u128 * fees.total_trade_fee_bps as u128 / BPS_DENOMINATOR);
+    }
+
+    #[test]
+    fn gross_amount_covers_net_after_fees(net in 0u64..u64::MAX / BPS_DENOMINATOR as u64, fees in fee_params()) {
+        prop_assume!(fees.total_trade_fee_bps < BPS_DENOMINATOR as u64);
+        let gross = gross_amount_for_net(net as u128, fees.total_trade_fee_bps).unwrap();
+        let (creator_fee, treasury_fee) = calculate_fees(gross, fees.total_trade_fee_bps, fees.creator_fee_bps_share).unwrap();
+        prop_assert!(gross - creator_fee - treasury_fee >= net as u128);
+    }
+
+    #[test]
+    fn quotes_split_every_lamport(state in curve_state(), fees in fee_params(), sol in 0u64..100_000_000_000, tokens in 0u64..1_000_000_000_000_000) {
+        // 报价中的SOL在曲线、创作者和国库之间分配，不多不少
+        if let Ok(q) = quote_buy(&state, &fees, NO_THRESHOLD, sol) {
+            prop_assert!(q.sol_in <= sol);
+            prop_assert_eq!(q.sol_in, q.sol_to_curve + q.creator_fee + q.treasury_fee);
+        }
+        if let Ok(q) = quote_buy_exact_tokens_out(&state, &fees, tokens) {
+            prop_assert_eq!(q.sol_in, q.sol_to_curve + q.creator_fee + q.treasury_fee);
+        }
+        if let Ok(q) = quote_sell(&state, &fees, tokens) {
+            prop_assert_eq!(q.sol_from_curve, q.sol_out + q.creator_fee + q.treasury_fee);
+        }
+        if let Ok(q) = quote_sell_for_exact_sol(&state, &fees, sol) {
+            prop_assert_eq!(q.sol_from_curve, q.sol_out + q.creator_fee + q.treasury_fee);
+        }
+    }
+}
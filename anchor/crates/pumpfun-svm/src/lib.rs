@@ -0,0 +1,320 @@
+//! 基于 `solana-program-test` 的测试环境：pumpfun 程序在真实的 bank 中执行，交易经过签名校验、
+//! 计算预算、租金和账户规则检查；Token-2022 与关联代币账户程序以 SBF 字节码加载。
+//!
+//! 设置了 `SBF_OUT_DIR`（或 `BPF_OUT_DIR`）时，bank 加载 `anchor build` 生成的 `pumpfun.so`；
+//! 否则回退为以原生函数注册的内置程序，此时 pumpfun 自身的指令不计入计算单元，
+//! 事件通过下面的 `sol_log_data` 转发写入日志。
+
+use std::{collections::BTreeSet, sync::Once};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+use solana_account::AccountSharedData;
+use solana_program::{
+    account_info::AccountInfo,
+    clock::Clock,
+    entrypoint::ProgramResult,
+    instruction::{Instruction, InstructionError},
+    message::Message,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    rent::Rent,
+};
+use solana_program_test::{BanksClient, ProgramTestContext};
+use solana_sdk_ids::{system_program, sysvar};
+use solana_sysvar::program_stubs::{self, SyscallStubs};
+use solana_transaction::Transaction;
+use spl_token_2022::{
+    extension::StateWithExtensions,
+    state::{Account as TokenAccount, Mint},
+};
+
+pub use solana_account::Account;
+pub use solana_keypair::Keypair;
+pub use solana_program_test::{processor, ProgramTest};
+pub use solana_signer::Signer;
+
+const FEE_PAYER_LAMPORTS: u64 = 10 * LAMPORTS_PER_SOL; // 每笔交易的手续费付款人余额
+
+#[derive(Debug, Clone, PartialEq, Eq)] // 交易错误
+pub struct TransactionError {
+    pub error: solana_transaction_error::TransactionError, // bank 返回的错误
+    pub logs: Vec<String>,                                 // 执行日志（签名校验等执行前失败时为空）
+}
+
+impl TransactionError {
+    pub fn instruction_error(&self) -> Option<(usize, &InstructionError)> {
+        // 函数：失败的指令序号和指令错误
+        match &self.error {
+            solana_transaction_error::TransactionError::InstructionError(index, error) => {
+                Some((*index as usize, error))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn custom_code(&self) -> Option<u32> {
+        // 函数：程序自定义错误码（Anchor 错误码从 6000 开始）
+        match self.instruction_error() {
+            Some((_, InstructionError::Custom(code))) => Some(*code),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)] // 交易结果
+pub struct TransactionMeta {
+    pub logs: Vec<String>,            // 日志（事件为 `Program data:` 行）
+    pub compute_units_consumed: u64,  // 消耗的计算单元
+}
+
+impl TransactionMeta {
+    pub fn program_data(&self) -> Vec<Vec<u8>> {
+        // 函数：解码全部 `Program data:` 行（emit! 写入的事件）；原生回退时该行带 `Program log: ` 前缀
+        self.logs
+            .iter()
+            .map(|line| line.strip_prefix("Program log: ").unwrap_or(line))
+            .filter_map(|line| line.strip_prefix("Program data: "))
+            .filter_map(|encoded| {
+                encoded
+                    .split(' ')
+                    .map(|field| STANDARD.decode(field))
+                    .collect::<Result<Vec<_>, _>>()
+                    .ok()
+            })
+            .map(|fields| fields.concat())
+            .collect()
+    }
+}
+
+struct LogData {
+    inner: Box<dyn SyscallStubs>, // solana-program-test 安装的 syscall stub
+}
+
+impl SyscallStubs for LogData {
+    // 原生回退时 solana-program-test 不处理 sol_log_data（emit! 的事件会丢失），这里写成日志行，其余调用原样转发
+    fn sol_log(&self, message: &str) {
+        self.inner.sol_log(message)
+    }
+    fn sol_log_compute_units(&self) {
+        self.inner.sol_log_compute_units()
+    }
+    fn sol_remaining_compute_units(&self) -> u64 {
+        self.inner.sol_remaining_compute_units()
+    }
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        self.inner.sol_invoke_signed(instruction, account_infos, signers_seeds)
+    }
+    fn sol_get_sysvar(&self, sysvar_id_addr: *const u8, var_addr: *mut u8, offset: u64, length: u64) -> u64 {
+        self.inner.sol_get_sysvar(sysvar_id_addr, var_addr, offset, length)
+    }
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.inner.sol_get_clock_sysvar(var_addr)
+    }
+    fn sol_get_epoch_schedule_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.inner.sol_get_epoch_schedule_sysvar(var_addr)
+    }
+    fn sol_get_fees_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.inner.sol_get_fees_sysvar(var_addr)
+    }
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.inner.sol_get_rent_sysvar(var_addr)
+    }
+    fn sol_get_epoch_rewards_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.inner.sol_get_epoch_rewards_sysvar(var_addr)
+    }
+    fn sol_get_last_restart_slot(&self, var_addr: *mut u8) -> u64 {
+        self.inner.sol_get_last_restart_slot(var_addr)
+    }
+    fn sol_get_epoch_stake(&self, vote_address: *const u8) -> u64 {
+        self.inner.sol_get_epoch_stake(vote_address)
+    }
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        self.inner.sol_get_return_data()
+    }
+    fn sol_set_return_data(&self, data: &[u8]) {
+        self.inner.sol_set_return_data(data)
+    }
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        let encoded: Vec<String> = fields.iter().map(|field| STANDARD.encode(field)).collect();
+        self.inner.sol_log(&format!("Program data: {}", encoded.join(" ")))
+    }
+    fn sol_get_processed_sibling_instruction(&self, index: usize) -> Option<Instruction> {
+        self.inner.sol_get_processed_sibling_instruction(index)
+    }
+    fn sol_get_stack_height(&self) -> u64 {
+        self.inner.sol_get_stack_height()
+    }
+}
+
+struct Placeholder; // 替换 stub 的瞬间占位，此时还没有交易在执行
+
+impl SyscallStubs for Placeholder {}
+
+fn install_log_data() {
+    // 函数：在 solana-program-test 安装自己的 stub 之后包上一层；每个 Svm 启动后都经过这里，只生效一次
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let inner = program_stubs::set_syscall_stubs(Box::new(Placeholder));
+        program_stubs::set_syscall_stubs(Box::new(LogData { inner }));
+    });
+}
+
+pub struct Svm {
+    runtime: tokio::runtime::Runtime, // 驱动 bank 的单线程运行时
+    context: ProgramTestContext,      // bank 和 banks 客户端
+    known: BTreeSet<Pubkey>,          // 交易、空投或写入过的账户（守恒检查的范围，不含手续费付款人）
+}
+
+impl Svm {
+    pub fn new(program_test: ProgramTest) -> Self {
+        // 函数：启动 bank
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("failed to build the tokio runtime");
+        let context = runtime.block_on(program_test.start_with_context());
+        install_log_data();
+        Self {
+            runtime,
+            context,
+            known: BTreeSet::new(),
+        }
+    }
+
+    fn banks(&self) -> BanksClient {
+        self.context.banks_client.clone()
+    }
+
+    pub fn account(&self, address: &Pubkey) -> Option<Account> {
+        self.runtime
+            .block_on(self.banks().get_account(*address))
+            .expect("banks client failed")
+    }
+
+    pub fn set_account(&mut self, address: Pubkey, account: Account) {
+        // 函数：直接写入账户（模拟无法通过交易达到的状态）
+        self.context.set_account(&address, &AccountSharedData::from(account));
+        self.known.insert(address);
+    }
+
+    pub fn balance(&self, address: &Pubkey) -> u64 {
+        self.account(address).map_or(0, |account| account.lamports)
+    }
+
+    pub fn airdrop(&mut self, address: &Pubkey, lamports: u64) {
+        // 函数：凭空增加余额（新账户归系统程序所有）
+        let mut account = self
+            .account(address)
+            .unwrap_or_else(|| Account::new(0, 0, &system_program::ID));
+        account.lamports += lamports;
+        self.set_account(*address, account);
+    }
+
+    pub fn airdrop_sol(&mut self, address: &Pubkey, sol: u64) {
+        self.airdrop(address, sol * LAMPORTS_PER_SOL);
+    }
+
+    pub fn token_amount(&self, address: &Pubkey) -> u64 {
+        // 函数：代币账户余额，账户不存在时为 0
+        self.account(address).map_or(0, |account| {
+            StateWithExtensions::<TokenAccount>::unpack(&account.data)
+                .map(|state| state.base.amount)
+                .unwrap_or(0)
+        })
+    }
+
+    pub fn mint_supply(&self, mint: &Pubkey) -> u64 {
+        // 函数：铸币总供应量
+        self.account(mint).map_or(0, |account| {
+            StateWithExtensions::<Mint>::unpack(&account.data)
+                .map(|state| state.base.supply)
+                .unwrap_or(0)
+        })
+    }
+
+    pub fn token_amount_held(&self, mint: &Pubkey) -> u64 {
+        // 函数：已知账户中该铸币所有代币账户的余额之和（守恒检查用，应等于总供应量）
+        self.known
+            .iter()
+            .filter_map(|address| self.account(address))
+            .filter(|account| account.owner == spl_token_2022::ID)
+            .filter_map(|account| {
+                StateWithExtensions::<TokenAccount>::unpack(&account.data)
+                    .ok()
+                    .filter(|state| state.base.mint == *mint)
+                    .map(|state| state.base.amount)
+            })
+            .sum()
+    }
+
+    pub fn total_lamports(&self) -> u128 {
+        // 函数：已知账户的 lamports 总和（守恒检查用）；手续费由每笔交易单独的付款人支付，
+        // 程序和 sysvar 账户的余额固定，第一次出现在交易里时不应改变总量，都不计入
+        self.known
+            .iter()
+            .filter_map(|address| self.account(address))
+            .filter(|account| !account.executable && account.owner != sysvar::ID)
+            .map(|account| account.lamports as u128)
+            .sum()
+    }
+
+    pub fn clock(&self) -> Clock {
+        self.runtime
+            .block_on(self.banks().get_sysvar::<Clock>())
+            .expect("banks client failed")
+    }
+
+    pub fn set_unix_timestamp(&mut self, unix_timestamp: i64) {
+        let mut clock = self.clock();
+        clock.unix_timestamp = unix_timestamp;
+        self.context.set_sysvar(&clock);
+    }
+
+    pub fn rent(&self) -> Rent {
+        self.runtime.block_on(self.banks().get_rent()).expect("banks client failed")
+    }
+
+    pub fn process_transaction(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<TransactionMeta, TransactionError> {
+        // 函数：签名并执行一笔交易；手续费付款人每笔新建，保证相同指令重复提交时签名不同
+        let fee_payer = Keypair::new();
+        self.context.set_account(
+            &fee_payer.pubkey(),
+            &AccountSharedData::new(FEE_PAYER_LAMPORTS, 0, &system_program::ID),
+        );
+        let message = Message::new(instructions, Some(&fee_payer.pubkey()));
+        self.known.extend(message.account_keys.iter().skip(1));
+        let blockhash = self
+            .runtime
+            .block_on(self.banks().get_latest_blockhash())
+            .expect("banks client failed");
+        let mut keypairs = vec![&fee_payer];
+        keypairs.extend(signers.iter().filter(|keypair| message.signer_keys().contains(&&keypair.pubkey())));
+        let mut transaction = Transaction::new_unsigned(message);
+        transaction.try_sign(&keypairs, blockhash).expect("missing a signer keypair");
+
+        let outcome = self
+            .runtime
+            .block_on(self.banks().process_transaction_with_metadata(transaction))
+            .expect("banks client failed");
+        let (logs, compute_units_consumed) = outcome
+            .metadata
+            .map_or((Vec::new(), 0), |metadata| (metadata.log_messages, metadata.compute_units_consumed));
+        match outcome.result {
+            Ok(()) => Ok(TransactionMeta {
+                logs,
+                compute_units_consumed,
+            }),
+            Err(error) => Err(TransactionError { error, logs }),
+        }
+    }
+}
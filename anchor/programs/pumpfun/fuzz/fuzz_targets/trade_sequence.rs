@@ -0,0 +1,142 @@
+//! 随机买卖序列：在 solana-program-test 的 bank 中对同一条曲线执行任意的 buy/sell 指令，每笔交易后检查
+//! lamports 守恒、代币余额之和等于总供应量、曲线 lamports 与储备一致且足以回购所有已售代币；
+//! 最后所有交易者清仓，交易者整体拿回的SOL不多于投入的SOL。
+//!
+//! 运行：`cargo +nightly fuzz run trade_sequence`（在 programs/pumpfun 目录下）
+#![no_main]
+
+#[path = "../../tests/common/mod.rs"]
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use arbitrary::Arbitrary;
+use common::*;
+use libfuzzer_sys::fuzz_target;
+use pumpfun_client::{instructions as ix, pda};
+
+const TRADERS: usize = 3; // 交易者数量
+const MAX_TRADES: usize = 64; // 每个输入最多执行的交易数
+const TRADER_SOL: u64 = 1_000; // 每个交易者的初始余额（SOL）
+
+#[derive(Debug, Arbitrary)] // 一笔交易；数量按交易者余额或曲线储备取模，使大部分交易能够成交
+enum Trade {
+    Buy { trader: u8, sol: u64 },             // 以给定SOL（含费用）买入
+    BuyExactTokens { trader: u8, tokens: u64 }, // 买入精确数量代币
+    Sell { trader: u8, tokens: u64 },         // 卖出持有代币的一部分
+    SellForExactSol { trader: u8, sol: u64 }, // 卖出换取精确SOL
+    Claim,                                    // 创作者领取费用
+}
+
+struct Harness {
+    env: Env,                      // 环境
+    mint: Pubkey,                  // 铸币
+    creator: Pubkey,               // 创作者
+    traders: [Pubkey; TRADERS],    // 交易者
+    lamports: u128,                // lamports 总量
+}
+
+impl Harness {
+    fn new() -> Self {
+        let mut env = Env::new();
+        let creator = env.user(10);
+        let mint = env.create(creator);
+        let traders = [(); TRADERS].map(|_| env.user(TRADER_SOL));
+        let lamports = env.svm.total_lamports();
+        Self {
+            env,
+            mint,
+            creator,
+            traders,
+            lamports,
+        }
+    }
+
+    fn instruction(&self, trade: &Trade) -> (anchor_lang::solana_program::instruction::Instruction, Pubkey) {
+        // 函数：把随机交易转换为指令和签名者
+        let trader = |index: &u8| self.traders[*index as usize % TRADERS];
+        let curve = self.env.curve(&self.mint);
+        match trade {
+            Trade::Buy { trader: t, sol } => {
+                let buyer = trader(t);
+                let sol = sol % (self.env.svm.balance(&buyer) + 1);
+                (self.env.buy_ix(buyer, self.mint).buy(sol, 0, FAR_DEADLINE), buyer)
+            }
+            Trade::BuyExactTokens { trader: t, tokens } => {
+                let buyer = trader(t);
+                let tokens = tokens % (curve.real_token_reserves + 1);
+                (
+                    self.env.buy_ix(buyer, self.mint).buy_exact_tokens_out(tokens, u64::MAX, FAR_DEADLINE),
+                    buyer,
+                )
+            }
+            Trade::Sell { trader: t, tokens } => {
+                let seller = trader(t);
+                let tokens = tokens % (self.env.tokens(&seller, &self.mint) + 1);
+                (self.env.sell_ix(seller, self.mint).sell(tokens, 0, FAR_DEADLINE), seller)
+            }
+            Trade::SellForExactSol { trader: t, sol } => {
+                let seller = trader(t);
+                let sol = sol % (curve.real_sol_reserves + 1);
+                (
+                    self.env.sell_ix(seller, self.mint).sell_for_exact_sol(sol, u64::MAX, FAR_DEADLINE),
+                    seller,
+                )
+            }
+            Trade::Claim => (
+                ix::ClaimCreatorFees {
+                    creator: self.creator,
+                    creator_fee_recipient: self.creator,
+                    token_mint: self.mint,
+                }
+                .instruction(),
+                self.creator,
+            ),
+        }
+    }
+
+    fn send(&mut self, instruction: anchor_lang::solana_program::instruction::Instruction, signer: Pubkey) {
+        // 函数：执行交易；程序可以拒绝交易，但不能违反运行时规则
+        if let Err(error) = self.env.send(&[instruction], &[signer]) {
+            assert!(error.custom_code().is_some(), "runtime rejected the program: {error:?}");
+        }
+        self.check_invariants();
+    }
+
+    fn check_invariants(&self) {
+        // 函数：每笔交易后的不变量
+        let svm = &self.env.svm;
+        assert_eq!(svm.total_lamports(), self.lamports, "lamports were created or destroyed");
+        assert_eq!(svm.token_amount_held(&self.mint), svm.mint_supply(&self.mint), "token balances do not add up to supply");
+
+        let account = svm.account(&pda::bonding_curve(&self.mint).0).unwrap();
+        let curve = self.env.curve(&self.mint);
+        let rent = svm.rent().minimum_balance(account.data.len());
+        assert_eq!(account.lamports, rent + curve.real_sol_reserves + curve.creator_fees_owed, "curve lamports drifted from its reserves");
+        if let Ok(sol_out) = curve.get_sell_output(curve.tokens_sold().unwrap()) {
+            assert!(sol_out <= curve.real_sol_reserves, "curve cannot buy back every sold token");
+        }
+    }
+}
+
+fuzz_target!(|trades: Vec<Trade>| {
+    let mut harness = Harness::new();
+    let total = |harness: &Harness| harness.traders.iter().map(|t| harness.env.svm.balance(t)).sum::<u64>();
+    let before = total(&harness);
+    for trade in trades.iter().take(MAX_TRADES) {
+        let (instruction, signer) = harness.instruction(trade);
+        harness.send(instruction, signer);
+    }
+    if harness.env.curve(&harness.mint).is_completed {
+        return; // 曲线已完成，不能再卖回曲线
+    }
+
+    // 清仓：卖出全部代币后，交易者的余额之和不高于初始余额之和（单个交易者可以从其他交易者处获利）
+    for trader in harness.traders {
+        let tokens = harness.env.tokens(&trader, &harness.mint);
+        if tokens > 0 {
+            let instruction = harness.env.sell_ix(trader, harness.mint).sell(tokens, 0, FAR_DEADLINE);
+            harness.send(instruction, trader);
+        }
+    }
+    assert!(total(&harness) <= before, "traders made a profit against the curve");
+});
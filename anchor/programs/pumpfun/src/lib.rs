@@ -1,28 +1,28 @@
+#![allow(deprecated)] // Anchor 0.31 的 #[program] 宏展开内部使用了已弃用的 AccountInfo::realloc
+
 use anchor_lang::{
     // 导入Anchor框架的核心模块，用于Solana程序开发
     prelude::*, // 导入Anchor的预导入项，包括常用类型和宏
     solana_program::{
         // 导入Solana程序运行时的核心模块
-        // 新增导入 Rent 和 system_program  // 注释已存在：新增导入Rent（租金计算）和system_program（系统程序）
-        clock::Clock,                            // 导入时钟模块，用于获取当前时间戳
-        instruction::Instruction,                // 导入指令模块，用于构建程序指令
-        program::invoke,                         // 导入invoke函数，用于调用其他程序
-        program::invoke_signed,                  // 导入invoke_signed函数，用于以签名方式调用程序
-        rent::Rent,                              // 导入Rent，用于计算账户最小租金余额
-        system_program as anchor_system_program, // 别名为anchor_system_program，用于系统程序操作
+        clock::Clock,           // 导入时钟模块，用于获取当前时间戳
+        program::invoke,        // 导入invoke函数，用于调用其他程序
+        program::invoke_signed, // 导入invoke_signed函数，用于以签名方式调用程序
+        instruction::{AccountMeta, Instruction}, // 导入指令类型，用于委员会提案调用本程序
+        rent::Rent,             // 导入Rent，用于计算账户最小租金余额
     },
     system_program, // 导入系统程序，用于账户创建和转移
+    InstructionData, // 导入指令数据编码，用于委员会提案调用本程序
 };
 use anchor_spl::{
     // 导入Anchor的SPL（Solana Program Library）集成模块
-    associated_token::{create as create_ata, AssociatedToken}, // 导入关联代币账户创建函数和程序
+    associated_token::{self, AssociatedToken}, // 导入关联代币账户创建函数和程序
     token_2022::{
         // 导入Token-2022扩展标准
-        spl_token_2022::extension::Length,
-        transfer_checked,
         Burn,
-        Transfer,
-        TransferChecked, // 导入扩展、转账、销毁等功能
+        InitializeAccount3,
+        InitializeMint2,
+        TransferChecked, // 导入转账、销毁、初始化铸币等功能
     },
     token_interface::{
         // 导入通用代币接口
@@ -37,59 +37,54 @@ use anchor_spl::{
         TokenInterface, // 导入元数据接口、铸币、设置权限等
     },
 };
-use borsh::BorshSerialize; // 导入Borsh序列化，用于数据序列化
 use spl_token_2022::instruction::AuthorityType; // 导入Token-2022的权限类型
 use spl_token_2022::{
     // 导入Token-2022扩展
-    extension::{ExtensionType, StateWithExtensions}, // 导入扩展类型和状态
-    state::Mint as SplMint,                          // 导入铸币状态
+    extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions}, // 导入扩展类型和状态
+    state::Mint as SplMint,                                                   // 导入铸币状态
 };
 use spl_token_metadata_interface::{
     // 导入Token元数据接口
-    instruction::initialize as initialize_metadata,
-    state::TokenMetadata, // 导入初始化函数和元数据状态
+    instruction::{
+        initialize as initialize_metadata, update_authority as update_metadata_authority,
+        update_field as update_metadata_field,
+    },
+    state::{Field, TokenMetadata}, // 导入初始化、更新函数和元数据状态
 };
-use std::convert::TryFrom; // 导入TryFrom，用于类型转换
+use pumpfun_math::{amm, BuyQuote, Curve, CurveState, FeeParams, MathError, MigrationQuote, SellQuote, BPS_DENOMINATOR}; // 导入共享的曲线、费用和DEX数学
 use std::mem::size_of; // 导入size_of，用于计算结构体大小
 
 declare_id!("E61ngnb26CrW5CHtx2gAWzKhnJ5o6TMDVFoNS9Lhr62g"); // 声明程序ID，用于标识这个Solana程序
 
-const TOTAL_SUPPLY: u64 = 1_000_000_000 * 10_u64.pow(6); // 定义总供应量：10亿代币，精度为6位小数
-const MINT_DECIMALS: u8 = 6; // 定义铸币小数位数：6位
+const TOTAL_SUPPLY: u64 = 1_000_000_000 * 10_u64.pow(6); // 定义默认总供应量：10亿代币，精度为6位小数
+const MINT_DECIMALS: u8 = 6; // 定义默认铸币小数位数（也是LP铸币的小数位数）：6位
+const MAX_CURVE_PRESETS: usize = 4; // 配置中可保存的曲线预设数量
+const MAX_MINT_DECIMALS: u8 = 9; // 预设允许的最大小数位数
 const MIN_SOL_TRADE_AMOUNT: u64 = 1_000_000; // 定义最小SOL交易金额：0.001 SOL（以lamports计）
-const LIQUIDITY_TOKEN_PERCENT: u64 = 90; // 定义流动性代币百分比：90%
-
-mod math {
-    // 定义数学模块，用于费用计算
-    pub fn calculate_fees(
-        // 函数：计算费用
-        amount: u128,                    // 输入金额
-        total_bps: u64,                  // 总基点（bps）
-        creator_share_of_total_bps: u64, // 创作者在总bps中的份额
-    ) -> (u128, u128) {
-        // 返回：(创作者费用, 国库费用)
-        if total_bps == 0 || amount == 0 {
-            // 如果bps为0或金额为0，返回0
-            return (0, 0);
-        }
-        let total_fee = amount // 计算总费用：金额 * bps / 10000
-            .checked_mul(total_bps as u128)
-            .unwrap()
-            .checked_div(10000)
-            .unwrap();
-        if total_fee == 0 || total_bps == 0 {
-            // 如果总费用为0，返回(0, total_fee)
-            return (0, total_fee);
-        }
-        let creator_fee = total_fee // 计算创作者费用：总费用 * 份额 / 总bps
-            .checked_mul(creator_share_of_total_bps as u128)
-            .unwrap()
-            .checked_div(total_bps as u128)
-            .unwrap();
-        let treasury_fee = total_fee.checked_sub(creator_fee).unwrap(); // 计算国库费用：总费用 - 创作者费用
-        (creator_fee, treasury_fee) // 返回费用对
-    }
-}
+const MINIMUM_LIQUIDITY: u64 = 1_000; // 首次注入流动性时永久锁定的最小LP数量
+const MAX_NAME_LENGTH: usize = 32; // 代币名称最大长度
+const MAX_SYMBOL_LENGTH: usize = 10; // 代币符号最大长度
+const MAX_URI_LENGTH: usize = 200; // 元数据URI最大长度
+const MAX_METADATA_KEY_LENGTH: usize = 32; // 自定义元数据字段名最大长度
+const MAX_ADDITIONAL_METADATA_FIELDS: usize = 8; // 自定义元数据字段最大数量
+const MAX_TRADE_FEE_BPS: u64 = 1_000; // 总交易费用硬上限：10%
+const MAX_DEX_LP_FEE_BPS: u64 = 1_000; // DEX LP费用硬上限：10%
+const MAX_CREATION_FEE_SOL: u64 = 10 * 10_u64.pow(9); // 创建费用硬上限：10 SOL
+const MIN_MIGRATION_THRESHOLD_SOL: u64 = 10_u64.pow(9); // 迁移阈值硬下限：1 SOL
+const MAX_MIGRATION_THRESHOLD_SOL: u64 = 10_000 * 10_u64.pow(9); // 迁移阈值硬上限：10,000 SOL
+const MIN_CONFIG_TIMELOCK_SECS: i64 = 3_600; // 配置变更延迟下限：1小时
+const MAX_CONFIG_TIMELOCK_SECS: i64 = 30 * 86_400; // 配置变更延迟上限：30天
+const DEFAULT_CONFIG_TIMELOCK_SECS: i64 = 86_400; // 默认配置变更延迟：1天
+const MAX_VESTING_DURATION_SECS: i64 = 4 * 365 * 86_400; // 创作者费用释放期上限：4年
+pub const MAX_COUNCIL_MEMBERS: usize = 10; // 治理委员会最大成员数（客户端构造成员变更提案时使用）
+pub const MAX_FEE_SPLIT_RECIPIENTS: usize = 8; // 创作者费用分账最大接收者数
+pub const PAUSE_CREATE: u8 = 1 << 0; // 暂停位：创建代币
+pub const PAUSE_BUY: u8 = 1 << 1; // 暂停位：曲线和DEX买入（含注入流动性）
+pub const PAUSE_SELL: u8 = 1 << 2; // 暂停位：曲线和DEX卖出
+pub const PAUSE_CLAIM: u8 = 1 << 3; // 暂停位：领取创作者费用
+pub const PAUSE_MIGRATE: u8 = 1 << 4; // 暂停位：迁移到DEX
+pub const PAUSE_ALL: u8 = PAUSE_CREATE | PAUSE_BUY | PAUSE_SELL | PAUSE_CLAIM | PAUSE_MIGRATE; // 全部暂停位
+const LIQUIDITY_TOKEN_PERCENT: u64 = 90; // 定义流动性代币百分比：默认预设中90%在曲线上出售，其余留作迁移储备
 
 #[program] // Anchor宏：定义Solana程序模块
 pub mod pumpfun {
@@ -101,11 +96,23 @@ pub mod pumpfun {
         let config = &mut ctx.accounts.protocol_config; // 获取可变配置账户
         config.governance_authority = ctx.accounts.authority.key(); // 设置治理权限
         config.treasury = ctx.accounts.treasury.key(); // 设置国库地址
-        config.creation_fee_sol = 1 * 10_u64.pow(9); // 设置创建费用：1 SOL
+        config.creation_fee_sol = 10_u64.pow(9); // 设置创建费用：1 SOL
         config.total_trade_fee_bps = 30; // 设置总交易费用bps：30
         config.creator_fee_bps_share = 10; // 设置创作者费用份额：10
         config.migration_threshold_sol = 50 * 10_u64.pow(9); // 设置迁移阈值：50 SOL
-        config.is_paused = false; // 设置暂停状态：false
+        config.paused = 0; // 设置暂停位：全部开放
+        config.dex_lp_fee_bps = 25; // 设置DEX LP费用bps：25
+        config.curve_presets[0] = CurvePreset {
+            // 设置默认曲线预设（索引0）
+            virtual_sol_reserves: 10_u64.pow(9),           // 虚拟SOL储备：1 SOL
+            virtual_token_reserves: 100_000 * 10_u64.pow(6), // 虚拟代币储备：100,000 代币
+            total_supply: TOTAL_SUPPLY,                    // 总供应量
+            decimals: MINT_DECIMALS,                       // 小数位数
+            curve_supply_bps: (LIQUIDITY_TOKEN_PERCENT * 100) as u16, // 曲线可售份额，其余为迁移储备
+            curve_kind: CurveKind::ConstantProduct,        // 曲线类型：恒定乘积
+        };
+        config.curve_preset_count = 1; // 预设数量：1
+        config.config_timelock_secs = DEFAULT_CONFIG_TIMELOCK_SECS; // 配置变更延迟：1天
         config.bump = ctx.bumps.protocol_config; // 设置bump种子
         emit!(ConfigInitialized {
             // 发出事件：配置初始化
@@ -115,32 +122,413 @@ pub mod pumpfun {
         Ok(()) // 返回成功
     }
 
-    pub fn update_config(ctx: Context<UpdateConfig>, new_config: ProtocolConfigV1) -> Result<()> {
-        // 函数：更新配置
+    pub fn queue_config_change(ctx: Context<QueueConfigChange>, change: ConfigChange) -> Result<()> {
+        // 函数：排队一项配置变更，延迟期满后才能执行（排队时即检查硬上限）
+        change.validate()?;
+        let config = &mut ctx.accounts.protocol_config; // 获取可变配置账户
+        let id = config.next_config_change_id; // 本次变更编号
+        let queued_at = Clock::get()?.unix_timestamp; // 当前时间
+        let executable_at = queued_at
+            .checked_add(config.config_timelock_secs)
+            .ok_or(PumpError::MathOverflow)?; // 最早可执行时间
+        ctx.accounts.pending_change.set_inner(PendingConfigChange {
+            id,
+            change,
+            queued_at,
+            executable_at,
+            bump: ctx.bumps.pending_change,
+        });
+        config.next_config_change_id = id.checked_add(1).ok_or(PumpError::MathOverflow)?; // 编号递增
+        emit!(ConfigChangeQueued {
+            // 发出事件：配置变更已排队
+            id,
+            change,
+            executable_at
+        });
+        Ok(()) // 返回成功
+    }
+
+    pub fn execute_config_change(ctx: Context<ExecuteConfigChange>, id: u64) -> Result<()> {
+        // 函数：延迟期满后执行已排队的配置变更，并关闭待执行账户
+        let pending = &ctx.accounts.pending_change; // 待执行变更
+        require!(
+            // 延迟期必须已满
+            Clock::get()?.unix_timestamp >= pending.executable_at,
+            PumpError::TimelockNotElapsed
+        );
+        if let ConfigChange::Treasury { treasury } = pending.change {
+            // 新国库必须作为系统账户传入，确保能够接收SOL
+            require!(
+                ctx.accounts.new_treasury.as_ref().is_some_and(|account| account.key() == treasury),
+                PumpError::InvalidTreasury
+            );
+        }
+        if let ConfigChange::MigrationThreshold { migration_threshold_sol } = pending.change {
+            // 新阈值下每个已启用预设的迁移储备仍须足够
+            let config = &ctx.accounts.protocol_config;
+            for preset in &config.curve_presets[..config.curve_preset_count as usize] {
+                preset.validate(migration_threshold_sol)?;
+            }
+        }
+        pending.change.apply(&mut ctx.accounts.protocol_config); // 应用变更（发出对应字段的更新事件）
+        emit!(ConfigChangeExecuted {
+            // 发出事件：配置变更已执行
+            id,
+            change: pending.change
+        });
+        Ok(()) // 返回成功
+    }
+
+    pub fn cancel_config_change(ctx: Context<CancelConfigChange>, id: u64) -> Result<()> {
+        // 函数：撤回已排队的配置变更，并关闭待执行账户
+        let pending = &ctx.accounts.pending_change; // 待撤回变更
+        emit!(ConfigChangeCancelled {
+            // 发出事件：配置变更已撤回
+            id,
+            change: pending.change
+        });
+        Ok(()) // 返回成功
+    }
+
+    pub fn set_paused(ctx: Context<SetConfig>, paused: u8) -> Result<()> {
+        // 函数：治理设置各指令族的暂停位（可暂停也可恢复，立即生效，不经过时间锁）
+        require!(paused & !PAUSE_ALL == 0, PumpError::InvalidPauseFlags); // 不能包含未定义的位
+        let config = &mut ctx.accounts.protocol_config; // 获取可变配置账户
+        emit!(PausedUpdated {
+            // 发出事件：暂停位更新（旧值和新值）
+            authority: config.governance_authority,
+            old_paused: config.paused,
+            new_paused: paused
+        });
+        config.paused = paused;
+        Ok(()) // 返回成功
+    }
+
+    pub fn set_guardian(ctx: Context<SetConfig>, guardian: Pubkey) -> Result<()> {
+        // 函数：治理设置守护者（默认值表示没有守护者）
+        let config = &mut ctx.accounts.protocol_config; // 获取可变配置账户
+        emit!(GuardianUpdated {
+            // 发出事件：守护者更新（旧值和新值）
+            old_guardian: config.guardian,
+            new_guardian: guardian
+        });
+        config.guardian = guardian;
+        Ok(()) // 返回成功
+    }
+
+    pub fn guardian_pause(ctx: Context<GuardianPause>, families: u8) -> Result<()> {
+        // 函数：守护者暂停指定的指令族；只能增加暂停位，恢复和其他配置仍需治理
+        require!(families != 0 && families & !PAUSE_ALL == 0, PumpError::InvalidPauseFlags); // 至少一个已定义的位
+        let config = &mut ctx.accounts.protocol_config; // 获取可变配置账户
+        let paused = config.paused | families; // 新暂停位
+        emit!(PausedUpdated {
+            // 发出事件：暂停位更新（旧值和新值）
+            authority: config.guardian,
+            old_paused: config.paused,
+            new_paused: paused
+        });
+        config.paused = paused;
+        Ok(()) // 返回成功
+    }
+
+    pub fn freeze_curve(ctx: Context<CurveGovernance>, mint: Pubkey, frozen: bool) -> Result<()> {
+        // 函数：治理冻结或解冻单条曲线（立即生效）；冻结期间曲线不能交易或迁移
+        let curve = &mut ctx.accounts.bonding_curve; // 获取可变曲线
+        require!(!curve.is_migrated(), PumpError::AlreadyMigrated); // 迁移后曲线已不再交易
+        curve.is_frozen = frozen;
+        emit!(CurveFreezeUpdated {
+            // 发出事件：曲线冻结状态更新
+            mint,
+            frozen
+        });
+        Ok(()) // 返回成功
+    }
+
+    pub fn emergency_unwind(ctx: Context<CurveGovernance>, mint: Pubkey, unwinding: bool) -> Result<()> {
+        // 函数：治理开启或关闭紧急清退；清退期间持有人按比例兑付真实SOL储备，曲线交易和迁移停止
+        let curve = &mut ctx.accounts.bonding_curve; // 获取可变曲线
+        require!(!curve.is_migrated(), PumpError::AlreadyMigrated); // 迁移后储备已转入DEX池
+        require!(unwinding || curve.redeemed_tokens == 0, PumpError::RedemptionsStarted); // 兑付后的储备不再对应曲线价格，不能恢复交易
+        curve.is_unwinding = unwinding;
+        emit!(EmergencyUnwindUpdated {
+            // 发出事件：紧急清退状态更新（附当前可兑付的储备和流通量）
+            mint,
+            unwinding,
+            real_sol_reserves: curve.real_sol_reserves,
+            tokens_outstanding: curve.tokens_sold()?
+        });
+        Ok(()) // 返回成功
+    }
+
+    pub fn set_curve_preset(ctx: Context<SetCurvePreset>, index: u8, preset: CurvePreset) -> Result<()> {
+        // 函数：治理新增或替换曲线预设
+        let config = &mut ctx.accounts.protocol_config; // 获取可变配置账户
+        require!(
+            // 只能替换已有预设或追加到末尾
+            (index as usize) < MAX_CURVE_PRESETS && index <= config.curve_preset_count,
+            PumpError::InvalidCurvePreset
+        );
+        preset.validate(config.migration_threshold_sol)?; // 检查预设参数
+        config.curve_presets[index as usize] = preset;
+        if index == config.curve_preset_count {
+            config.curve_preset_count += 1; // 追加
+        }
+        emit!(CurvePresetUpdated {
+            // 发出事件：曲线预设更新
+            index,
+            virtual_sol_reserves: preset.virtual_sol_reserves,
+            virtual_token_reserves: preset.virtual_token_reserves,
+            total_supply: preset.total_supply,
+            decimals: preset.decimals,
+            curve_supply_bps: preset.curve_supply_bps,
+            curve_kind: preset.curve_kind
+        });
+        Ok(()) // 返回成功
+    }
+
+    pub fn propose_governance(ctx: Context<GovernanceProposal>, new_authority: Pubkey) -> Result<()> {
+        // 函数：提名新的治理权限，新权限调用 accept_governance 后才生效；重复提名会覆盖之前的提名
+        let config = &mut ctx.accounts.protocol_config; // 获取可变配置账户
+        require!(
+            // 不能提名空地址或当前权限
+            new_authority != Pubkey::default() && new_authority != config.governance_authority,
+            PumpError::InvalidGovernanceProposal
+        );
+        config.pending_governance = new_authority; // 记录待接受的权限
+        emit!(GovernanceProposed {
+            // 发出事件：治理权限提名
+            governance: config.governance_authority,
+            pending_governance: new_authority
+        });
+        Ok(()) // 返回成功
+    }
+
+    pub fn accept_governance(ctx: Context<AcceptGovernance>) -> Result<()> {
+        // 函数：被提名的权限签名接受，完成治理权限转移
+        let config = &mut ctx.accounts.protocol_config; // 获取可变配置账户
+        let previous_governance = config.governance_authority; // 原权限
+        config.governance_authority = ctx.accounts.pending_governance.key(); // 转移权限
+        config.pending_governance = Pubkey::default(); // 清除提名
+        emit!(GovernanceAccepted {
+            // 发出事件：治理权限转移完成
+            previous_governance,
+            new_governance: config.governance_authority
+        });
+        Ok(()) // 返回成功
+    }
+
+    pub fn cancel_governance_proposal(ctx: Context<GovernanceProposal>) -> Result<()> {
+        // 函数：当前治理权限撤回尚未被接受的提名
         let config = &mut ctx.accounts.protocol_config; // 获取可变配置账户
-        config.set_inner(new_config.into()); // 更新配置内部数据
-        config.bump = ctx.bumps.protocol_config; // 更新bump
-        emit!(ConfigUpdated {
-            // 发出事件：配置更新
-            new_governance: config.governance_authority,
-            new_treasury: config.treasury
+        require_keys_neq!(config.pending_governance, Pubkey::default(), PumpError::NoPendingGovernance); // 必须存在提名
+        let cancelled_governance = config.pending_governance; // 被撤回的提名
+        config.pending_governance = Pubkey::default(); // 清除提名
+        emit!(GovernanceProposalCancelled {
+            // 发出事件：治理权限提名撤回
+            governance: config.governance_authority,
+            cancelled_governance
+        });
+        Ok(()) // 返回成功
+    }
+
+    pub fn create_governance_council(
+        // 函数：当前治理权限创建 M-of-N 治理委员会；之后提名委员会权限 PDA 并由委员会提案接受，治理即交给委员会
+        ctx: Context<CreateGovernanceCouncil>,
+        members: Vec<Pubkey>, // 委员会成员
+        threshold: u8,        // 执行提案所需的批准数
+    ) -> Result<()> {
+        let council = &mut ctx.accounts.council; // 获取可变委员会账户
+        council.set_members(&members, threshold)?; // 检查并写入成员
+        council.bump = ctx.bumps.council; // 设置bump种子
+        council.authority_bump = ctx.bumps.council_authority; // 委员会权限 PDA 的bump
+        emit!(GovernanceCouncilUpdated {
+            // 发出事件：委员会成员更新
+            authority: ctx.accounts.council_authority.key(),
+            members,
+            threshold,
+            generation: council.generation
+        });
+        Ok(()) // 返回成功
+    }
+
+    pub fn create_council_proposal(ctx: Context<CreateCouncilProposal>, action: CouncilAction) -> Result<()> {
+        // 函数：委员会成员提出提案，提案人自动批准
+        action.validate()?; // 提前拒绝无效的成员变更
+        let council = &mut ctx.accounts.council; // 获取可变委员会账户
+        let proposer = ctx.accounts.proposer.key(); // 提案人
+        let index = council.member_index(&proposer)?; // 提案人必须是成员
+        let id = council.next_proposal_id; // 提案编号
+        ctx.accounts.proposal.set_inner(CouncilProposal {
+            id,
+            proposer,
+            action,
+            approvals: 1 << index, // 提案人的批准
+            generation: council.generation,
+            bump: ctx.bumps.proposal,
+        });
+        council.next_proposal_id = id.checked_add(1).ok_or(PumpError::MathOverflow)?; // 编号递增
+        emit!(CouncilProposalCreated {
+            // 发出事件：委员会提案创建
+            id,
+            proposer,
+            action
+        });
+        Ok(()) // 返回成功
+    }
+
+    pub fn approve_council_proposal(ctx: Context<ApproveCouncilProposal>, id: u64) -> Result<()> {
+        // 函数：委员会成员批准提案
+        let council = &ctx.accounts.council; // 委员会
+        let member = ctx.accounts.member.key(); // 批准的成员
+        let index = council.member_index(&member)?; // 必须是成员
+        let proposal = &mut ctx.accounts.proposal; // 获取可变提案账户
+        require!(proposal.generation == council.generation, PumpError::StaleCouncilProposal); // 成员变更后旧提案失效
+        require!(proposal.approvals & (1 << index) == 0, PumpError::ProposalAlreadyApproved); // 不能重复批准
+        proposal.approvals |= 1 << index; // 记录批准
+        emit!(CouncilProposalApproved {
+            // 发出事件：委员会提案批准
+            id,
+            member,
+            approvals: proposal.approval_count()
+        });
+        Ok(()) // 返回成功
+    }
+
+    pub fn execute_council_proposal<'info>(
+        // 函数：批准数达到门槛后由成员执行提案，并关闭提案账户
+        ctx: Context<'_, '_, '_, 'info, ExecuteCouncilProposal<'info>>,
+        id: u64,
+    ) -> Result<()> {
+        let council = &mut ctx.accounts.council; // 获取可变委员会账户
+        council.member_index(&ctx.accounts.member.key())?; // 执行者必须是成员
+        let proposal = &ctx.accounts.proposal; // 提案
+        require!(proposal.generation == council.generation, PumpError::StaleCouncilProposal); // 成员变更后旧提案失效
+        require!(proposal.approval_count() >= council.threshold, PumpError::CouncilThresholdNotMet); // 批准数必须达到门槛
+        let action = proposal.action; // 提案内容
+        if let CouncilAction::UpdateCouncil {
+            members,
+            member_count,
+            threshold,
+        } = action
+        {
+            // 成员变更直接作用于委员会账户，并使其余未执行的提案失效
+            let members = &members[..member_count as usize];
+            council.set_members(members, threshold)?;
+            council.generation = council.generation.checked_add(1).ok_or(PumpError::MathOverflow)?;
+            emit!(GovernanceCouncilUpdated {
+                // 发出事件：委员会成员更新
+                authority: ctx.accounts.council_authority.key(),
+                members: members.to_vec(),
+                threshold,
+                generation: council.generation
+            });
+        } else {
+            // 其余提案以委员会权限 PDA 签名调用本程序的治理指令；
+            // 剩余账户按该指令的账户顺序传入，由指令自身的约束校验（PDA 种子、has_one 等）
+            let authority = ctx.accounts.council_authority.key(); // 委员会权限
+            let accounts = ctx
+                .remaining_accounts
+                .iter()
+                .map(|account| AccountMeta {
+                    pubkey: account.key(),
+                    is_signer: account.key() == authority,
+                    is_writable: account.is_writable,
+                })
+                .collect();
+            let instruction = Instruction {
+                program_id: crate::ID,
+                accounts,
+                data: action.instruction_data(),
+            };
+            let mut account_infos = ctx.remaining_accounts.to_vec();
+            account_infos.push(ctx.accounts.pumpfun_program.to_account_info());
+            invoke_signed(
+                &instruction,
+                &account_infos,
+                &[&[b"council_authority", &[council.authority_bump]]],
+            )?;
+        }
+        emit!(CouncilProposalExecuted {
+            // 发出事件：委员会提案执行
+            id,
+            action
+        });
+        Ok(()) // 返回成功
+    }
+
+    pub fn cancel_council_proposal(ctx: Context<CancelCouncilProposal>, id: u64) -> Result<()> {
+        // 函数：提案人撤回尚未执行的提案，并关闭提案账户
+        emit!(CouncilProposalCancelled {
+            // 发出事件：委员会提案撤回
+            id,
+            action: ctx.accounts.proposal.action
         });
         Ok(()) // 返回成功
     }
 
-    pub fn create(ctx: Context<Create>, name: String, symbol: String) -> Result<()> {
+    pub fn create(
         // 函数：创建代币
+        ctx: Context<Create>,
+        name: String,
+        symbol: String,
+        uri: String,
+        preset_index: u8,
+    ) -> Result<()> {
+        require!(
+            // 检查元数据长度
+            !name.is_empty()
+                && name.len() <= MAX_NAME_LENGTH
+                && !symbol.is_empty()
+                && symbol.len() <= MAX_SYMBOL_LENGTH
+                && uri.len() <= MAX_URI_LENGTH,
+            PumpError::InvalidMetadataLength
+        );
+        require_keys_eq!(
+            // 元数据扩展只存在于 Token-2022
+            ctx.accounts.token_program.key(),
+            spl_token_2022::ID,
+            PumpError::InvalidTokenProgram
+        );
+
         let config = &ctx.accounts.protocol_config; // 获取配置
+        config.require_not_paused(PAUSE_CREATE)?; // 检查创建未暂停
+        require!(
+            // 检查预设索引
+            preset_index < config.curve_preset_count,
+            PumpError::InvalidCurvePreset
+        );
+        let preset = config.curve_presets[preset_index as usize]; // 选定的曲线预设
         let curve = &mut ctx.accounts.bonding_curve; // 获取可变绑定曲线账户
         curve.creator = ctx.accounts.creator.key(); // 设置创作者
         curve.token_mint = ctx.accounts.token_mint.key(); // 设置代币铸币地址
         curve.token_vault = ctx.accounts.token_vault.key(); // 设置代币金库
-        curve.virtual_sol_reserves = 1 * 10_u64.pow(9); // 设置虚拟SOL储备：1 SOL
-        curve.virtual_token_reserves = 100_000 * 10_u64.pow(6); // 设置虚拟代币储备：100,000 代币
+        let curve_supply = preset.curve_supply(); // 曲线可售代币
+        let migration_reserve = preset.total_supply - curve_supply; // 迁移储备代币
+        let CurveState {
+            virtual_sol_reserves: virtual_sol,
+            virtual_token_reserves: virtual_token,
+            ..
+        } = preset.initial_curve_state();
+        curve.curve_kind = preset.curve_kind; // 设置曲线类型
+        curve.virtual_sol_reserves = virtual_sol; // 设置虚拟SOL储备
+        curve.virtual_token_reserves = virtual_token; // 设置虚拟代币储备
         curve.real_sol_reserves = 0; // 设置真实SOL储备：0
+        curve.initial_virtual_sol_reserves = virtual_sol; // 记录初始参数，保证报价可复现
+        curve.initial_virtual_token_reserves = virtual_token;
+        curve.total_supply = preset.total_supply;
+        curve.decimals = preset.decimals;
+        curve.real_token_reserves = curve_supply;
         curve.is_completed = false; // 设置完成状态：false
         curve.dex_pool = Pubkey::default(); // 设置DEX池：默认
         curve.creator_fees_owed = 0; // 设置欠创作者费用：0
+        curve.is_frozen = false; // 设置冻结状态：false
+        curve.is_unwinding = false; // 设置紧急清退状态：false
+        curve.has_creator_vesting = false; // 设置费用释放计划：无
+        curve.pending_creator = Pubkey::default(); // 设置待接受的创作者：无
+        curve.creator_fee_recipient = curve.creator; // 创作者费用默认付给创作者
+        curve.has_fee_split = false; // 设置分账：无
+        curve.redeemed_tokens = 0; // 设置累计兑付：0
+        curve.redeemed_sol = 0;
         curve.bump = ctx.bumps.bonding_curve; // 设置bump
         system_program::transfer(
             // 转移创建费用到国库
@@ -153,20 +541,122 @@ pub mod pumpfun {
             ),
             config.creation_fee_sol,
         )?;
-        token_interface::set_authority(
-            // 设置铸币权限给曲线账户
+
+        let token_mint_key = ctx.accounts.token_mint.key(); // 获取铸币key
+        let curve_key = curve.key(); // 曲线PDA同时是铸币权限和元数据更新权限
+        let curve_signer_seeds = &[b"bonding_curve", token_mint_key.as_ref(), &[curve.bump]]; // 准备签名种子
+
+        // 1. 创建带 MetadataPointer 扩展的铸币账户，并预付元数据所需的租金（元数据初始化时由 Token-2022 自动扩容）
+        let token_metadata = TokenMetadata {
+            update_authority: OptionalNonZeroPubkey::try_from(Some(curve_key))?,
+            mint: token_mint_key,
+            name: name.clone(),
+            symbol: symbol.clone(),
+            uri: uri.clone(),
+            additional_metadata: vec![],
+        };
+        let mint_len = ExtensionType::try_calculate_account_len::<SplMint>(&[ExtensionType::MetadataPointer])?; // 铸币+指针扩展长度
+        let metadata_len = token_metadata.tlv_size_of()?; // 元数据TLV长度
+        let mint_lamports = Rent::get()?.minimum_balance(mint_len + metadata_len); // 按最终大小计算租金
+        system_program::create_account(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::CreateAccount {
+                    from: ctx.accounts.creator.to_account_info(),
+                    to: ctx.accounts.token_mint.to_account_info(),
+                },
+            ),
+            mint_lamports,
+            mint_len as u64,
+            &spl_token_2022::ID,
+        )?;
+
+        // 2. 初始化 MetadataPointer，指向铸币自身
+        invoke(
+            &spl_token_2022::extension::metadata_pointer::instruction::initialize(
+                &spl_token_2022::ID,
+                &token_mint_key,
+                Some(curve_key),
+                Some(token_mint_key),
+            )?,
+            &[ctx.accounts.token_mint.to_account_info()],
+        )?;
+
+        // 3. 初始化铸币，铸币权限直接交给曲线PDA
+        token_interface::initialize_mint2(
             CpiContext::new(
                 ctx.accounts.token_program.to_account_info(),
-                SetAuthority {
-                    current_authority: ctx.accounts.creator.to_account_info(),
-                    account_or_mint: ctx.accounts.token_mint.to_account_info(),
+                InitializeMint2 {
+                    mint: ctx.accounts.token_mint.to_account_info(),
                 },
             ),
-            AuthorityType::MintTokens,
-            Some(curve.key()),
+            preset.decimals,
+            &curve_key,
+            None,
         )?;
-        let token_mint_key = ctx.accounts.token_mint.key(); // 获取铸币key
-        let curve_signer_seeds = &[b"bonding_curve", token_mint_key.as_ref(), &[curve.bump]]; // 准备签名种子
+
+        // 4. 初始化 TokenMetadata（需要铸币权限签名）
+        invoke_signed(
+            &initialize_metadata(
+                &spl_token_2022::ID,
+                &token_mint_key,
+                &curve_key,
+                &token_mint_key,
+                &curve_key,
+                name.clone(),
+                symbol.clone(),
+                uri.clone(),
+            ),
+            &[
+                ctx.accounts.token_mint.to_account_info(),
+                ctx.accounts.bonding_curve.to_account_info(),
+            ],
+            &[&curve_signer_seeds[..]],
+        )?;
+
+        // 5. 创建迁移储备金库（PDA代币账户，权限为曲线PDA），迁移前不参与曲线交易
+        let migration_vault_seeds = &[
+            b"migration_vault".as_ref(),
+            token_mint_key.as_ref(),
+            &[ctx.bumps.migration_vault],
+        ];
+        let vault_len = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Account>(
+            &ExtensionType::get_required_init_account_extensions(&[ExtensionType::MetadataPointer]),
+        )?; // 代币账户长度
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::CreateAccount {
+                    from: ctx.accounts.creator.to_account_info(),
+                    to: ctx.accounts.migration_vault.to_account_info(),
+                },
+                &[&migration_vault_seeds[..]],
+            ),
+            Rent::get()?.minimum_balance(vault_len),
+            vault_len as u64,
+            &spl_token_2022::ID,
+        )?;
+        token_interface::initialize_account3(CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            InitializeAccount3 {
+                account: ctx.accounts.migration_vault.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+                authority: ctx.accounts.bonding_curve.to_account_info(),
+            },
+        ))?;
+
+        // 6. 创建曲线的代币金库（关联代币账户），铸造曲线可售部分和迁移储备
+        associated_token::create(CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            associated_token::Create {
+                payer: ctx.accounts.creator.to_account_info(),
+                associated_token: ctx.accounts.token_vault.to_account_info(),
+                authority: ctx.accounts.bonding_curve.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+        ))?;
         token_interface::mint_to(
             // 铸币到金库
             CpiContext::new_with_signer(
@@ -174,19 +664,118 @@ pub mod pumpfun {
                 MintTo {
                     mint: ctx.accounts.token_mint.to_account_info(),
                     to: ctx.accounts.token_vault.to_account_info(),
-                    authority: curve.to_account_info(),
+                    authority: ctx.accounts.bonding_curve.to_account_info(),
                 },
                 &[&curve_signer_seeds[..]],
             ),
-            TOTAL_SUPPLY,
+            curve_supply,
         )?;
+        if migration_reserve > 0 {
+            token_interface::mint_to(
+                // 铸币到迁移储备金库
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    MintTo {
+                        mint: ctx.accounts.token_mint.to_account_info(),
+                        to: ctx.accounts.migration_vault.to_account_info(),
+                        authority: ctx.accounts.bonding_curve.to_account_info(),
+                    },
+                    &[&curve_signer_seeds[..]],
+                ),
+                migration_reserve,
+            )?;
+        }
         emit!(TokenCreated {
             // 发出事件：代币创建
-            mint: ctx.accounts.token_mint.key(),
+            mint: token_mint_key,
             creator: ctx.accounts.creator.key(),
-            bonding_curve: curve.key(),
+            bonding_curve: curve_key,
             name,
-            symbol
+            symbol,
+            uri
+        });
+        Ok(()) // 返回成功
+    }
+
+    pub fn update_token_metadata(
+        // 函数：创作者在曲线活跃期间更新URI或自定义字段（如网站、社交账号）
+        ctx: Context<UpdateTokenMetadata>,
+        field: String,
+        value: String,
+    ) -> Result<()> {
+        let curve = &ctx.accounts.bonding_curve; // 获取曲线
+        require!(!curve.is_completed, PumpError::CurveCompleted); // 毕业后元数据已冻结
+        require!(value.len() <= MAX_URI_LENGTH, PumpError::InvalidMetadataLength); // 检查值长度
+        let metadata_field = match field.as_str() {
+            "uri" => Field::Uri, // URI
+            "name" | "symbol" => return err!(PumpError::InvalidMetadataField), // 名称和符号不可修改
+            key => {
+                require!(
+                    // 检查字段名长度
+                    !key.is_empty() && key.len() <= MAX_METADATA_KEY_LENGTH,
+                    PumpError::InvalidMetadataLength
+                );
+                Field::Key(key.to_string())
+            }
+        };
+
+        // 1. 在本地应用修改以计算新的元数据大小，并检查字段数量上限
+        let mint_info = ctx.accounts.token_mint.to_account_info();
+        let mut metadata = {
+            let mint_data = mint_info.try_borrow_data()?;
+            let mint_state = StateWithExtensions::<SplMint>::unpack(&mint_data)?;
+            mint_state.get_variable_len_extension::<TokenMetadata>()?
+        };
+        let old_metadata_len = metadata.tlv_size_of()?; // 修改前的TLV长度
+        metadata.update(metadata_field.clone(), value.clone());
+        require!(
+            metadata.additional_metadata.len() <= MAX_ADDITIONAL_METADATA_FIELDS,
+            PumpError::TooManyMetadataFields
+        );
+        let new_metadata_len = metadata.tlv_size_of()?; // 修改后的TLV长度
+
+        // 2. Token-2022 扩容时不会自行补租金，需要创作者预先补足
+        let new_mint_len = mint_info
+            .data_len()
+            .saturating_sub(old_metadata_len)
+            .saturating_add(new_metadata_len);
+        let required_lamports = Rent::get()?.minimum_balance(new_mint_len);
+        let lamports_shortfall = required_lamports.saturating_sub(mint_info.lamports());
+        if lamports_shortfall > 0 {
+            system_program::transfer(
+                // 补足铸币账户租金
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.creator.to_account_info(),
+                        to: mint_info.clone(),
+                    },
+                ),
+                lamports_shortfall,
+            )?;
+        }
+
+        // 3. 以曲线PDA（元数据更新权限）签名更新字段
+        let token_mint_key = curve.token_mint; // 获取铸币key
+        let curve_signer_seeds = &[b"bonding_curve", token_mint_key.as_ref(), &[curve.bump]]; // 准备签名种子
+        invoke_signed(
+            &update_metadata_field(
+                &spl_token_2022::ID,
+                &token_mint_key,
+                &curve.key(),
+                metadata_field,
+                value.clone(),
+            ),
+            &[mint_info, curve.to_account_info()],
+            &[&curve_signer_seeds[..]],
+        )?;
+
+        emit!(TokenMetadataUpdated {
+            // 发出事件：元数据更新
+            mint: token_mint_key,
+            creator: ctx.accounts.creator.key(),
+            field,
+            value
         });
         Ok(()) // 返回成功
     }
@@ -201,16 +790,21 @@ pub mod pumpfun {
         // 1. 现在这个检查可以正常工作了，因为 Anchor 不会再因为 token_vault 而提前失败。  // 注释已存在：检查曲线未完成
         let curve = &mut ctx.accounts.bonding_curve;
         require!(!curve.is_completed, PumpError::CurveCompleted);
+        curve.require_tradable()?; // 检查曲线未冻结、未清退
 
         // 2. 对于正常的交易路径，我们必须手动补上 `has_one` 的安全检查。  // 注释已存在：手动检查金库key
         require_keys_eq!(
             curve.token_vault,
             ctx.accounts.token_vault.key(),
-            PumpError::InvalidMetadataLength // 复用一个错误码，或创建一个新的如 `InvalidTokenVault`
+            PumpError::InvalidTokenVault
         );
 
         // 3. 原始的业务逻辑保持不变。  // 注释已存在：原始逻辑
         let token_amount_out;
+        let sol_to_curve; // 转入曲线的SOL：曲线部分 + 创作者费用
+        let sol_to_treasury; // 转入国库的SOL
+        let mut completed = false; // 本次购买是否触发曲线完成
+        let creator_fee_charged; // 实际收取的创作者费用
         {
             let clock = Clock::get()?; // 获取当前时钟
             require!(
@@ -219,70 +813,137 @@ pub mod pumpfun {
                 PumpError::DeadlineExceeded
             );
             let config = &ctx.accounts.protocol_config; // 获取配置
-            require!(!config.is_paused, PumpError::ProtocolPaused); // 检查协议未暂停
+            config.require_not_paused(PAUSE_BUY)?; // 检查买入未暂停
             require!(
                 // 检查交易金额不小于最小
                 total_sol_in >= MIN_SOL_TRADE_AMOUNT,
                 PumpError::TradeAmountTooSmall
             );
 
-            let (creator_fee, treasury_fee) = math::calculate_fees(
-                // 计算费用
-                total_sol_in as u128,
-                config.total_trade_fee_bps,
-                config.creator_fee_bps_share,
-            );
-            let amount_for_curve = total_sol_in // 计算曲线金额：总输入 - 费用
-                .checked_sub(treasury_fee as u64)
-                .unwrap()
-                .checked_sub(creator_fee as u64)
-                .unwrap();
+            let remaining_to_threshold = config // 距离迁移阈值还差的SOL
+                .migration_threshold_sol
+                .saturating_sub(curve.real_sol_reserves);
+            require!(remaining_to_threshold > 0, PumpError::CurveCompleted); // 已达阈值则不能再买
 
-            let calculated_tokens_out = curve.get_buy_output(amount_for_curve); // 计算输出代币
+            // 超过阈值时曲线金额截断到恰好填满阈值，并按截断后的金额重新计算费用
+            let quote = curve.quote_buy(config, total_sol_in)?; // 计算报价
+            require!(
+                // 检查曲线可售代币足够
+                quote.tokens_out <= curve.real_token_reserves,
+                PumpError::InsufficientTokenReserves
+            );
             require!(
                 // 检查滑点
-                calculated_tokens_out >= min_tokens_out,
+                quote.tokens_out >= min_tokens_out,
                 PumpError::SlippageLimitExceeded
             );
 
             curve.creator_fees_owed = curve // 更新欠创作者费用
                 .creator_fees_owed
-                .checked_add(creator_fee as u64)
-                .unwrap();
-            curve.update_buy_state(amount_for_curve, calculated_tokens_out); // 更新曲线状态
-            token_amount_out = calculated_tokens_out; // 设置输出金额
+                .checked_add(quote.creator_fee)
+                .ok_or(PumpError::MathOverflow)?;
+            curve.update_buy_state(quote.sol_to_curve, quote.tokens_out)?; // 更新曲线状态
+            token_amount_out = quote.tokens_out; // 设置输出金额
+            sol_to_curve = quote.sol_to_curve.checked_add(quote.creator_fee).ok_or(PumpError::MathOverflow)?; // 曲线部分 + 创作者费用
+            sol_to_treasury = quote.treasury_fee; // 国库费用
+            creator_fee_charged = quote.creator_fee;
+            if curve.real_sol_reserves >= config.migration_threshold_sol || curve.real_token_reserves == 0 {
+                // 达到迁移阈值或可售代币售罄：自动完成曲线，锁定交易
+                curve.is_completed = true;
+                completed = true;
+            }
         }
+        let sol_charged = sol_to_curve.checked_add(sol_to_treasury).ok_or(PumpError::MathOverflow)?; // 实际扣款
+        ctx.accounts.settle(sol_to_curve, sol_to_treasury, token_amount_out)?; // 转账SOL和代币
 
-        let token_mint_key = ctx.accounts.token_mint.key(); // 获取铸币key
-        let curve_bump = ctx.accounts.bonding_curve.bump; // 获取bump
-        let curve_signer_seeds = &[b"bonding_curve", token_mint_key.as_ref(), &[curve_bump]]; // 准备签名种子
-        let signer = &[&curve_signer_seeds[..]]; // 签名者
+        emit!(BuyEvent {
+            // 发出事件：购买事件
+            mint: ctx.accounts.token_mint.key(),
+            buyer: ctx.accounts.buyer.key(),
+            sol_in: sol_charged,
+            tokens_out: token_amount_out,
+            sol_refunded: total_sol_in.saturating_sub(sol_charged),
+            creator_fee: creator_fee_charged,
+            treasury_fee: sol_to_treasury
+        });
+        ctx.accounts.finish(completed) // 发出完成事件并检查不变量
+    }
 
-        token_interface::transfer_checked(
-            // 转账代币给买家
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                TransferChecked {
-                    from: ctx.accounts.token_vault.to_account_info(), // 使用 .to_account_info()
-                    mint: ctx.accounts.token_mint.to_account_info(),
-                    to: ctx.accounts.buyer_token_account.to_account_info(),
-                    authority: ctx.accounts.bonding_curve.to_account_info(),
-                },
-                signer,
-            ),
-            token_amount_out,
-            MINT_DECIMALS,
-        )?;
+    pub fn buy_exact_tokens_out(
+        // 函数：买入精确数量的代币，最多支付 max_sol_in（含费用）
+        ctx: Context<Buy>,
+        tokens_out: u64,
+        max_sol_in: u64,
+        deadline: i64,
+    ) -> Result<()> {
+        let curve = &mut ctx.accounts.bonding_curve;
+        require!(!curve.is_completed, PumpError::CurveCompleted);
+        curve.require_tradable()?; // 检查曲线未冻结、未清退
+        require_keys_eq!(
+            curve.token_vault,
+            ctx.accounts.token_vault.key(),
+            PumpError::InvalidTokenVault
+        );
+        require!(
+            // 检查截止时间
+            Clock::get()?.unix_timestamp <= deadline,
+            PumpError::DeadlineExceeded
+        );
+        let config = &ctx.accounts.protocol_config; // 获取配置
+        config.require_not_paused(PAUSE_BUY)?; // 检查买入未暂停
+        require!(
+            // 检查曲线可售代币足够
+            tokens_out > 0 && tokens_out <= curve.real_token_reserves,
+            PumpError::InsufficientTokenReserves
+        );
+
+        // 曲线所需SOL和含费用总额均向上取整，取整余量留在曲线
+        let BuyQuote {
+            sol_in: gross_sol_in,
+            sol_to_curve: amount_for_curve,
+            creator_fee,
+            treasury_fee,
+            ..
+        } = curve.quote_buy_exact_tokens_out(config, tokens_out)?;
+        require!(
+            // 检查交易金额不小于最小
+            gross_sol_in >= MIN_SOL_TRADE_AMOUNT,
+            PumpError::TradeAmountTooSmall
+        );
+        require!(gross_sol_in <= max_sol_in, PumpError::SlippageLimitExceeded); // 检查滑点
+        require!(
+            // 精确买入不能越过迁移阈值，剩余部分请用 buy 填满
+            amount_for_curve
+                <= config
+                    .migration_threshold_sol
+                    .saturating_sub(curve.real_sol_reserves),
+            PumpError::ExceedsMigrationThreshold
+        );
+
+        curve.creator_fees_owed = curve // 更新欠创作者费用
+            .creator_fees_owed
+            .checked_add(creator_fee)
+            .ok_or(PumpError::MathOverflow)?;
+        curve.update_buy_state(amount_for_curve, tokens_out)?; // 更新曲线状态
+        let completed = curve.real_sol_reserves >= config.migration_threshold_sol || curve.real_token_reserves == 0;
+        if completed {
+            // 达到迁移阈值或可售代币售罄：自动完成曲线，锁定交易
+            curve.is_completed = true;
+        }
 
+        let sol_to_curve = amount_for_curve.checked_add(creator_fee).ok_or(PumpError::MathOverflow)?; // 进入曲线账户的SOL（含创作者费用）
+        ctx.accounts.settle(sol_to_curve, treasury_fee, tokens_out)?; // 转账SOL和代币
         emit!(BuyEvent {
             // 发出事件：购买事件
             mint: ctx.accounts.token_mint.key(),
             buyer: ctx.accounts.buyer.key(),
-            sol_in: total_sol_in,
-            tokens_out: token_amount_out
+            sol_in: gross_sol_in,
+            tokens_out,
+            sol_refunded: 0,
+            creator_fee,
+            treasury_fee
         });
-
-        Ok(()) // 返回成功
+        ctx.accounts.finish(completed) // 发出完成事件并检查不变量
     }
 
     pub fn sell(
@@ -294,6 +955,7 @@ pub mod pumpfun {
     ) -> Result<()> {
         let sol_amount_out_net; // 声明净输出SOL
         let treasury_fee; // 声明国库费用
+        let creator_fee_charged; // 声明创作者费用
         {
             let curve = &mut ctx.accounts.bonding_curve; // 获取可变曲线
             let clock = Clock::get()?; // 获取时钟
@@ -303,94 +965,119 @@ pub mod pumpfun {
                 PumpError::DeadlineExceeded
             );
             let config = &ctx.accounts.protocol_config; // 获取配置
-            require!(!config.is_paused, PumpError::ProtocolPaused); // 检查未暂停
+            config.require_not_paused(PAUSE_SELL)?; // 检查卖出未暂停
             require!(!curve.is_completed, PumpError::CurveCompleted); // 检查曲线未完成
-            let sol_amount_out_gross = curve.get_sell_output(token_amount); // 计算总输出SOL
+            curve.require_tradable()?; // 检查曲线未冻结、未清退
+            let quote = curve.quote_sell(config, token_amount)?; // 计算报价：总输出SOL扣除费用
             require!(
                 // 检查金额有效
-                token_amount > 0 && sol_amount_out_gross >= MIN_SOL_TRADE_AMOUNT,
+                token_amount > 0 && quote.sol_from_curve >= MIN_SOL_TRADE_AMOUNT,
                 PumpError::TradeAmountTooSmall
             );
             require!(
                 // 检查储备足够
-                curve.real_sol_reserves >= sol_amount_out_gross,
+                curve.real_sol_reserves >= quote.sol_from_curve,
                 PumpError::InsufficientSolReserves
             );
-            let (creator_fee, treasury_fee_calc) = math::calculate_fees(
-                // 计算费用
-                sol_amount_out_gross as u128,
-                config.total_trade_fee_bps,
-                config.creator_fee_bps_share,
-            );
-            let calculated_sol_out_net =
-                sol_amount_out_gross // 计算净输出：总输出 - 费用
-                    .checked_sub((creator_fee + treasury_fee_calc) as u64)
-                    .unwrap();
             require!(
                 // 检查滑点
-                calculated_sol_out_net >= min_sol_out,
+                quote.sol_out >= min_sol_out,
                 PumpError::SlippageLimitExceeded
             );
             curve.creator_fees_owed = curve // 更新欠费用
                 .creator_fees_owed
-                .checked_add(creator_fee as u64)
-                .unwrap();
-            curve.update_sell_state(token_amount, sol_amount_out_gross); // 更新曲线状态
-            sol_amount_out_net = calculated_sol_out_net; // 设置净输出
-            treasury_fee = treasury_fee_calc; // 设置国库费用
-        }
-        token_interface::transfer_checked(
-            // 转账代币到金库
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                TransferChecked {
-                    from: ctx.accounts.seller_token_account.to_account_info(),
-                    mint: ctx.accounts.token_mint.to_account_info(),
-                    to: ctx.accounts.token_vault.to_account_info(),
-                    authority: ctx.accounts.seller.to_account_info(),
-                },
-            ),
-            token_amount,
-            MINT_DECIMALS,
-        )?;
-        let curve_account_info = ctx.accounts.bonding_curve.to_account_info(); // 获取曲线账户信息
-        let total_sol_to_transfer = sol_amount_out_net.checked_add(treasury_fee as u64).unwrap(); // 计算总转移SOL
-        if **curve_account_info.lamports.borrow() < total_sol_to_transfer {
-            // 检查余额足够
-            return err!(PumpError::InsufficientSolReserves);
-        }
-        let seller_account_info = ctx.accounts.seller.to_account_info(); // 获取卖家账户
-        **curve_account_info.try_borrow_mut_lamports()? -= sol_amount_out_net; // 从曲线扣除净输出
-        **seller_account_info.try_borrow_mut_lamports()? += sol_amount_out_net; // 转移到卖家
-        if treasury_fee > 0 {
-            // 如果有国库费用
-            let treasury_account_info = ctx.accounts.treasury.to_account_info(); // 获取国库
-            **curve_account_info.try_borrow_mut_lamports()? -= treasury_fee as u64; // 从曲线扣除
-            **treasury_account_info.try_borrow_mut_lamports()? += treasury_fee as u64;
-            // 转移到国库
+                .checked_add(quote.creator_fee)
+                .ok_or(PumpError::MathOverflow)?;
+            curve.update_sell_state(token_amount, quote.sol_from_curve)?; // 更新曲线状态
+            sol_amount_out_net = quote.sol_out; // 设置净输出
+            treasury_fee = quote.treasury_fee; // 设置国库费用
+            creator_fee_charged = quote.creator_fee;
         }
+        ctx.accounts.settle(token_amount, sol_amount_out_net, treasury_fee)?; // 转账代币和SOL
         emit!(SellEvent {
             // 发出事件：出售事件
             mint: ctx.accounts.bonding_curve.token_mint,
             seller: ctx.accounts.seller.key(),
             tokens_in: token_amount,
-            sol_out: sol_amount_out_net
+            sol_out: sol_amount_out_net,
+            creator_fee: creator_fee_charged,
+            treasury_fee
         });
         Ok(()) // 返回成功
     }
 
-    pub fn claim_creator_fees(ctx: Context<ClaimCreatorFees>) -> Result<()> {
-        // 函数：领取创作者费用
+    pub fn sell_for_exact_sol(
+        // 函数：卖出代币换取精确数量的SOL（扣费后），最多卖出 max_tokens_in
+        ctx: Context<Sell>,
+        sol_out: u64,
+        max_tokens_in: u64,
+        deadline: i64,
+    ) -> Result<()> {
+        require!(
+            // 检查截止时间
+            Clock::get()?.unix_timestamp <= deadline,
+            PumpError::DeadlineExceeded
+        );
+        let config = &ctx.accounts.protocol_config; // 获取配置
+        config.require_not_paused(PAUSE_SELL)?; // 检查卖出未暂停
         let curve = &mut ctx.accounts.bonding_curve; // 获取可变曲线
-        let fees_to_claim = curve.creator_fees_owed; // 获取欠费用
-        require!(fees_to_claim > 0, PumpError::NoFeesToClaim); // 检查有费用可领
-        let rent = Rent::get()?; // 获取租金计算
-        let min_balance = rent.minimum_balance(curve.to_account_info().data_len()); // 计算最小余额
-        let available_sol_for_fees = curve // 计算可用SOL：总lamports - 最小租金 - 真实储备
-            .to_account_info()
-            .lamports()
-            .saturating_sub(min_balance)
-            .saturating_sub(curve.real_sol_reserves);
+        require!(!curve.is_completed, PumpError::CurveCompleted); // 检查曲线未完成
+        curve.require_tradable()?; // 检查曲线未冻结、未清退
+
+        // 含费用总额和所需代币均向上取整，取整余量留在曲线
+        let SellQuote {
+            tokens_in,
+            sol_from_curve,
+            creator_fee,
+            treasury_fee,
+            ..
+        } = curve.quote_sell_for_exact_sol(config, sol_out)?;
+        require!(
+            // 检查金额有效
+            sol_from_curve >= MIN_SOL_TRADE_AMOUNT,
+            PumpError::TradeAmountTooSmall
+        );
+        require!(
+            // 检查储备足够：即使卖回全部已售代币也要能付出
+            curve.real_sol_reserves >= sol_from_curve
+                && curve.get_sell_output(curve.tokens_sold()?)? >= sol_from_curve,
+            PumpError::InsufficientSolReserves
+        );
+        require!(tokens_in <= max_tokens_in, PumpError::SlippageLimitExceeded); // 检查滑点
+
+        curve.creator_fees_owed = curve // 更新欠费用
+            .creator_fees_owed
+            .checked_add(creator_fee)
+            .ok_or(PumpError::MathOverflow)?;
+        curve.update_sell_state(tokens_in, sol_from_curve)?; // 更新曲线状态，取整余量留在曲线
+
+        ctx.accounts.settle(tokens_in, sol_out, treasury_fee)?; // 转账代币和SOL
+        emit!(SellEvent {
+            // 发出事件：出售事件
+            mint: ctx.accounts.bonding_curve.token_mint,
+            seller: ctx.accounts.seller.key(),
+            tokens_in,
+            sol_out,
+            creator_fee,
+            treasury_fee
+        });
+        Ok(()) // 返回成功
+    }
+
+    pub fn claim_creator_fees(ctx: Context<ClaimCreatorFees>) -> Result<()> {
+        // 函数：领取创作者费用
+        ctx.accounts.protocol_config.require_not_paused(PAUSE_CLAIM)?; // 检查领取未暂停
+        let curve = &mut ctx.accounts.bonding_curve; // 获取可变曲线
+        require!(!curve.has_creator_vesting, PumpError::CreatorFeesVesting); // 开启释放计划后只能按计划领取
+        let fees_to_claim = curve.creator_fees_owed; // 获取欠费用
+        require!(fees_to_claim > 0, PumpError::NoFeesToClaim); // 检查有费用可领
+        let rent = Rent::get()?; // 获取租金计算
+        let min_balance = rent.minimum_balance(curve.to_account_info().data_len()); // 计算最小余额
+        let available_sol_for_fees = curve // 计算可用SOL：总lamports - 最小租金 - 真实储备
+            .to_account_info()
+            .lamports()
+            .saturating_sub(min_balance)
+            .saturating_sub(curve.real_sol_reserves);
         require!(
             // 检查可用足够
             available_sol_for_fees >= fees_to_claim,
@@ -398,30 +1085,273 @@ pub mod pumpfun {
         );
         curve.creator_fees_owed = 0; // 清零欠费用
         let curve_account_info = curve.to_account_info(); // 获取曲线信息
-        let creator_account_info = ctx.accounts.creator.to_account_info(); // 获取创作者信息
+        let recipient_account_info = ctx.accounts.creator_fee_recipient.to_account_info(); // 获取费用接收者信息
         if **curve_account_info.lamports.borrow() < fees_to_claim {
             // 再次检查余额
             return err!(PumpError::InsufficientFeeReserves);
         }
         **curve_account_info.try_borrow_mut_lamports()? -= fees_to_claim; // 扣除费用
-        **creator_account_info.try_borrow_mut_lamports()? += fees_to_claim; // 转移到创作者
+        **recipient_account_info.try_borrow_mut_lamports()? += fees_to_claim; // 转移到费用接收者
         emit!(CreatorFeeClaimed {
             // 发出事件：费用领取
             mint: curve.token_mint,
             creator: curve.creator,
+            recipient: curve.creator_fee_recipient,
             amount: fees_to_claim
         });
+        if curve.has_fee_split && !ctx.remaining_accounts.is_empty() {
+            // 分账：费用接收者就是分账账户；传入全部接收者（按顺序）时直接按比例付清，否则留在分账账户由接收者自行领取
+            let mut fee_split = FeeSplit::try_deserialize(&mut &recipient_account_info.try_borrow_data()?[..])?;
+            fee_split.distribute(&recipient_account_info, ctx.remaining_accounts)?;
+            fee_split.try_serialize(&mut &mut recipient_account_info.try_borrow_mut_data()?[..])?;
+        }
+        Ok(()) // 返回成功
+    }
+
+    pub fn create_creator_vesting(ctx: Context<CreateCreatorVesting>, cliff_secs: i64, duration_secs: i64) -> Result<()> {
+        // 函数：创作者为本曲线开启费用释放计划（不可撤销）；此后费用汇入释放账户，从现在起在 duration_secs 内线性释放，cliff_secs 前不可领取
+        require!(
+            duration_secs > 0 && duration_secs <= MAX_VESTING_DURATION_SECS && (0..=duration_secs).contains(&cliff_secs),
+            PumpError::InvalidVestingSchedule
+        );
+        let now = Clock::get()?.unix_timestamp; // 获取当前时间
+        let curve = &mut ctx.accounts.bonding_curve; // 获取可变曲线
+        curve.has_creator_vesting = true;
+        ctx.accounts.creator_vesting.set_inner(CreatorVesting {
+            token_mint: curve.token_mint,
+            start_time: now,
+            cliff_secs,
+            duration_secs,
+            total_accrued: 0,
+            total_claimed: 0,
+            bump: ctx.bumps.creator_vesting,
+        });
+        emit!(CreatorVestingCreated {
+            // 发出事件：释放计划创建
+            mint: curve.token_mint,
+            creator: curve.creator,
+            start_time: now,
+            cliff_secs,
+            duration_secs
+        });
+        Ok(()) // 返回成功
+    }
+
+    pub fn claim_vested_creator_fees(ctx: Context<ClaimVestedCreatorFees>, max_amount: u64) -> Result<()> {
+        // 函数：先把曲线上的欠费汇入释放账户，再领取已释放未领取的部分（最多 max_amount）
+        ctx.accounts.protocol_config.require_not_paused(PAUSE_CLAIM)?; // 检查领取未暂停
+        let curve = &mut ctx.accounts.bonding_curve; // 获取可变曲线
+        let vesting = &mut ctx.accounts.creator_vesting; // 获取可变释放账户
+        let curve_account_info = curve.to_account_info(); // 获取曲线信息
+        let accrued = curve.sweep_creator_fees(&curve_account_info, &vesting.to_account_info())?; // 欠费汇入释放账户
+        vesting.total_accrued = vesting.total_accrued.checked_add(accrued).ok_or(PumpError::MathOverflow)?;
+        let amount = vesting.claimable(Clock::get()?.unix_timestamp)?.min(max_amount); // 本次领取金额
+        require!(amount > 0, PumpError::NoFeesToClaim); // 检查有费用可领
+        vesting.total_claimed = vesting.total_claimed.checked_add(amount).ok_or(PumpError::MathOverflow)?; // 不会超过 total_accrued
+        **vesting.to_account_info().try_borrow_mut_lamports()? -= amount; // 从释放账户扣除
+        **ctx.accounts.creator_fee_recipient.to_account_info().try_borrow_mut_lamports()? += amount; // 转移到费用接收者
+        emit!(CreatorVestingClaimed {
+            // 发出事件：领取已释放费用（附累计汇入和累计领取）
+            mint: curve.token_mint,
+            creator: curve.creator,
+            recipient: curve.creator_fee_recipient,
+            amount,
+            total_accrued: vesting.total_accrued,
+            total_claimed: vesting.total_claimed
+        });
+        Ok(()) // 返回成功
+    }
+
+    pub fn transfer_creator(ctx: Context<CreatorAuthority>, new_creator: Pubkey) -> Result<()> {
+        // 函数：提名新的创作者，新创作者调用 accept_creator 后才生效；重复提名会覆盖之前的提名
+        let curve = &mut ctx.accounts.bonding_curve; // 获取可变曲线
+        require!(
+            // 不能提名空地址或当前创作者
+            new_creator != Pubkey::default() && new_creator != curve.creator,
+            PumpError::InvalidCreatorTransfer
+        );
+        curve.pending_creator = new_creator; // 记录待接受的创作者
+        emit!(CreatorTransferProposed {
+            // 发出事件：创作者提名
+            mint: curve.token_mint,
+            creator: curve.creator,
+            pending_creator: new_creator
+        });
+        Ok(()) // 返回成功
+    }
+
+    pub fn accept_creator(ctx: Context<AcceptCreator>) -> Result<()> {
+        // 函数：被提名的创作者签名接受；费用接收者同时重置为新创作者，避免费用继续流向原团队
+        let curve = &mut ctx.accounts.bonding_curve; // 获取可变曲线
+        let previous_creator = curve.creator; // 原创作者
+        curve.creator = ctx.accounts.pending_creator.key(); // 转移创作者权限
+        curve.pending_creator = Pubkey::default(); // 清除提名
+        if !curve.has_fee_split {
+            curve.creator_fee_recipient = curve.creator; // 重置费用接收者；已开启分账的曲线保留分账，由新创作者决定是否更换比例
+        }
+        emit!(CreatorTransferred {
+            // 发出事件：创作者转移完成
+            mint: curve.token_mint,
+            previous_creator,
+            new_creator: curve.creator
+        });
+        Ok(()) // 返回成功
+    }
+
+    pub fn cancel_creator_transfer(ctx: Context<CreatorAuthority>) -> Result<()> {
+        // 函数：当前创作者撤回尚未被接受的提名
+        let curve = &mut ctx.accounts.bonding_curve; // 获取可变曲线
+        require_keys_neq!(curve.pending_creator, Pubkey::default(), PumpError::NoPendingCreator); // 必须存在提名
+        let cancelled_creator = curve.pending_creator; // 被撤回的提名
+        curve.pending_creator = Pubkey::default(); // 清除提名
+        emit!(CreatorTransferCancelled {
+            // 发出事件：创作者提名撤回
+            mint: curve.token_mint,
+            creator: curve.creator,
+            cancelled_creator
+        });
+        Ok(()) // 返回成功
+    }
+
+    pub fn set_creator_fee_recipient(ctx: Context<CreatorAuthority>, recipient: Pubkey) -> Result<()> {
+        // 函数：创作者指定费用接收者（如多签或分账账户），曲线控制权仍归创作者
+        require_keys_neq!(recipient, Pubkey::default(), PumpError::InvalidFeeRecipient); // 不能指向空地址
+        let curve = &mut ctx.accounts.bonding_curve; // 获取可变曲线
+        require!(!curve.has_fee_split, PumpError::FeeSplitActive); // 分账曲线的费用只能汇入分账账户
+        emit!(CreatorFeeRecipientUpdated {
+            // 发出事件：费用接收者更新（旧值和新值）
+            mint: curve.token_mint,
+            old_recipient: curve.creator_fee_recipient,
+            new_recipient: recipient
+        });
+        curve.creator_fee_recipient = recipient;
+        Ok(()) // 返回成功
+    }
+
+    pub fn create_fee_split(ctx: Context<CreateFeeSplit>, recipients: Vec<Pubkey>, shares_bps: Vec<u16>) -> Result<()> {
+        // 函数：创作者开启分账：最多 MAX_FEE_SPLIT_RECIPIENTS 个接收者按基点分配（合计 10000），费用接收者随之指向分账账户
+        let curve = &mut ctx.accounts.bonding_curve; // 获取可变曲线
+        let fee_split = &mut ctx.accounts.fee_split; // 获取分账账户
+        fee_split.token_mint = curve.token_mint;
+        fee_split.bump = ctx.bumps.fee_split;
+        fee_split.set_recipients(&recipients, &shares_bps)?; // 检查并写入接收者和比例
+        curve.has_fee_split = true;
+        curve.creator_fee_recipient = fee_split.key(); // 此后领取的费用（含释放计划）都汇入分账账户
+        emit!(FeeSplitUpdated {
+            // 发出事件：分账设置
+            mint: curve.token_mint,
+            creator: curve.creator,
+            recipients,
+            shares_bps
+        });
+        Ok(()) // 返回成功
+    }
+
+    pub fn update_fee_split(ctx: Context<UpdateFeeSplit>, recipients: Vec<Pubkey>, shares_bps: Vec<u16>) -> Result<()> {
+        // 函数：更换接收者和比例；曲线上的欠费先汇入并按旧比例付清（剩余账户按顺序传入旧接收者），再启用新比例
+        ctx.accounts.protocol_config.require_not_paused(PAUSE_CLAIM)?; // 付清旧份额属于领取
+        let fee_split_info = ctx.accounts.fee_split.to_account_info(); // 获取分账账户信息
+        let curve = &mut ctx.accounts.bonding_curve; // 获取可变曲线
+        if !curve.has_creator_vesting {
+            let curve_account_info = curve.to_account_info(); // 获取曲线信息
+            curve.sweep_creator_fees(&curve_account_info, &fee_split_info)?; // 欠费汇入分账账户
+        }
+        let fee_split = &mut ctx.accounts.fee_split; // 获取可变分账账户
+        fee_split.distribute(&fee_split_info, ctx.remaining_accounts)?; // 按旧比例付清
+        fee_split.set_recipients(&recipients, &shares_bps)?; // 启用新比例（零头留给新接收者）
+        emit!(FeeSplitUpdated {
+            // 发出事件：分账设置
+            mint: curve.token_mint,
+            creator: curve.creator,
+            recipients,
+            shares_bps
+        });
+        Ok(()) // 返回成功
+    }
+
+    pub fn claim_fee_split_share(ctx: Context<ClaimFeeSplitShare>) -> Result<()> {
+        // 函数：分账接收者自行领取份额；曲线上的欠费先汇入分账账户（释放计划下由创作者领取已释放部分时汇入）
+        ctx.accounts.protocol_config.require_not_paused(PAUSE_CLAIM)?; // 检查领取未暂停
+        let fee_split_info = ctx.accounts.fee_split.to_account_info(); // 获取分账账户信息
+        let curve = &mut ctx.accounts.bonding_curve; // 获取可变曲线
+        if !curve.has_creator_vesting {
+            let curve_account_info = curve.to_account_info(); // 获取曲线信息
+            curve.sweep_creator_fees(&curve_account_info, &fee_split_info)?; // 欠费汇入分账账户
+        }
+        let fee_split = &mut ctx.accounts.fee_split; // 获取可变分账账户
+        let recipient = ctx.accounts.recipient.to_account_info(); // 获取接收者信息
+        let index = fee_split
+            .recipients()
+            .iter()
+            .position(|key| key == recipient.key)
+            .ok_or(PumpError::NotFeeSplitRecipient)?; // 接收者序号
+        let amount = fee_split.pay(index, &fee_split_info, &recipient)?; // 付给接收者（发出领取事件）
+        require!(amount > 0, PumpError::NoFeesToClaim); // 检查有费用可领
+        Ok(()) // 返回成功
+    }
+
+    pub fn emergency_redeem(ctx: Context<EmergencyRedeem>, token_amount: u64) -> Result<()> {
+        // 函数：紧急清退期间按比例赎回：SOL = 真实SOL储备 * 代币 / 流通量，不收费用，也不受暂停位影响
+        let curve = &mut ctx.accounts.bonding_curve; // 获取可变曲线
+        require!(curve.is_unwinding, PumpError::CurveNotUnwinding); // 检查曲线处于清退状态
+        let sol_out = curve.get_redeem_output(token_amount)?; // 按比例计算兑付
+        require!(token_amount > 0 && sol_out > 0, PumpError::TradeAmountTooSmall); // 不兑付零SOL
+        curve.update_sell_state(token_amount, sol_out)?; // 代币回到曲线，剩余持有人的兑付比例不变
+        curve.redeemed_tokens = curve.redeemed_tokens.checked_add(token_amount).ok_or(PumpError::MathOverflow)?; // 累计兑付代币
+        curve.redeemed_sol = curve.redeemed_sol.checked_add(sol_out).ok_or(PumpError::MathOverflow)?; // 累计兑付SOL
+        token_interface::transfer_checked(
+            // 转账代币到金库
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.holder_token_account.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: ctx.accounts.token_vault.to_account_info(),
+                    authority: ctx.accounts.holder.to_account_info(),
+                },
+            ),
+            token_amount,
+            ctx.accounts.token_mint.decimals,
+        )?;
+        let curve_account_info = ctx.accounts.bonding_curve.to_account_info(); // 获取曲线信息
+        **curve_account_info.try_borrow_mut_lamports()? -= sol_out; // 从曲线扣除兑付
+        **ctx.accounts.holder.to_account_info().try_borrow_mut_lamports()? += sol_out; // 转移到持有人
+        ctx.accounts.bonding_curve.check_lamports_invariant(&curve_account_info)?; // 检查曲线lamports不变量
+        emit!(EmergencyRedeemed {
+            // 发出事件：紧急赎回
+            mint: ctx.accounts.bonding_curve.token_mint,
+            holder: ctx.accounts.holder.key(),
+            tokens_in: token_amount,
+            sol_out,
+            redeemed_tokens: ctx.accounts.bonding_curve.redeemed_tokens,
+            redeemed_sol: ctx.accounts.bonding_curve.redeemed_sol
+        });
         Ok(()) // 返回成功
     }
 
     pub fn complete_and_migrate(ctx: Context<CompleteAndMigrate>) -> Result<()> {
         // 函数：完成并迁移到DEX
-        // Phase 1: 获取所需的值  // 阶段1：获取值
+        // Phase 0: 检查曲线状态，任何人都可以调用  // 阶段0：无需许可，但必须已完成且未迁移
+        ctx.accounts.protocol_config.require_not_paused(PAUSE_MIGRATE)?; // 检查迁移未暂停
         let curve = &ctx.accounts.bonding_curve;
+        require!(!curve.is_migrated(), PumpError::AlreadyMigrated);
+        curve.require_tradable()?; // 冻结或清退中的曲线不能迁移
+        require!(
+            curve.is_completed
+                || curve.real_sol_reserves >= ctx.accounts.protocol_config.migration_threshold_sol,
+            PumpError::MigrationThresholdNotMet
+        );
+
+        // Phase 1: 获取所需的值  // 阶段1：获取值
         let token_mint_key = curve.token_mint;
         let curve_bump = curve.bump;
-        let sol_to_deposit = curve.real_sol_reserves;
-        let tokens_to_deposit = ctx.accounts.token_vault.amount;
+        let MigrationQuote {
+            sol_to_pool: sol_to_deposit,
+            tokens_to_pool: tokens_to_deposit,
+            reserve_tokens_burned: reserve_tokens_to_burn, // 多余储备代币销毁
+            lp_tokens: lp_amount_to_mint,
+        } = curve.quote_migration(ctx.accounts.migration_vault.amount)?; // 以曲线最终价格注入池子，保证价格连续
+        let unsold_tokens_to_burn = ctx.accounts.token_vault.amount; // 曲线未售出的代币销毁
 
         // Phase 2: 更新 DexPool 的状态  // 阶段2：更新DEX池
         let dex_pool = &mut ctx.accounts.dex_pool;
@@ -439,14 +1369,14 @@ pub mod pumpfun {
         ];
         let curve_signer = &[&curve_signer_seeds[..]];
 
-        // Phase 4: 转移 Token 并手动关闭 token_vault  // 阶段4：转移并关闭金库
+        // Phase 4: 从迁移储备转移 Token，销毁多余代币并关闭两个金库  // 阶段4：转移、销毁并关闭金库
         if tokens_to_deposit > 0 {
             token_interface::transfer_checked(
-                // 转移代币到DEX金库
+                // 转移储备代币到DEX金库
                 CpiContext::new_with_signer(
                     ctx.accounts.token_program.to_account_info(),
                     TransferChecked {
-                        from: ctx.accounts.token_vault.to_account_info(),
+                        from: ctx.accounts.migration_vault.to_account_info(),
                         mint: ctx.accounts.token_mint.to_account_info(),
                         to: ctx.accounts.dex_token_vault.to_account_info(),
                         authority: ctx.accounts.bonding_curve.to_account_info(),
@@ -454,23 +1384,73 @@ pub mod pumpfun {
                     curve_signer,
                 ),
                 tokens_to_deposit,
-                MINT_DECIMALS,
+                ctx.accounts.token_mint.decimals,
             )?;
         }
 
-        token_interface::close_account(CpiContext::new_with_signer(
-            // 关闭金库账户
-            ctx.accounts.token_program.to_account_info(),
-            token_interface::CloseAccount {
-                account: ctx.accounts.token_vault.to_account_info(),
-                destination: ctx.accounts.creator.to_account_info(),
-                authority: ctx.accounts.bonding_curve.to_account_info(),
-            },
+        for (vault, amount) in [
+            (ctx.accounts.migration_vault.to_account_info(), reserve_tokens_to_burn),
+            (ctx.accounts.token_vault.to_account_info(), unsold_tokens_to_burn),
+        ] {
+            if amount > 0 {
+                token_interface::burn(
+                    // 销毁多余代币
+                    CpiContext::new_with_signer(
+                        ctx.accounts.token_program.to_account_info(),
+                        Burn {
+                            mint: ctx.accounts.token_mint.to_account_info(),
+                            from: vault.clone(),
+                            authority: ctx.accounts.bonding_curve.to_account_info(),
+                        },
+                        curve_signer,
+                    ),
+                    amount,
+                )?;
+            }
+            token_interface::close_account(CpiContext::new_with_signer(
+                // 关闭金库账户
+                ctx.accounts.token_program.to_account_info(),
+                token_interface::CloseAccount {
+                    account: vault,
+                    destination: ctx.accounts.payer.to_account_info(), // 金库租金作为调用者的激励
+                    authority: ctx.accounts.bonding_curve.to_account_info(),
+                },
+                curve_signer,
+            ))?;
+        }
+
+        // Phase 4.5: 撤销元数据更新权限和元数据指针权限，毕业后元数据不可再修改  // 阶段4.5：冻结元数据
+        invoke_signed(
+            &update_metadata_authority(
+                &spl_token_2022::ID,
+                &token_mint_key,
+                &ctx.accounts.bonding_curve.key(),
+                OptionalNonZeroPubkey::default(),
+            ),
+            &[
+                ctx.accounts.token_mint.to_account_info(),
+                ctx.accounts.bonding_curve.to_account_info(),
+            ],
             curve_signer,
-        ))?;
+        )?;
+        token_interface::set_authority(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                SetAuthority {
+                    current_authority: ctx.accounts.bonding_curve.to_account_info(),
+                    account_or_mint: ctx.accounts.token_mint.to_account_info(),
+                },
+                curve_signer,
+            ),
+            AuthorityType::MetadataPointer,
+            None,
+        )?;
+        emit!(TokenMetadataFrozen {
+            // 发出事件：元数据冻结
+            mint: token_mint_key
+        });
 
-        // Phase 5: 铸造和销毁 LP token  // 阶段5：铸造并销毁LP代币
-        let lp_amount_to_mint = 1_000_000_000;
+        // Phase 5: 铸造初始 LP token 到池子自有的 LP 金库，永久锁定迁移流动性  // 阶段5：铸造并锁定初始LP
         let dex_pool_signer_seeds = &[
             b"dex_pool".as_ref(),
             token_mint_key.as_ref(),
@@ -478,68 +1458,454 @@ pub mod pumpfun {
         ];
         let dex_signer = &[&dex_pool_signer_seeds[..]];
 
-        token_interface::mint_to(
-            // 铸造LP代币
+        if lp_amount_to_mint > 0 {
+            token_interface::mint_to(
+                // 铸造LP代币
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    MintTo {
+                        mint: ctx.accounts.lp_mint.to_account_info(),
+                        to: ctx.accounts.lp_vault.to_account_info(),
+                        authority: dex_pool.to_account_info(),
+                    },
+                    dex_signer,
+                ),
+                lp_amount_to_mint,
+            )?;
+        }
+        dex_pool.lp_supply = lp_amount_to_mint; // 记录LP总供应量
+
+        emit!(DexMigrated {
+            // 发出事件：DEX迁移
+            mint: token_mint_key,
+            dex_pool: dex_pool.key(),
+            sol_reserves: dex_pool.sol_reserves,
+            token_reserves: dex_pool.token_reserves,
+            tokens_burned: reserve_tokens_to_burn + unsold_tokens_to_burn
+        });
+
+        // ==================== 最终的、根本性的修复 ====================  // 修复：标记完成并转移储备SOL
+        // 1. 先将 bonding_curve 帐户标记为已完成并记录 DEX 池（即已迁移）
+        let dex_pool_key = ctx.accounts.dex_pool.key();
+        let curve = &mut ctx.accounts.bonding_curve;
+        curve.is_completed = true;
+        curve.dex_pool = dex_pool_key;
+        curve.real_sol_reserves = 0; // 储备已全部转入DEX
+
+        // 2. 只把真实SOL储备全部转入DEX，欠创作者的费用和租金留在曲线账户中，仍可领取
+        let curve_account_info = ctx.accounts.bonding_curve.to_account_info();
+        if sol_to_deposit > 0 {
+            **curve_account_info.try_borrow_mut_lamports()? -= sol_to_deposit; // 扣除储备lamports
+            **ctx
+                .accounts
+                .dex_sol_vault
+                .to_account_info()
+                .try_borrow_mut_lamports()? += sol_to_deposit; // 转移到DEX SOL金库
+        }
+        let curve = &ctx.accounts.bonding_curve;
+        curve.check_lamports_invariant(&curve_account_info)?; // 检查剩余lamports仍覆盖欠费和租金
+        // ==================== 修复结束 ====================
+
+        Ok(()) // 返回成功
+    }
+    pub fn dex_swap_sol_for_tokens(
+        // 函数：在迁移后的DEX池中用SOL兑换代币
+        ctx: Context<DexSwap>,
+        sol_in: u64,
+        min_tokens_out: u64,
+        deadline: i64,
+    ) -> Result<()> {
+        let clock = Clock::get()?; // 获取当前时钟
+        require!(
+            // 检查截止时间
+            clock.unix_timestamp <= deadline,
+            PumpError::DeadlineExceeded
+        );
+        let config = &ctx.accounts.protocol_config; // 获取配置
+        config.require_not_paused(PAUSE_BUY)?; // 检查买入未暂停
+        require!(
+            // 检查交易金额不小于最小
+            sol_in >= MIN_SOL_TRADE_AMOUNT,
+            PumpError::TradeAmountTooSmall
+        );
+
+        let sol_reserves = DexPool::sol_vault_balance(&ctx.accounts.dex_sol_vault)?; // 按金库余额计算储备
+        let token_reserves = ctx.accounts.dex_token_vault.amount;
+        require!(
+            // 检查池子有流动性
+            sol_reserves > 0 && token_reserves > 0,
+            PumpError::DexPoolEmpty
+        );
+        let (tokens_out, lp_fee) = amm::constant_product_out(
+            // 计算输出代币
+            sol_in as u128,
+            sol_reserves as u128,
+            token_reserves as u128,
+            config.dex_lp_fee_bps,
+        ).map_err(PumpError::from)?;
+        let tokens_out = tokens_out as u64;
+        require!(
+            // 检查滑点
+            tokens_out > 0 && tokens_out >= min_tokens_out,
+            PumpError::SlippageLimitExceeded
+        );
+
+        system_program::transfer(
+            // 转移SOL到DEX SOL金库
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.user.to_account_info(),
+                    to: ctx.accounts.dex_sol_vault.to_account_info(),
+                },
+            ),
+            sol_in,
+        )?;
+
+        let token_mint_key = ctx.accounts.token_mint.key(); // 获取铸币key
+        let dex_pool_signer_seeds = &[
+            b"dex_pool".as_ref(),
+            token_mint_key.as_ref(),
+            &[ctx.accounts.dex_pool.bump],
+        ];
+        token_interface::transfer_checked(
+            // 从DEX代币金库转出代币给用户
             CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
-                MintTo {
-                    mint: ctx.accounts.lp_mint.to_account_info(),
-                    to: ctx.accounts.lp_vault.to_account_info(),
-                    authority: dex_pool.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.dex_token_vault.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: ctx.accounts.user_token_account.to_account_info(),
+                    authority: ctx.accounts.dex_pool.to_account_info(),
                 },
-                dex_signer,
+                &[&dex_pool_signer_seeds[..]],
             ),
-            lp_amount_to_mint,
+            tokens_out,
+            ctx.accounts.token_mint.decimals,
         )?;
 
-        token_interface::burn(
-            // 销毁LP代币
+        let accounts = &mut *ctx.accounts;
+        accounts
+            .dex_pool
+            .sync_reserves(&accounts.dex_sol_vault, &mut accounts.dex_token_vault)?; // 同步池子储备
+        let dex_pool = &ctx.accounts.dex_pool;
+        emit!(DexSwapEvent {
+            // 发出事件：DEX兑换
+            mint: token_mint_key,
+            user: ctx.accounts.user.key(),
+            sol_to_tokens: true,
+            amount_in: sol_in,
+            amount_out: tokens_out,
+            lp_fee: lp_fee as u64,
+            sol_reserves: dex_pool.sol_reserves,
+            token_reserves: dex_pool.token_reserves
+        });
+        Ok(()) // 返回成功
+    }
+
+    pub fn dex_swap_tokens_for_sol(
+        // 函数：在迁移后的DEX池中用代币兑换SOL
+        ctx: Context<DexSwap>,
+        tokens_in: u64,
+        min_sol_out: u64,
+        deadline: i64,
+    ) -> Result<()> {
+        let clock = Clock::get()?; // 获取当前时钟
+        require!(
+            // 检查截止时间
+            clock.unix_timestamp <= deadline,
+            PumpError::DeadlineExceeded
+        );
+        let config = &ctx.accounts.protocol_config; // 获取配置
+        config.require_not_paused(PAUSE_SELL)?; // 检查卖出未暂停
+
+        let sol_reserves = DexPool::sol_vault_balance(&ctx.accounts.dex_sol_vault)?; // 按金库余额计算储备
+        let token_reserves = ctx.accounts.dex_token_vault.amount;
+        require!(
+            // 检查池子有流动性
+            sol_reserves > 0 && token_reserves > 0,
+            PumpError::DexPoolEmpty
+        );
+        let (sol_out, lp_fee) = amm::constant_product_out(
+            // 计算输出SOL
+            tokens_in as u128,
+            token_reserves as u128,
+            sol_reserves as u128,
+            config.dex_lp_fee_bps,
+        ).map_err(PumpError::from)?;
+        let sol_out = sol_out as u64;
+        require!(
+            // 检查金额有效
+            tokens_in > 0 && sol_out >= MIN_SOL_TRADE_AMOUNT,
+            PumpError::TradeAmountTooSmall
+        );
+        require!(
+            // 检查滑点
+            sol_out >= min_sol_out,
+            PumpError::SlippageLimitExceeded
+        );
+
+        token_interface::transfer_checked(
+            // 转移代币到DEX代币金库
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.user_token_account.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: ctx.accounts.dex_token_vault.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            tokens_in,
+            ctx.accounts.token_mint.decimals,
+        )?;
+
+        let sol_vault_info = ctx.accounts.dex_sol_vault.to_account_info(); // DEX SOL金库由本程序拥有，可直接扣减
+        let user_info = ctx.accounts.user.to_account_info();
+        **sol_vault_info.try_borrow_mut_lamports()? -= sol_out; // 从金库扣除
+        **user_info.try_borrow_mut_lamports()? += sol_out; // 转给用户
+
+        let accounts = &mut *ctx.accounts;
+        accounts
+            .dex_pool
+            .sync_reserves(&accounts.dex_sol_vault, &mut accounts.dex_token_vault)?; // 同步池子储备
+        let dex_pool = &ctx.accounts.dex_pool;
+        emit!(DexSwapEvent {
+            // 发出事件：DEX兑换
+            mint: dex_pool.token_mint,
+            user: ctx.accounts.user.key(),
+            sol_to_tokens: false,
+            amount_in: tokens_in,
+            amount_out: sol_out,
+            lp_fee: lp_fee as u64,
+            sol_reserves: dex_pool.sol_reserves,
+            token_reserves: dex_pool.token_reserves
+        });
+        Ok(()) // 返回成功
+    }
+
+    pub fn add_liquidity(
+        // 函数：向DEX池注入流动性并按份额铸造LP代币
+        ctx: Context<DexLiquidity>,
+        max_sol_in: u64,
+        max_tokens_in: u64,
+        min_lp_out: u64,
+        deadline: i64,
+    ) -> Result<()> {
+        let clock = Clock::get()?; // 获取当前时钟
+        require!(
+            // 检查截止时间
+            clock.unix_timestamp <= deadline,
+            PumpError::DeadlineExceeded
+        );
+        ctx.accounts.protocol_config.require_not_paused(PAUSE_BUY)?; // 注入流动性属于买入族；移除流动性不受暂停影响
+
+        let sol_reserves = DexPool::sol_vault_balance(&ctx.accounts.dex_sol_vault)? as u128;
+        let token_reserves = ctx.accounts.dex_token_vault.amount as u128;
+        let lp_supply = ctx.accounts.dex_pool.lp_supply as u128;
+
+        let (sol_in, tokens_in, lp_out, lp_locked) = if lp_supply == 0 {
+            // 首次注入：按给定数量入池，LP = sqrt(x*y)，并锁定 MINIMUM_LIQUIDITY
+            let initial_lp = amm::initial_lp_amount(max_sol_in as u128, max_tokens_in as u128).map_err(PumpError::from)?;
+            require!(initial_lp > MINIMUM_LIQUIDITY, PumpError::TradeAmountTooSmall);
+            (max_sol_in, max_tokens_in, initial_lp - MINIMUM_LIQUIDITY, MINIMUM_LIQUIDITY)
+        } else {
+            // 后续注入：按当前比例计算所需数量，输入向上取整，LP向下取整
+            require!(sol_reserves > 0 && token_reserves > 0, PumpError::DexPoolEmpty);
+            let tokens_needed = amm::proportional_amount(max_sol_in as u128, token_reserves, sol_reserves, true).map_err(PumpError::from)?;
+            let (sol_in, tokens_in) = if tokens_needed <= max_tokens_in as u128 {
+                (max_sol_in as u128, tokens_needed)
+            } else {
+                let sol_needed = amm::proportional_amount(max_tokens_in as u128, sol_reserves, token_reserves, true).map_err(PumpError::from)?;
+                (sol_needed, max_tokens_in as u128)
+            };
+            require!(sol_in <= max_sol_in as u128, PumpError::SlippageLimitExceeded);
+            let lp_out = amm::proportional_amount(sol_in, lp_supply, sol_reserves, false).map_err(PumpError::from)?
+                .min(amm::proportional_amount(tokens_in, lp_supply, token_reserves, false).map_err(PumpError::from)?);
+            (sol_in as u64, tokens_in as u64, lp_out as u64, 0)
+        };
+        require!(lp_out > 0, PumpError::TradeAmountTooSmall);
+        require!(lp_out >= min_lp_out, PumpError::SlippageLimitExceeded); // 检查LP滑点
+
+        system_program::transfer(
+            // 转移SOL到DEX SOL金库
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.user.to_account_info(),
+                    to: ctx.accounts.dex_sol_vault.to_account_info(),
+                },
+            ),
+            sol_in,
+        )?;
+        token_interface::transfer_checked(
+            // 转移代币到DEX代币金库
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.user_token_account.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: ctx.accounts.dex_token_vault.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            tokens_in,
+            ctx.accounts.token_mint.decimals,
+        )?;
+
+        let token_mint_key = ctx.accounts.token_mint.key(); // 获取铸币key
+        let dex_pool_signer_seeds = &[
+            b"dex_pool".as_ref(),
+            token_mint_key.as_ref(),
+            &[ctx.accounts.dex_pool.bump],
+        ];
+        let dex_signer = &[&dex_pool_signer_seeds[..]];
+        token_interface::mint_to(
+            // 铸造LP代币给用户
             CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
-                Burn {
+                MintTo {
                     mint: ctx.accounts.lp_mint.to_account_info(),
-                    from: ctx.accounts.lp_vault.to_account_info(),
-                    authority: dex_pool.to_account_info(),
+                    to: ctx.accounts.user_lp_account.to_account_info(),
+                    authority: ctx.accounts.dex_pool.to_account_info(),
                 },
                 dex_signer,
             ),
-            lp_amount_to_mint,
+            lp_out,
         )?;
+        if lp_locked > 0 {
+            token_interface::mint_to(
+                // 铸造锁定的最小流动性到池子自有的LP金库
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    MintTo {
+                        mint: ctx.accounts.lp_mint.to_account_info(),
+                        to: ctx.accounts.lp_vault.to_account_info(),
+                        authority: ctx.accounts.dex_pool.to_account_info(),
+                    },
+                    dex_signer,
+                ),
+                lp_locked,
+            )?;
+        }
 
-        emit!(DexMigrated {
-            // 发出事件：DEX迁移
+        let accounts = &mut *ctx.accounts;
+        accounts.dex_pool.lp_supply = accounts
+            .dex_pool
+            .lp_supply
+            .checked_add(lp_out)
+            .ok_or(PumpError::MathOverflow)?
+            .checked_add(lp_locked)
+            .ok_or(PumpError::MathOverflow)?; // 更新LP总供应量
+        accounts
+            .dex_pool
+            .sync_reserves(&accounts.dex_sol_vault, &mut accounts.dex_token_vault)?; // 同步池子储备
+        let dex_pool = &accounts.dex_pool;
+        emit!(LiquidityAdded {
+            // 发出事件：注入流动性
             mint: token_mint_key,
-            dex_pool: dex_pool.key(),
+            user: accounts.user.key(),
+            sol_amount: sol_in,
+            token_amount: tokens_in,
+            lp_amount: lp_out,
+            lp_supply: dex_pool.lp_supply,
             sol_reserves: dex_pool.sol_reserves,
             token_reserves: dex_pool.token_reserves
         });
+        Ok(()) // 返回成功
+    }
 
-        // ==================== 最终的、根本性的修复 ====================  // 修复：标记完成并转移多余SOL
-        // 1. 先将 bonding_curve 帐户标记为已完成
-        let curve = &mut ctx.accounts.bonding_curve;
-        curve.is_completed = true;
+    pub fn remove_liquidity(
+        // 函数：销毁LP代币并按份额取回SOL和代币
+        ctx: Context<DexLiquidity>,
+        lp_amount: u64,
+        min_sol_out: u64,
+        min_tokens_out: u64,
+        deadline: i64,
+    ) -> Result<()> {
+        let clock = Clock::get()?; // 获取当前时钟
+        require!(
+            // 检查截止时间
+            clock.unix_timestamp <= deadline,
+            PumpError::DeadlineExceeded
+        );
+        require!(lp_amount > 0, PumpError::TradeAmountTooSmall); // 检查数量有效
 
-        // 2. 计算并转移多余的 SOL，同时保留足够的租金以维持帐户存在
-        let rent = Rent::get()?;
-        let curve_account_info = ctx.accounts.bonding_curve.to_account_info();
-        let min_rent_for_curve = rent.minimum_balance(curve_account_info.data_len());
+        let sol_reserves = DexPool::sol_vault_balance(&ctx.accounts.dex_sol_vault)? as u128;
+        let token_reserves = ctx.accounts.dex_token_vault.amount as u128;
+        let lp_supply = ctx.accounts.dex_pool.lp_supply as u128;
+        require!(lp_supply > lp_amount as u128, PumpError::InsufficientLiquidity); // 锁定的LP永远不能被取出
 
-        let transferable_lamports = curve_account_info
-            .lamports()
-            .saturating_sub(min_rent_for_curve);
+        let sol_out = amm::proportional_amount(lp_amount as u128, sol_reserves, lp_supply, false).map_err(PumpError::from)? as u64; // 向下取整
+        let tokens_out = amm::proportional_amount(lp_amount as u128, token_reserves, lp_supply, false).map_err(PumpError::from)? as u64;
+        require!(sol_out > 0 || tokens_out > 0, PumpError::TradeAmountTooSmall);
+        require!(
+            // 检查两侧滑点
+            sol_out >= min_sol_out && tokens_out >= min_tokens_out,
+            PumpError::SlippageLimitExceeded
+        );
 
-        if transferable_lamports > 0 {
-            **curve_account_info.try_borrow_mut_lamports()? -= transferable_lamports; // 扣除可转移lamports
-            **ctx
-                .accounts
-                .dex_sol_vault
-                .to_account_info()
-                .try_borrow_mut_lamports()? += transferable_lamports; // 转移到DEX SOL金库
+        token_interface::burn(
+            // 销毁用户的LP代币
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.lp_mint.to_account_info(),
+                    from: ctx.accounts.user_lp_account.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            lp_amount,
+        )?;
+
+        let token_mint_key = ctx.accounts.token_mint.key(); // 获取铸币key
+        let dex_pool_signer_seeds = &[
+            b"dex_pool".as_ref(),
+            token_mint_key.as_ref(),
+            &[ctx.accounts.dex_pool.bump],
+        ];
+        if tokens_out > 0 {
+            token_interface::transfer_checked(
+                // 从DEX代币金库转出代币给用户
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.dex_token_vault.to_account_info(),
+                        mint: ctx.accounts.token_mint.to_account_info(),
+                        to: ctx.accounts.user_token_account.to_account_info(),
+                        authority: ctx.accounts.dex_pool.to_account_info(),
+                    },
+                    &[&dex_pool_signer_seeds[..]],
+                ),
+                tokens_out,
+                ctx.accounts.token_mint.decimals,
+            )?;
         }
-        // ==================== 修复结束 ====================
-
+        let sol_vault_info = ctx.accounts.dex_sol_vault.to_account_info(); // DEX SOL金库由本程序拥有，可直接扣减
+        let user_info = ctx.accounts.user.to_account_info();
+        **sol_vault_info.try_borrow_mut_lamports()? -= sol_out; // 从金库扣除
+        **user_info.try_borrow_mut_lamports()? += sol_out; // 转给用户
+
+        let accounts = &mut *ctx.accounts;
+        accounts.dex_pool.lp_supply = accounts.dex_pool.lp_supply.checked_sub(lp_amount).ok_or(PumpError::ReserveUnderflow)?; // 更新LP总供应量
+        accounts
+            .dex_pool
+            .sync_reserves(&accounts.dex_sol_vault, &mut accounts.dex_token_vault)?; // 同步池子储备
+        let dex_pool = &accounts.dex_pool;
+        emit!(LiquidityRemoved {
+            // 发出事件：移除流动性
+            mint: token_mint_key,
+            user: accounts.user.key(),
+            sol_amount: sol_out,
+            token_amount: tokens_out,
+            lp_amount,
+            lp_supply: dex_pool.lp_supply,
+            sol_reserves: dex_pool.sol_reserves,
+            token_reserves: dex_pool.token_reserves
+        });
         Ok(()) // 返回成功
     }
+
     pub fn initialize_dex_pool(ctx: Context<InitializeDexPool>) -> Result<()> {
         // 函数：初始化DEX池
         // ===================== 最終的、真正的修復 =====================  // 修复：手动设置bump
@@ -554,7 +1920,7 @@ pub mod pumpfun {
         let vault_bump = ctx.bumps.dex_sol_vault;
         let vault_signer_seeds: &[&[u8]] =
             &[b"dex_sol_vault", token_mint_key.as_ref(), &[vault_bump]];
-        let signer = &[&vault_signer_seeds[..]];
+        let signer = &[vault_signer_seeds];
 
         // 2. 通過 CPI 調用系統程序來創建帳戶  // 通过CPI创建SOL金库账户
         system_program::create_account(
@@ -639,8 +2005,106 @@ pub struct InitializeDexPool<'info> {
     pub associated_token_program: Program<'info, AssociatedToken>, // 关联代币程序
 }
 
+#[derive(Accounts)] // DEX兑换上下文（两个方向共用）
+pub struct DexSwap<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>, // 交易者
+
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>, // 配置
+
+    pub token_mint: InterfaceAccount<'info, Mint>, // 代币铸币
+
+    #[account(
+        mut,
+        seeds = [b"dex_pool", token_mint.key().as_ref()],
+        bump = dex_pool.bump,
+        has_one = token_mint @ PumpError::DexPoolNotActive // 迁移前token_mint未设置
+    )]
+    pub dex_pool: Account<'info, DexPool>, // DEX池
+
+    #[account(mut, seeds = [b"dex_sol_vault", token_mint.key().as_ref()], bump)]
+    /// CHECK: PDA's correctness is guaranteed by seeds.
+    pub dex_sol_vault: UncheckedAccount<'info>, // DEX SOL金库
+
+    #[account(mut, address = dex_pool.token_vault)]
+    pub dex_token_vault: InterfaceAccount<'info, TokenAccount>, // DEX代币金库
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = token_mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>, // 用户代币账户
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)] // DEX流动性上下文（注入与移除共用）
+pub struct DexLiquidity<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>, // 流动性提供者
+
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>, // 配置
+
+    pub token_mint: InterfaceAccount<'info, Mint>, // 代币铸币
+
+    #[account(
+        mut,
+        seeds = [b"dex_pool", token_mint.key().as_ref()],
+        bump = dex_pool.bump,
+        has_one = token_mint @ PumpError::DexPoolNotActive // 迁移前token_mint未设置
+    )]
+    pub dex_pool: Account<'info, DexPool>, // DEX池
+
+    #[account(mut, seeds = [b"dex_sol_vault", token_mint.key().as_ref()], bump)]
+    /// CHECK: PDA's correctness is guaranteed by seeds.
+    pub dex_sol_vault: UncheckedAccount<'info>, // DEX SOL金库
+
+    #[account(mut, address = dex_pool.token_vault)]
+    pub dex_token_vault: InterfaceAccount<'info, TokenAccount>, // DEX代币金库
+
+    #[account(mut, seeds = [b"lp_mint", token_mint.key().as_ref()], bump)]
+    pub lp_mint: InterfaceAccount<'info, Mint>, // LP铸币
+
+    #[account(
+        mut,
+        associated_token::mint = lp_mint,
+        associated_token::authority = dex_pool,
+        associated_token::token_program = token_program
+    )]
+    pub lp_vault: InterfaceAccount<'info, TokenAccount>, // 池子自有的LP金库（锁定流动性）
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>, // 用户代币账户
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = lp_mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program
+    )]
+    pub user_lp_account: InterfaceAccount<'info, TokenAccount>, // 用户LP账户
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
 // STATE ACCOUNTS  // 状态账户
 #[account] // Anchor宏：协议配置账户
+#[derive(Debug)]
 pub struct ProtocolConfig {
     // 结构：协议配置
     pub governance_authority: Pubkey, // 治理权限
@@ -649,15 +2113,400 @@ pub struct ProtocolConfig {
     pub total_trade_fee_bps: u64,     // 总交易费用bps
     pub creator_fee_bps_share: u64,   // 创作者份额
     pub migration_threshold_sol: u64, // 迁移阈值
-    pub is_paused: bool,              // 暂停状态
+    pub paused: u8,                   // 暂停位（PAUSE_* 按指令族）
     pub bump: u8,                     // bump种子
+    pub dex_lp_fee_bps: u64,          // DEX兑换的LP费用bps
+    pub curve_presets: [CurvePreset; MAX_CURVE_PRESETS], // 治理批准的曲线预设
+    pub curve_preset_count: u8,                          // 已启用的预设数量
+    pub pending_governance: Pubkey,                      // 已提名、待接受的治理权限（默认值表示无）
+    pub config_timelock_secs: i64,                       // 配置变更从排队到可执行的延迟（秒）
+    pub next_config_change_id: u64,                      // 下一项排队变更的编号
+    pub guardian: Pubkey,                                // 守护者：只能暂停（默认值表示无）
 }
 impl ProtocolConfig {
     // 实现：协议配置
     pub const LEN: usize = 8 + size_of::<Self>(); // 计算账户长度：8字节锚 + 结构体大小
+    pub fn require_not_paused(&self, family: u8) -> Result<()> {
+        // 函数：检查指令族未暂停
+        require!(self.paused & family == 0, PumpError::ProtocolPaused);
+        Ok(())
+    }
+    pub fn fee_params(&self) -> FeeParams {
+        // 函数：交易费用参数
+        FeeParams {
+            total_trade_fee_bps: self.total_trade_fee_bps,
+            creator_fee_bps_share: self.creator_fee_bps_share,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, Debug)] // 曲线预设
+pub struct CurvePreset {
+    pub virtual_sol_reserves: u64,   // 初始虚拟SOL储备
+    pub virtual_token_reserves: u64, // 初始虚拟代币储备
+    pub total_supply: u64,           // 总供应量
+    pub decimals: u8,                // 小数位数
+    pub curve_supply_bps: u16,       // 曲线可售份额（bps）
+    pub curve_kind: CurveKind,       // 曲线类型
+}
+impl CurvePreset {
+    // 实现：曲线预设
+    pub fn validate(&self, migration_threshold_sol: u64) -> Result<()> {
+        // 函数：检查预设参数；迁移储备必须足以按最终曲线价格配对完成时的全部真实SOL（迁移不增发）
+        let kind_is_valid = match self.curve_kind {
+            CurveKind::ConstantProduct => self.virtual_sol_reserves > 0 && self.virtual_token_reserves > 0,
+            CurveKind::Linear { base_price, .. } => base_price > 0,
+            CurveKind::Exponential {
+                base_price,
+                doubling_interval,
+            } => base_price > 0 && doubling_interval > 0,
+        };
+        require!(
+            kind_is_valid
+                && self.total_supply > 0
+                && self.decimals <= MAX_MINT_DECIMALS
+                && self.curve_supply_bps > 0
+                && self.curve_supply_bps <= 10000,
+            PumpError::InvalidCurvePreset
+        );
+        let migration_reserve = self.total_supply - self.curve_supply(); // 迁移储备代币
+        require!(
+            pumpfun_math::quote::migration_reserve_needed(&self.initial_curve_state(), migration_threshold_sol)
+                .is_ok_and(|needed| needed <= migration_reserve),
+            PumpError::InvalidCurvePreset
+        );
+        Ok(())
+    }
+    pub fn curve_supply(&self) -> u64 {
+        // 函数：曲线可售代币 = 总供应量 * 份额 / 10000
+        ((self.total_supply as u128) * (self.curve_supply_bps as u128) / 10000) as u64
+    }
+    pub fn initial_curve_state(&self) -> CurveState {
+        // 函数：按预设新建曲线的定价状态；恒定乘积使用预设的虚拟储备，线性/指数曲线的虚拟储备只记录已投入SOL和剩余代币
+        let curve_supply = self.curve_supply();
+        let (virtual_sol, virtual_token) = match self.curve_kind {
+            CurveKind::ConstantProduct => (self.virtual_sol_reserves, self.virtual_token_reserves),
+            _ => (0, curve_supply),
+        };
+        CurveState {
+            curve: self.curve_kind.into(),
+            virtual_sol_reserves: virtual_sol,
+            virtual_token_reserves: virtual_token,
+            initial_virtual_token_reserves: virtual_token,
+            real_sol_reserves: 0,
+            real_token_reserves: curve_supply,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)] // 需经时间锁的配置变更
+pub enum ConfigChange {
+    Fees {
+        total_trade_fee_bps: u64,   // 总交易费用bps
+        creator_fee_bps_share: u64, // 创作者在总bps中的份额
+        dex_lp_fee_bps: u64,        // DEX兑换的LP费用bps
+    },
+    Treasury {
+        treasury: Pubkey, // 新国库（执行时必须是系统账户）
+    },
+    CreationFee {
+        creation_fee_sol: u64, // 创建费用
+    },
+    MigrationThreshold {
+        migration_threshold_sol: u64, // 迁移阈值
+    },
+    TimelockDelay {
+        delay_secs: i64, // 新的配置变更延迟（秒）
+    },
+}
+impl ConfigChange {
+    // 实现：配置变更
+    pub fn validate(&self) -> Result<()> {
+        // 函数：检查硬上限
+        match *self {
+            ConfigChange::Fees {
+                total_trade_fee_bps,
+                creator_fee_bps_share,
+                dex_lp_fee_bps,
+            } => {
+                require!(
+                    // 费用不能超过硬上限
+                    total_trade_fee_bps <= MAX_TRADE_FEE_BPS && dex_lp_fee_bps <= MAX_DEX_LP_FEE_BPS,
+                    PumpError::FeeExceedsCap
+                );
+                require!(creator_fee_bps_share <= total_trade_fee_bps, PumpError::CreatorShareExceedsTotal); // 创作者份额不能超过总费用
+            }
+            ConfigChange::Treasury { .. } => {}
+            ConfigChange::CreationFee { creation_fee_sol } => {
+                require!(creation_fee_sol <= MAX_CREATION_FEE_SOL, PumpError::CreationFeeExceedsCap); // 不能超过硬上限
+            }
+            ConfigChange::MigrationThreshold { migration_threshold_sol } => {
+                require!(
+                    // 阈值必须在硬上下限之间
+                    (MIN_MIGRATION_THRESHOLD_SOL..=MAX_MIGRATION_THRESHOLD_SOL).contains(&migration_threshold_sol),
+                    PumpError::InvalidMigrationThreshold
+                );
+            }
+            ConfigChange::TimelockDelay { delay_secs } => {
+                require!(
+                    // 延迟必须在上下限之间
+                    (MIN_CONFIG_TIMELOCK_SECS..=MAX_CONFIG_TIMELOCK_SECS).contains(&delay_secs),
+                    PumpError::InvalidTimelockDelay
+                );
+            }
+        }
+        Ok(())
+    }
+    pub fn apply(&self, config: &mut ProtocolConfig) {
+        // 函数：把变更写入配置，并发出对应字段的更新事件（旧值和新值）
+        match *self {
+            ConfigChange::Fees {
+                total_trade_fee_bps,
+                creator_fee_bps_share,
+                dex_lp_fee_bps,
+            } => {
+                emit!(FeesUpdated {
+                    old_total_trade_fee_bps: config.total_trade_fee_bps,
+                    new_total_trade_fee_bps: total_trade_fee_bps,
+                    old_creator_fee_bps_share: config.creator_fee_bps_share,
+                    new_creator_fee_bps_share: creator_fee_bps_share,
+                    old_dex_lp_fee_bps: config.dex_lp_fee_bps,
+                    new_dex_lp_fee_bps: dex_lp_fee_bps
+                });
+                config.total_trade_fee_bps = total_trade_fee_bps;
+                config.creator_fee_bps_share = creator_fee_bps_share;
+                config.dex_lp_fee_bps = dex_lp_fee_bps;
+            }
+            ConfigChange::Treasury { treasury } => {
+                emit!(TreasuryUpdated {
+                    old_treasury: config.treasury,
+                    new_treasury: treasury
+                });
+                config.treasury = treasury;
+            }
+            ConfigChange::CreationFee { creation_fee_sol } => {
+                emit!(CreationFeeUpdated {
+                    old_creation_fee_sol: config.creation_fee_sol,
+                    new_creation_fee_sol: creation_fee_sol
+                });
+                config.creation_fee_sol = creation_fee_sol;
+            }
+            ConfigChange::MigrationThreshold { migration_threshold_sol } => {
+                emit!(MigrationThresholdUpdated {
+                    old_migration_threshold_sol: config.migration_threshold_sol,
+                    new_migration_threshold_sol: migration_threshold_sol
+                });
+                config.migration_threshold_sol = migration_threshold_sol;
+            }
+            ConfigChange::TimelockDelay { delay_secs } => {
+                emit!(TimelockDelayUpdated {
+                    old_delay_secs: config.config_timelock_secs,
+                    new_delay_secs: delay_secs
+                });
+                config.config_timelock_secs = delay_secs;
+            }
+        }
+    }
+}
+
+#[account] // Anchor宏：排队中的配置变更账户
+#[derive(Debug)]
+pub struct PendingConfigChange {
+    // 结构：排队中的配置变更
+    pub id: u64,             // 变更编号
+    pub change: ConfigChange, // 变更内容
+    pub queued_at: i64,      // 排队时间
+    pub executable_at: i64,  // 最早可执行时间
+    pub bump: u8,            // bump
+}
+impl PendingConfigChange {
+    // 实现：排队中的配置变更
+    pub const LEN: usize = 8 + size_of::<Self>(); // 计算长度（枚举按最大变体计算，足够容纳任何变更）
+}
+
+#[account] // Anchor宏：治理委员会账户
+#[derive(Debug)]
+pub struct GovernanceCouncil {
+    // 结构：M-of-N 治理委员会；委员会权限 PDA（council_authority）作为治理权限签名
+    pub members: [Pubkey; MAX_COUNCIL_MEMBERS], // 成员（前 member_count 个有效）
+    pub member_count: u8,                       // 成员数量
+    pub threshold: u8,                          // 执行提案所需的批准数
+    pub generation: u32,                        // 成员变更次数，旧提案随之失效
+    pub next_proposal_id: u64,                  // 下一个提案编号
+    pub bump: u8,                               // bump
+    pub authority_bump: u8,                     // 委员会权限 PDA 的bump
+}
+impl GovernanceCouncil {
+    // 实现：治理委员会
+    pub const LEN: usize = 8 + size_of::<Self>(); // 计算长度
+    pub fn validate_members(members: &[Pubkey], threshold: u8) -> Result<()> {
+        // 函数：成员非空、不重复、不超过上限，门槛在 1 和成员数之间
+        let unique = members
+            .iter()
+            .enumerate()
+            .all(|(i, member)| *member != Pubkey::default() && !members[..i].contains(member));
+        require!(
+            !members.is_empty()
+                && members.len() <= MAX_COUNCIL_MEMBERS
+                && unique
+                && threshold >= 1
+                && threshold as usize <= members.len(),
+            PumpError::InvalidCouncil
+        );
+        Ok(())
+    }
+    pub fn set_members(&mut self, members: &[Pubkey], threshold: u8) -> Result<()> {
+        // 函数：检查并写入成员和门槛
+        Self::validate_members(members, threshold)?;
+        self.members = [Pubkey::default(); MAX_COUNCIL_MEMBERS];
+        self.members[..members.len()].copy_from_slice(members);
+        self.member_count = members.len() as u8;
+        self.threshold = threshold;
+        Ok(())
+    }
+    pub fn members(&self) -> &[Pubkey] {
+        // 函数：有效成员
+        &self.members[..self.member_count as usize]
+    }
+    pub fn member_index(&self, key: &Pubkey) -> Result<usize> {
+        // 函数：成员序号（即批准位图中的位）
+        self.members()
+            .iter()
+            .position(|member| member == key)
+            .ok_or_else(|| error!(PumpError::NotCouncilMember))
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)] // 委员会提案内容
+#[allow(clippy::large_enum_variant)] // 提案账户本来就按最大变体分配空间，装箱没有收益
+pub enum CouncilAction {
+    QueueConfigChange {
+        change: ConfigChange, // 排队配置变更
+    },
+    ExecuteConfigChange {
+        id: u64, // 执行已排队的配置变更
+    },
+    CancelConfigChange {
+        id: u64, // 撤回已排队的配置变更
+    },
+    SetPaused {
+        paused: u8, // 新的暂停位
+    },
+    SetGuardian {
+        guardian: Pubkey, // 新守护者
+    },
+    FreezeCurve {
+        mint: Pubkey, // 曲线的铸币
+        frozen: bool, // 冻结或解冻
+    },
+    EmergencyUnwind {
+        mint: Pubkey,    // 曲线的铸币
+        unwinding: bool, // 开启或关闭紧急清退
+    },
+    SetCurvePreset {
+        index: u8,           // 预设索引
+        preset: CurvePreset, // 曲线预设
+    },
+    ProposeGovernance {
+        new_authority: Pubkey, // 提名新的治理权限
+    },
+    AcceptGovernance,         // 委员会接受治理权限
+    CancelGovernanceProposal, // 撤回治理权限提名
+    UpdateCouncil {
+        members: [Pubkey; MAX_COUNCIL_MEMBERS], // 新成员（前 member_count 个有效）
+        member_count: u8,                       // 新成员数量
+        threshold: u8,                          // 新门槛
+    },
+}
+impl CouncilAction {
+    // 实现：委员会提案内容
+    pub fn validate(&self) -> Result<()> {
+        // 函数：成员变更在提案时即检查
+        if let CouncilAction::UpdateCouncil {
+            members,
+            member_count,
+            threshold,
+        } = self
+        {
+            require!(*member_count as usize <= MAX_COUNCIL_MEMBERS, PumpError::InvalidCouncil);
+            GovernanceCouncil::validate_members(&members[..*member_count as usize], *threshold)?;
+        }
+        Ok(())
+    }
+    pub fn instruction_data(&self) -> Vec<u8> {
+        // 函数：对应治理指令的指令数据（成员变更不经过指令，返回空）
+        match *self {
+            CouncilAction::QueueConfigChange { change } => instruction::QueueConfigChange { change }.data(),
+            CouncilAction::ExecuteConfigChange { id } => instruction::ExecuteConfigChange { id }.data(),
+            CouncilAction::CancelConfigChange { id } => instruction::CancelConfigChange { id }.data(),
+            CouncilAction::SetPaused { paused } => instruction::SetPaused { paused }.data(),
+            CouncilAction::SetGuardian { guardian } => instruction::SetGuardian { guardian }.data(),
+            CouncilAction::FreezeCurve { mint, frozen } => instruction::FreezeCurve { mint, frozen }.data(),
+            CouncilAction::EmergencyUnwind { mint, unwinding } => instruction::EmergencyUnwind { mint, unwinding }.data(),
+            CouncilAction::SetCurvePreset { index, preset } => instruction::SetCurvePreset { index, preset }.data(),
+            CouncilAction::ProposeGovernance { new_authority } => {
+                instruction::ProposeGovernance { new_authority }.data()
+            }
+            CouncilAction::AcceptGovernance => instruction::AcceptGovernance {}.data(),
+            CouncilAction::CancelGovernanceProposal => instruction::CancelGovernanceProposal {}.data(),
+            CouncilAction::UpdateCouncil { .. } => Vec::new(),
+        }
+    }
+}
+
+#[account] // Anchor宏：委员会提案账户
+#[derive(Debug)]
+pub struct CouncilProposal {
+    // 结构：委员会提案
+    pub id: u64,               // 提案编号
+    pub proposer: Pubkey,      // 提案人（收回租金）
+    pub action: CouncilAction, // 提案内容
+    pub approvals: u16,        // 批准位图（按成员序号）
+    pub generation: u32,       // 提案时的委员会成员版本
+    pub bump: u8,              // bump
+}
+impl CouncilProposal {
+    // 实现：委员会提案
+    pub const LEN: usize = 8 + size_of::<Self>(); // 计算长度（枚举按最大变体计算）
+    pub fn approval_count(&self) -> u8 {
+        // 函数：批准数
+        self.approvals.count_ones() as u8
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, Debug)] // 曲线类型
+pub enum CurveKind {
+    #[default]
+    ConstantProduct, // 虚拟储备恒定乘积 x * y = k
+    Linear {
+        base_price: u64, // 起始价格（lamports/最小代币单位 * PRICE_SCALE）
+        slope: u64,      // 每售出一个最小代币单位的价格增量（同上单位）
+    },
+    Exponential {
+        base_price: u64,        // 起始价格（lamports/最小代币单位 * PRICE_SCALE）
+        doubling_interval: u64, // 每售出多少最小代币单位价格翻倍（区间内线性插值）
+    },
+}
+
+impl From<CurveKind> for Curve {
+    fn from(kind: CurveKind) -> Self {
+        // 转换：链上曲线类型 -> pumpfun-math 曲线
+        match kind {
+            CurveKind::ConstantProduct => Curve::ConstantProduct,
+            CurveKind::Linear { base_price, slope } => Curve::Linear { base_price, slope },
+            CurveKind::Exponential {
+                base_price,
+                doubling_interval,
+            } => Curve::Exponential {
+                base_price,
+                doubling_interval,
+            },
+        }
+    }
 }
 
 #[account] // Anchor宏：绑定曲线账户
+#[derive(Debug)]
 pub struct BondingCurve {
     // 结构：绑定曲线
     pub creator: Pubkey,             // 创作者
@@ -670,57 +2519,277 @@ pub struct BondingCurve {
     pub dex_pool: Pubkey,            // DEX池
     pub creator_fees_owed: u64,      // 欠创作者费用
     pub bump: u8,                    // bump
+    pub initial_virtual_sol_reserves: u64,   // 创建时的虚拟SOL储备
+    pub initial_virtual_token_reserves: u64, // 创建时的虚拟代币储备
+    pub total_supply: u64,                   // 总供应量
+    pub decimals: u8,                        // 小数位数
+    pub real_token_reserves: u64,            // 曲线剩余可售代币
+    pub curve_kind: CurveKind,               // 曲线类型
+    pub is_frozen: bool,                     // 治理冻结：停止交易和迁移
+    pub is_unwinding: bool,                  // 紧急清退：停止交易，持有人按比例赎回
+    pub has_creator_vesting: bool,           // 创作者费用按 CreatorVesting 计划释放
+    pub pending_creator: Pubkey,             // 被提名、尚未接受的新创作者
+    pub creator_fee_recipient: Pubkey,       // 创作者费用接收者（默认创作者本人，开启分账后为分账账户）
+    pub has_fee_split: bool,                 // 创作者费用按 FeeSplit 比例分给多个接收者
+    pub redeemed_tokens: u64,                // 紧急清退中累计兑付的代币（非零后不能恢复交易）
+    pub redeemed_sol: u64,                   // 紧急清退中累计兑付的SOL
 }
 impl BondingCurve {
     // 实现：绑定曲线
     pub const LEN: usize = 8 + size_of::<Self>(); // 计算长度
-    pub fn get_buy_output(&self, sol_in: u64) -> u64 {
-        // 函数：计算购买输出
-        let x = self.virtual_sol_reserves as u128; // x = 虚拟SOL
-        let y = self.virtual_token_reserves as u128; // y = 虚拟代币
-        let k = x.checked_mul(y).unwrap(); // k = x * y
-        let new_x = x.checked_add(sol_in as u128).unwrap(); // 新x = x + 输入
-        (y.checked_sub(k.checked_div(new_x).unwrap()).unwrap()) as u64 // 输出 = y - (k / 新x)
-    }
-    pub fn get_sell_output(&self, tokens_in: u64) -> u64 {
-        // 函数：计算出售输出
-        let x = self.virtual_sol_reserves as u128;
-        let y = self.virtual_token_reserves as u128;
-        if x == 0 || y == 0 {
-            // 如果储备为0，返回0
-            return 0;
-        }
-        let k = x.checked_mul(y).unwrap();
-        let new_y = y.checked_add(tokens_in as u128).unwrap();
-        if new_y == 0 {
-            // 如果新y为0，返回x
-            return x as u64;
-        }
-        let new_x = k
-            .checked_add(new_y)
-            .unwrap()
-            .checked_sub(1)
-            .unwrap()
-            .checked_div(new_y)
-            .unwrap();
-        (x.saturating_sub(new_x)) as u64 // 输出 = x - 新x
-    }
-    pub fn update_buy_state(&mut self, sol_in: u64, tokens_out: u64) {
+    pub fn curve_state(&self) -> CurveState {
+        // 函数：定价所需的曲线状态，交给 pumpfun-math 计算
+        CurveState {
+            curve: self.curve_kind.into(),
+            virtual_sol_reserves: self.virtual_sol_reserves,
+            virtual_token_reserves: self.virtual_token_reserves,
+            initial_virtual_token_reserves: self.initial_virtual_token_reserves,
+            real_sol_reserves: self.real_sol_reserves,
+            real_token_reserves: self.real_token_reserves,
+        }
+    }
+    fn apply_state(&mut self, state: &CurveState) {
+        // 函数：写回交易后的储备
+        self.virtual_sol_reserves = state.virtual_sol_reserves;
+        self.virtual_token_reserves = state.virtual_token_reserves;
+        self.real_sol_reserves = state.real_sol_reserves;
+        self.real_token_reserves = state.real_token_reserves;
+    }
+    pub fn tokens_sold(&self) -> Result<u64> {
+        // 函数：曲线已售出的代币（线性/指数曲线的价格自变量）
+        Ok(self.curve_state().tokens_sold().map_err(PumpError::from)?)
+    }
+    pub fn require_tradable(&self) -> Result<()> {
+        // 函数：检查曲线未被冻结、未处于紧急清退
+        require!(!self.is_frozen && !self.is_unwinding, PumpError::CurveFrozen);
+        Ok(())
+    }
+    pub fn get_redeem_output(&self, tokens_in: u64) -> Result<u64> {
+        // 函数：紧急清退时按比例兑付的SOL，向下取整（偏向曲线）
+        Ok(self.curve_state().redeem_output(tokens_in).map_err(PumpError::from)?)
+    }
+    pub fn get_buy_output(&self, sol_in: u64) -> Result<u64> {
+        // 函数：计算购买输出，按曲线类型分派，均向下取整（偏向曲线）
+        Ok(self.curve_state().buy_output(sol_in).map_err(PumpError::from)?)
+    }
+    pub fn get_sell_output(&self, tokens_in: u64) -> Result<u64> {
+        // 函数：计算出售输出，按曲线类型分派，均向下取整（偏向曲线）
+        Ok(self.curve_state().sell_output(tokens_in).map_err(PumpError::from)?)
+    }
+    pub fn get_buy_cost(&self, tokens_out: u64) -> Result<u64> {
+        // 函数：买入精确数量代币所需的SOL（不含费用），向上取整（偏向曲线）
+        Ok(self.curve_state().buy_cost(tokens_out).map_err(PumpError::from)?)
+    }
+    pub fn get_sell_input(&self, sol_out: u64) -> Result<u64> {
+        // 函数：卖出得到至少 sol_out（不含费用）所需的最少代币，向上取整（偏向曲线）
+        Ok(self.curve_state().sell_input(sol_out).map_err(PumpError::from)?)
+    }
+    pub fn spot_price(&self) -> Result<(u128, u128)> {
+        // 函数：当前边际价格（lamports/最小代币单位），返回 (分子, 分母)
+        Ok(self.curve_state().spot_price().map_err(PumpError::from)?)
+    }
+    pub fn quote_buy(&self, config: &ProtocolConfig, total_sol_in: u64) -> Result<BuyQuote> {
+        // 函数：buy 的报价（链上和链下共用同一实现）
+        Ok(pumpfun_math::quote::quote_buy(
+            &self.curve_state(),
+            &config.fee_params(),
+            config.migration_threshold_sol,
+            total_sol_in,
+        )
+        .map_err(PumpError::from)?)
+    }
+    pub fn quote_buy_exact_tokens_out(&self, config: &ProtocolConfig, tokens_out: u64) -> Result<BuyQuote> {
+        // 函数：buy_exact_tokens_out 的报价
+        Ok(
+            pumpfun_math::quote::quote_buy_exact_tokens_out(&self.curve_state(), &config.fee_params(), tokens_out)
+                .map_err(PumpError::from)?,
+        )
+    }
+    pub fn quote_sell(&self, config: &ProtocolConfig, tokens_in: u64) -> Result<SellQuote> {
+        // 函数：sell 的报价
+        Ok(pumpfun_math::quote::quote_sell(&self.curve_state(), &config.fee_params(), tokens_in).map_err(PumpError::from)?)
+    }
+    pub fn quote_sell_for_exact_sol(&self, config: &ProtocolConfig, sol_out: u64) -> Result<SellQuote> {
+        // 函数：sell_for_exact_sol 的报价
+        Ok(
+            pumpfun_math::quote::quote_sell_for_exact_sol(&self.curve_state(), &config.fee_params(), sol_out)
+                .map_err(PumpError::from)?,
+        )
+    }
+    pub fn quote_migration(&self, reserve_tokens: u64) -> Result<MigrationQuote> {
+        // 函数：complete_and_migrate 的报价，reserve_tokens 为迁移金库余额
+        Ok(pumpfun_math::quote::quote_migration(&self.curve_state(), reserve_tokens).map_err(PumpError::from)?)
+    }
+    pub fn is_migrated(&self) -> bool {
+        // 函数：是否已迁移到DEX（迁移时记录dex_pool）
+        self.dex_pool != Pubkey::default()
+    }
+    pub fn check_lamports_invariant(&self, curve_info: &AccountInfo) -> Result<()> {
+        // 函数：检查曲线账户lamports >= 真实SOL储备 + 欠创作者费用 + 租金
+        let min_rent = Rent::get()?.minimum_balance(curve_info.data_len()); // 租金豁免最小余额
+        let required = self
+            .real_sol_reserves
+            .saturating_add(self.creator_fees_owed)
+            .saturating_add(min_rent); // 所需最小lamports
+        require!(
+            curve_info.lamports() >= required,
+            PumpError::LamportInvariantViolated
+        );
+        Ok(())
+    }
+    pub fn sweep_creator_fees(&mut self, curve_info: &AccountInfo, to: &AccountInfo) -> Result<u64> {
+        // 函数：把欠创作者费用全部从曲线账户转入 to（释放账户或分账账户），返回转出的金额
+        let amount = self.creator_fees_owed; // 待转出的费用
+        if amount > 0 {
+            self.creator_fees_owed = 0; // 清零欠费用
+            **curve_info.try_borrow_mut_lamports()? -= amount; // 从曲线扣除
+            **to.try_borrow_mut_lamports()? += amount; // 转入目标账户
+            self.check_lamports_invariant(curve_info)?; // 检查曲线lamports不变量
+        }
+        Ok(amount)
+    }
+    pub fn update_buy_state(&mut self, sol_in: u64, tokens_out: u64) -> Result<()> {
         // 函数：更新购买状态
-        self.real_sol_reserves = self.real_sol_reserves.checked_add(sol_in).unwrap(); // 更新真实SOL
-        self.virtual_sol_reserves = self.virtual_sol_reserves.checked_add(sol_in).unwrap(); // 更新虚拟SOL
-        self.virtual_token_reserves = self.virtual_token_reserves.checked_sub(tokens_out).unwrap();
-        // 更新虚拟代币
+        let mut state = self.curve_state();
+        state.apply_buy(sol_in, tokens_out).map_err(PumpError::from)?;
+        self.apply_state(&state);
+        Ok(())
     }
-    pub fn update_sell_state(&mut self, tokens_in: u64, sol_out: u64) {
+    pub fn update_sell_state(&mut self, tokens_in: u64, sol_out: u64) -> Result<()> {
         // 函数：更新出售状态
-        self.real_sol_reserves = self.real_sol_reserves.checked_sub(sol_out).unwrap();
-        self.virtual_sol_reserves = self.virtual_sol_reserves.checked_sub(sol_out).unwrap();
-        self.virtual_token_reserves = self.virtual_token_reserves.checked_add(tokens_in).unwrap();
+        let mut state = self.curve_state();
+        state.apply_sell(tokens_in, sol_out).map_err(PumpError::from)?;
+        self.apply_state(&state);
+        Ok(())
+    }
+}
+
+#[account] // Anchor宏：创作者费用释放账户（lamports = 租金 + 已汇入未领取的费用）
+#[derive(Debug)]
+pub struct CreatorVesting {
+    // 结构：创作者费用释放计划
+    pub token_mint: Pubkey,  // 曲线的铸币
+    pub start_time: i64,     // 开始释放时间
+    pub cliff_secs: i64,     // 悬崖期：此前不可领取
+    pub duration_secs: i64,  // 线性释放期
+    pub total_accrued: u64,  // 累计汇入的费用
+    pub total_claimed: u64,  // 累计领取的费用
+    pub bump: u8,            // bump
+}
+impl CreatorVesting {
+    // 实现：创作者费用释放计划
+    pub const LEN: usize = 8 + size_of::<Self>(); // 计算长度
+    pub fn vested(&self, now: i64) -> Result<u64> {
+        // 函数：截至 now 已释放的费用：悬崖期前为 0，之后按经过时间线性释放累计汇入的费用
+        let elapsed = now.saturating_sub(self.start_time); // 已经过时间
+        if elapsed < self.cliff_secs {
+            return Ok(0);
+        }
+        if elapsed >= self.duration_secs {
+            return Ok(self.total_accrued);
+        }
+        let vested = self.total_accrued as u128 * elapsed as u128 / self.duration_secs as u128; // 向下取整
+        Ok(u64::try_from(vested).map_err(|_| PumpError::MathOverflow)?)
+    }
+    pub fn claimable(&self, now: i64) -> Result<u64> {
+        // 函数：已释放未领取的费用
+        Ok(self.vested(now)?.saturating_sub(self.total_claimed))
+    }
+    pub fn unclaimed(&self) -> u64 {
+        // 函数：已汇入未领取的费用（含尚未释放的部分）
+        self.total_accrued - self.total_claimed
+    }
+}
+
+#[account] // Anchor宏：创作者费用分账账户（lamports = 租金 + 尚未付出的费用）
+#[derive(Debug)]
+pub struct FeeSplit {
+    // 结构：创作者费用分账；按累计收到的费用计算份额，接收者领取的先后不影响各自所得
+    pub token_mint: Pubkey,                             // 曲线的铸币
+    pub recipients: [Pubkey; MAX_FEE_SPLIT_RECIPIENTS], // 接收者（前 recipient_count 个有效）
+    pub shares_bps: [u16; MAX_FEE_SPLIT_RECIPIENTS],    // 各接收者的比例（基点，合计 10000）
+    pub claimed: [u64; MAX_FEE_SPLIT_RECIPIENTS],       // 各接收者按当前比例已领取的费用
+    pub recipient_count: u8,                            // 接收者数量
+    pub total_claimed: u64,                             // 按当前比例累计付出的费用
+    pub bump: u8,                                       // bump
+}
+impl FeeSplit {
+    // 实现：创作者费用分账
+    pub const LEN: usize = 8 + size_of::<Self>(); // 计算长度
+    pub fn set_recipients(&mut self, recipients: &[Pubkey], shares_bps: &[u16]) -> Result<()> {
+        // 函数：检查并写入接收者和比例（非空、不重复、不超过上限、比例为正且合计 10000），重新开始记账
+        let unique = recipients
+            .iter()
+            .enumerate()
+            .all(|(i, recipient)| *recipient != Pubkey::default() && !recipients[..i].contains(recipient));
+        let total_bps: u128 = shares_bps.iter().map(|bps| *bps as u128).sum();
+        require!(
+            !recipients.is_empty()
+                && recipients.len() <= MAX_FEE_SPLIT_RECIPIENTS
+                && recipients.len() == shares_bps.len()
+                && unique
+                && shares_bps.iter().all(|bps| *bps > 0)
+                && total_bps == BPS_DENOMINATOR,
+            PumpError::InvalidFeeSplit
+        );
+        self.recipients = [Pubkey::default(); MAX_FEE_SPLIT_RECIPIENTS];
+        self.recipients[..recipients.len()].copy_from_slice(recipients);
+        self.shares_bps = [0; MAX_FEE_SPLIT_RECIPIENTS];
+        self.shares_bps[..shares_bps.len()].copy_from_slice(shares_bps);
+        self.claimed = [0; MAX_FEE_SPLIT_RECIPIENTS];
+        self.recipient_count = recipients.len() as u8;
+        self.total_claimed = 0;
+        Ok(())
+    }
+    pub fn recipients(&self) -> &[Pubkey] {
+        // 函数：有效接收者
+        &self.recipients[..self.recipient_count as usize]
+    }
+    pub fn balance(fee_split_info: &AccountInfo) -> Result<u64> {
+        // 函数：分账账户中尚未付出的费用（扣除租金）
+        let min_rent = Rent::get()?.minimum_balance(fee_split_info.data_len());
+        Ok(fee_split_info.lamports().saturating_sub(min_rent))
+    }
+    pub fn owed(&self, index: usize, balance: u64) -> Result<u64> {
+        // 函数：第 index 个接收者可领取的份额 = 累计收到的费用（余额 + 已付出）* 比例 - 其已领取部分
+        let received = balance as u128 + self.total_claimed as u128; // 累计收到的费用
+        let entitled = received * self.shares_bps[index] as u128 / BPS_DENOMINATOR; // 向下取整，零头留到以后分配
+        let entitled = u64::try_from(entitled).map_err(|_| PumpError::MathOverflow)?;
+        Ok(entitled.saturating_sub(self.claimed[index]))
+    }
+    pub fn pay(&mut self, index: usize, fee_split_info: &AccountInfo, recipient_info: &AccountInfo) -> Result<u64> {
+        // 函数：把第 index 个接收者的份额从分账账户转给他，返回金额；金额为正时发出领取事件
+        let amount = self.owed(index, FeeSplit::balance(fee_split_info)?)?; // 本次付出金额
+        if amount > 0 {
+            self.claimed[index] += amount; // 份额之和不超过累计收到的费用，不会溢出
+            self.total_claimed += amount;
+            **fee_split_info.try_borrow_mut_lamports()? -= amount; // 从分账账户扣除
+            **recipient_info.try_borrow_mut_lamports()? += amount; // 转移到接收者
+            emit!(FeeSplitClaimed {
+                // 发出事件：分账份额领取
+                mint: self.token_mint,
+                recipient: recipient_info.key(),
+                amount
+            });
+        }
+        Ok(amount)
+    }
+    pub fn distribute(&mut self, fee_split_info: &AccountInfo, recipient_infos: &[AccountInfo]) -> Result<()> {
+        // 函数：给全部接收者付清份额；账户必须按接收者顺序一一传入
+        require!(
+            recipient_infos.len() == self.recipients().len()
+                && recipient_infos.iter().zip(self.recipients()).all(|(info, key)| info.key == key),
+            PumpError::FeeSplitRecipientMismatch
+        );
+        for (index, recipient_info) in recipient_infos.iter().enumerate() {
+            self.pay(index, fee_split_info, recipient_info)?;
+        }
+        Ok(())
     }
 }
 
 #[account] // Anchor宏：DEX池账户
+#[derive(Debug)]
 pub struct DexPool {
     // 结构：DEX池
     pub token_mint: Pubkey,  // 代币铸币
@@ -729,30 +2798,220 @@ pub struct DexPool {
     pub sol_reserves: u64,   // SOL储备
     pub token_reserves: u64, // 代币储备
     pub bump: u8,            // bump
+    pub lp_supply: u64,      // LP代币总供应量（含锁定部分）
 }
 impl DexPool {
     // 实现：DEX池
     pub const LEN: usize = 8 + size_of::<Self>(); // 计算长度
+    pub fn sol_vault_balance(sol_vault: &AccountInfo) -> Result<u64> {
+        // 函数：DEX SOL金库中可交易的SOL（扣除租金）
+        let min_rent = Rent::get()?.minimum_balance(sol_vault.data_len());
+        Ok(sol_vault.lamports().saturating_sub(min_rent))
+    }
+    pub fn sync_reserves(
+        // 函数：按金库真实余额同步池子储备
+        &mut self,
+        sol_vault: &AccountInfo,
+        token_vault: &mut InterfaceAccount<TokenAccount>,
+    ) -> Result<()> {
+        token_vault.reload()?; // CPI之后重新加载代币金库
+        self.sol_reserves = DexPool::sol_vault_balance(sol_vault)?;
+        self.token_reserves = token_vault.amount;
+        Ok(())
+    }
+}
+
+// INSTRUCTION CONTEXTS  // 指令上下文
+#[derive(Accounts)] // 初始化配置上下文
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>, // 权限签名者
+    #[account(init, payer = authority, seeds = [b"protocol_config"], bump, space = ProtocolConfig::LEN)]
+    pub protocol_config: Account<'info, ProtocolConfig>, // 配置账户
+    #[account(mut)]
+    pub treasury: SystemAccount<'info>, // 国库
+    pub system_program: Program<'info, System>, // 系统程序
+}
+
+#[derive(Accounts)] // 立即生效的配置上下文（暂停、守护者）
+pub struct SetConfig<'info> {
+    pub governance_authority: Signer<'info>, // 治理签名者
+    #[account(mut, seeds = [b"protocol_config"], bump = protocol_config.bump, has_one = governance_authority)]
+    pub protocol_config: Account<'info, ProtocolConfig>, // 配置账户
+}
+
+#[derive(Accounts)] // 单条曲线的治理上下文（冻结、紧急清退）
+#[instruction(mint: Pubkey)]
+pub struct CurveGovernance<'info> {
+    pub governance_authority: Signer<'info>, // 治理签名者
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump, has_one = governance_authority)]
+    pub protocol_config: Account<'info, ProtocolConfig>, // 配置账户
+    #[account(mut, seeds = [b"bonding_curve", mint.as_ref()], bump = bonding_curve.bump)]
+    pub bonding_curve: Account<'info, BondingCurve>, // 曲线（由指令参数中的铸币锁定）
+}
+
+#[derive(Accounts)] // 守护者暂停上下文
+pub struct GuardianPause<'info> {
+    pub guardian: Signer<'info>, // 守护者签名者
+    #[account(
+        mut,
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+        has_one = guardian @ PumpError::NotGuardian
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>, // 配置账户
+}
+
+#[derive(Accounts)] // 排队配置变更上下文
+pub struct QueueConfigChange<'info> {
+    #[account(mut)]
+    pub governance_authority: Signer<'info>, // 治理签名者（支付待执行账户租金）
+    #[account(mut, seeds = [b"protocol_config"], bump = protocol_config.bump, has_one = governance_authority)]
+    pub protocol_config: Account<'info, ProtocolConfig>, // 配置账户
+    #[account(
+        init,
+        payer = governance_authority,
+        space = PendingConfigChange::LEN,
+        seeds = [b"pending_config_change", protocol_config.next_config_change_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub pending_change: Account<'info, PendingConfigChange>, // 待执行变更
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)] // 执行配置变更上下文
+#[instruction(id: u64)]
+pub struct ExecuteConfigChange<'info> {
+    #[account(mut)]
+    pub governance_authority: Signer<'info>, // 治理签名者（收回租金）
+    #[account(mut, seeds = [b"protocol_config"], bump = protocol_config.bump, has_one = governance_authority)]
+    pub protocol_config: Account<'info, ProtocolConfig>, // 配置账户
+    #[account(
+        mut,
+        close = governance_authority,
+        seeds = [b"pending_config_change", id.to_le_bytes().as_ref()],
+        bump = pending_change.bump
+    )]
+    pub pending_change: Account<'info, PendingConfigChange>, // 待执行变更
+    pub new_treasury: Option<SystemAccount<'info>>, // 新国库（仅国库变更需要）
+}
+
+#[derive(Accounts)] // 撤回配置变更上下文
+#[instruction(id: u64)]
+pub struct CancelConfigChange<'info> {
+    #[account(mut)]
+    pub governance_authority: Signer<'info>, // 治理签名者（收回租金）
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump, has_one = governance_authority)]
+    pub protocol_config: Account<'info, ProtocolConfig>, // 配置账户
+    #[account(
+        mut,
+        close = governance_authority,
+        seeds = [b"pending_config_change", id.to_le_bytes().as_ref()],
+        bump = pending_change.bump
+    )]
+    pub pending_change: Account<'info, PendingConfigChange>, // 待撤回变更
+}
+
+#[derive(Accounts)] // 设置曲线预设上下文
+pub struct SetCurvePreset<'info> {
+    pub governance_authority: Signer<'info>, // 治理签名者
+    #[account(mut, seeds = [b"protocol_config"], bump = protocol_config.bump, has_one = governance_authority)]
+    pub protocol_config: Account<'info, ProtocolConfig>, // 配置账户
+}
+
+#[derive(Accounts)] // 治理权限提名上下文（提名与撤回共用）
+pub struct GovernanceProposal<'info> {
+    pub governance_authority: Signer<'info>, // 当前治理签名者
+    #[account(mut, seeds = [b"protocol_config"], bump = protocol_config.bump, has_one = governance_authority)]
+    pub protocol_config: Account<'info, ProtocolConfig>, // 配置账户
+}
+
+#[derive(Accounts)] // 接受治理权限上下文
+pub struct AcceptGovernance<'info> {
+    pub pending_governance: Signer<'info>, // 被提名的新治理权限
+    #[account(
+        mut,
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+        has_one = pending_governance @ PumpError::NotPendingGovernance
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>, // 配置账户
 }
 
-// INSTRUCTION CONTEXTS  // 指令上下文
-#[derive(Accounts)] // 初始化配置上下文
-pub struct InitializeConfig<'info> {
+#[derive(Accounts)] // 创建治理委员会上下文
+pub struct CreateGovernanceCouncil<'info> {
     #[account(mut)]
-    pub authority: Signer<'info>, // 权限签名者
-    #[account(init, payer = authority, seeds = [b"protocol_config"], bump, space = ProtocolConfig::LEN)]
+    pub governance_authority: Signer<'info>, // 当前治理签名者（支付委员会账户租金）
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump, has_one = governance_authority)]
     pub protocol_config: Account<'info, ProtocolConfig>, // 配置账户
+    #[account(init, payer = governance_authority, space = GovernanceCouncil::LEN, seeds = [b"governance_council"], bump)]
+    pub council: Account<'info, GovernanceCouncil>, // 委员会
+    #[account(seeds = [b"council_authority"], bump)]
+    pub council_authority: SystemAccount<'info>, // 委员会权限 PDA
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)] // 创建委员会提案上下文
+pub struct CreateCouncilProposal<'info> {
     #[account(mut)]
-    pub treasury: SystemAccount<'info>, // 国库
-    pub system_program: Program<'info, System>, // 系统程序
+    pub proposer: Signer<'info>, // 提案人（成员，支付提案账户租金）
+    #[account(mut, seeds = [b"governance_council"], bump = council.bump)]
+    pub council: Account<'info, GovernanceCouncil>, // 委员会
+    #[account(
+        init,
+        payer = proposer,
+        space = CouncilProposal::LEN,
+        seeds = [b"council_proposal", council.next_proposal_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, CouncilProposal>, // 提案
+    pub system_program: Program<'info, System>,
 }
 
-#[derive(Accounts)] // 更新配置上下文
-#[instruction(new_config: ProtocolConfigV1)]
-pub struct UpdateConfig<'info> {
-    pub governance_authority: Signer<'info>, // 治理签名者
-    #[account(mut, seeds = [b"protocol_config"], bump, has_one = governance_authority)]
-    pub protocol_config: Account<'info, ProtocolConfig>, // 配置账户
+#[derive(Accounts)] // 批准委员会提案上下文
+#[instruction(id: u64)]
+pub struct ApproveCouncilProposal<'info> {
+    pub member: Signer<'info>, // 批准的成员
+    #[account(seeds = [b"governance_council"], bump = council.bump)]
+    pub council: Account<'info, GovernanceCouncil>, // 委员会
+    #[account(mut, seeds = [b"council_proposal", id.to_le_bytes().as_ref()], bump = proposal.bump)]
+    pub proposal: Account<'info, CouncilProposal>, // 提案
+}
+
+#[derive(Accounts)] // 执行委员会提案上下文（剩余账户为治理指令的账户）
+#[instruction(id: u64)]
+pub struct ExecuteCouncilProposal<'info> {
+    pub member: Signer<'info>, // 执行的成员
+    #[account(mut, seeds = [b"governance_council"], bump = council.bump)]
+    pub council: Account<'info, GovernanceCouncil>, // 委员会
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [b"council_proposal", id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+        has_one = proposer
+    )]
+    pub proposal: Account<'info, CouncilProposal>, // 提案
+    #[account(mut)]
+    pub proposer: SystemAccount<'info>, // 提案人（收回租金）
+    #[account(mut, seeds = [b"council_authority"], bump = council.authority_bump)]
+    pub council_authority: SystemAccount<'info>, // 委员会权限 PDA（签名治理指令，支付其租金）
+    pub pumpfun_program: Program<'info, program::Pumpfun>, // 本程序
+}
+
+#[derive(Accounts)] // 撤回委员会提案上下文
+#[instruction(id: u64)]
+pub struct CancelCouncilProposal<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>, // 提案人（收回租金）
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [b"council_proposal", id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+        has_one = proposer
+    )]
+    pub proposal: Account<'info, CouncilProposal>, // 提案
 }
 
 #[derive(Accounts)] // 创建上下文
@@ -763,14 +3022,9 @@ pub struct Create<'info> {
     pub protocol_config: Account<'info, ProtocolConfig>, // 配置
     #[account(mut)]
     pub treasury: SystemAccount<'info>, // 国库
-    #[account(
-        init,
-        payer = creator,
-        mint::decimals = MINT_DECIMALS,
-        mint::authority = creator,
-        mint::token_program = token_program
-    )]
-    pub token_mint: InterfaceAccount<'info, Mint>, // 铸币
+    // 铸币账户需要带元数据扩展，因此在指令中手动创建和初始化
+    #[account(mut)]
+    pub token_mint: Signer<'info>, // 铸币
     #[account(
         init,
         payer = creator,
@@ -779,17 +3033,34 @@ pub struct Create<'info> {
         space = BondingCurve::LEN
     )]
     pub bonding_curve: Account<'info, BondingCurve>, // 曲线
+    // 铸币在指令中才创建，所以金库也在指令中通过关联代币程序创建
+    #[account(mut)]
+    /// CHECK: The associated token program verifies this is the bonding curve's ATA when creating it.
+    pub token_vault: UncheckedAccount<'info>, // 金库
+    #[account(mut, seeds = [b"migration_vault", token_mint.key().as_ref()], bump)]
+    /// CHECK: PDA's correctness is guaranteed by seeds; created and initialized in the instruction.
+    pub migration_vault: UncheckedAccount<'info>, // 迁移储备金库
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)] // 更新元数据上下文
+pub struct UpdateTokenMetadata<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>, // 创作者（补足租金）
     #[account(
-        init,
-        payer = creator,
-        associated_token::mint = token_mint,
-        associated_token::authority = bonding_curve,
-        associated_token::token_program = token_program
+        seeds = [b"bonding_curve", token_mint.key().as_ref()],
+        bump = bonding_curve.bump,
+        has_one = creator,
+        has_one = token_mint
     )]
-    pub token_vault: InterfaceAccount<'info, TokenAccount>, // 金库
+    pub bonding_curve: Account<'info, BondingCurve>, // 曲线（元数据更新权限）
+    #[account(mut)]
+    pub token_mint: InterfaceAccount<'info, Mint>, // 铸币（元数据存放在铸币上）
     pub system_program: Program<'info, System>,
+    #[account(address = spl_token_2022::ID @ PumpError::InvalidTokenProgram)]
     pub token_program: Interface<'info, TokenInterface>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
 #[derive(Accounts)] // 购买上下文
@@ -857,19 +3128,226 @@ pub struct Sell<'info> {
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
-#[derive(Accounts)] // 领取费用上下文
+impl Buy<'_> {
+    // 实现：买入的资金结算，供 buy 和 buy_exact_tokens_out 共用
+    fn settle(&self, sol_to_curve: u64, sol_to_treasury: u64, tokens_out: u64) -> Result<()> {
+        // 函数：从买家转移SOL（曲线部分 + 创作者费用到曲线，国库费用到国库），再把代币转给买家
+        system_program::transfer(
+            CpiContext::new(
+                self.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: self.buyer.to_account_info(),
+                    to: self.bonding_curve.to_account_info(),
+                },
+            ),
+            sol_to_curve,
+        )?;
+        if sol_to_treasury > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    self.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: self.buyer.to_account_info(),
+                        to: self.treasury.to_account_info(),
+                    },
+                ),
+                sol_to_treasury,
+            )?;
+        }
+
+        let token_mint_key = self.token_mint.key(); // 获取铸币key
+        let curve_signer_seeds = &[b"bonding_curve", token_mint_key.as_ref(), &[self.bonding_curve.bump]]; // 准备签名种子
+        token_interface::transfer_checked(
+            // 转账代币给买家
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.token_vault.to_account_info(),
+                    mint: self.token_mint.to_account_info(),
+                    to: self.buyer_token_account.to_account_info(),
+                    authority: self.bonding_curve.to_account_info(),
+                },
+                &[&curve_signer_seeds[..]],
+            ),
+            tokens_out,
+            self.token_mint.decimals,
+        )
+    }
+
+    fn finish(&self, completed: bool) -> Result<()> {
+        // 函数：曲线完成时发出事件，并检查曲线lamports足以覆盖储备、欠费和租金
+        let curve = &self.bonding_curve;
+        if completed {
+            emit!(BondingCurveCompleted {
+                // 发出事件：曲线完成
+                mint: curve.token_mint,
+                bonding_curve: curve.key(),
+                real_sol_reserves: curve.real_sol_reserves,
+                virtual_sol_reserves: curve.virtual_sol_reserves,
+                virtual_token_reserves: curve.virtual_token_reserves
+            });
+        }
+        curve.check_lamports_invariant(&curve.to_account_info())
+    }
+}
+
+impl Sell<'_> {
+    // 实现：卖出的资金结算，供 sell 和 sell_for_exact_sol 共用
+    fn settle(&self, tokens_in: u64, sol_out_net: u64, treasury_fee: u64) -> Result<()> {
+        // 函数：把代币转入金库，再从曲线支付卖家净额和国库费用（创作者费用留在曲线中）
+        token_interface::transfer_checked(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.seller_token_account.to_account_info(),
+                    mint: self.token_mint.to_account_info(),
+                    to: self.token_vault.to_account_info(),
+                    authority: self.seller.to_account_info(),
+                },
+            ),
+            tokens_in,
+            self.token_mint.decimals,
+        )?;
+        let curve_account_info = self.bonding_curve.to_account_info(); // 获取曲线账户信息
+        let total_sol_to_transfer = sol_out_net.checked_add(treasury_fee).ok_or(PumpError::MathOverflow)?; // 计算总转移SOL
+        if curve_account_info.lamports() < total_sol_to_transfer {
+            // 检查余额足够
+            return err!(PumpError::InsufficientSolReserves);
+        }
+        **curve_account_info.try_borrow_mut_lamports()? -= sol_out_net; // 从曲线扣除净输出
+        **self.seller.to_account_info().try_borrow_mut_lamports()? += sol_out_net; // 转移到卖家
+        if treasury_fee > 0 {
+            **curve_account_info.try_borrow_mut_lamports()? -= treasury_fee; // 从曲线扣除国库费用
+            **self.treasury.to_account_info().try_borrow_mut_lamports()? += treasury_fee; // 转移到国库
+        }
+        self.bonding_curve.check_lamports_invariant(&curve_account_info) // 检查曲线lamports不变量
+    }
+}
+
+#[derive(Accounts)] // 领取费用上下文（开启分账时剩余账户：分账接收者，按顺序）
 pub struct ClaimCreatorFees<'info> {
+    #[account(address = bonding_curve.creator)]
+    pub creator: Signer<'info>, // 创作者
+    /// CHECK: 只接收lamports，地址由曲线记录的费用接收者锁定（可以是其他程序拥有的多签或分账账户）
+    #[account(mut, address = bonding_curve.creator_fee_recipient @ PumpError::InvalidFeeRecipient)]
+    pub creator_fee_recipient: UncheckedAccount<'info>,
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>, // 配置（检查暂停位）
+    #[account(mut, seeds = [b"bonding_curve", bonding_curve.token_mint.as_ref()], bump = bonding_curve.bump)]
+    pub bonding_curve: Account<'info, BondingCurve>, // 曲线
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)] // 创建费用释放计划上下文
+pub struct CreateCreatorVesting<'info> {
     #[account(mut, address = bonding_curve.creator)]
+    pub creator: Signer<'info>, // 创作者（支付租金）
+    #[account(mut, seeds = [b"bonding_curve", bonding_curve.token_mint.as_ref()], bump = bonding_curve.bump)]
+    pub bonding_curve: Account<'info, BondingCurve>, // 曲线
+    #[account(
+        init,
+        payer = creator,
+        space = CreatorVesting::LEN,
+        seeds = [b"creator_vesting", bonding_curve.token_mint.as_ref()],
+        bump
+    )]
+    pub creator_vesting: Account<'info, CreatorVesting>, // 释放账户
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)] // 领取已释放费用上下文
+pub struct ClaimVestedCreatorFees<'info> {
+    #[account(address = bonding_curve.creator)]
     pub creator: Signer<'info>, // 创作者
+    /// CHECK: 只接收lamports，地址由曲线记录的费用接收者锁定
+    #[account(mut, address = bonding_curve.creator_fee_recipient @ PumpError::InvalidFeeRecipient)]
+    pub creator_fee_recipient: UncheckedAccount<'info>,
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>, // 配置（检查暂停位）
+    #[account(mut, seeds = [b"bonding_curve", bonding_curve.token_mint.as_ref()], bump = bonding_curve.bump)]
+    pub bonding_curve: Account<'info, BondingCurve>, // 曲线
+    #[account(mut, seeds = [b"creator_vesting", bonding_curve.token_mint.as_ref()], bump = creator_vesting.bump)]
+    pub creator_vesting: Account<'info, CreatorVesting>, // 释放账户
+}
+
+#[derive(Accounts)] // 开启分账上下文
+pub struct CreateFeeSplit<'info> {
+    #[account(mut, address = bonding_curve.creator)]
+    pub creator: Signer<'info>, // 创作者（支付租金）
     #[account(mut, seeds = [b"bonding_curve", bonding_curve.token_mint.as_ref()], bump = bonding_curve.bump)]
     pub bonding_curve: Account<'info, BondingCurve>, // 曲线
+    #[account(
+        init,
+        payer = creator,
+        space = FeeSplit::LEN,
+        seeds = [b"fee_split", bonding_curve.token_mint.as_ref()],
+        bump
+    )]
+    pub fee_split: Account<'info, FeeSplit>, // 分账账户
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)] // 更换分账比例上下文（剩余账户：旧接收者，按顺序）
+pub struct UpdateFeeSplit<'info> {
+    #[account(address = bonding_curve.creator)]
+    pub creator: Signer<'info>, // 创作者
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>, // 配置（检查暂停位）
+    #[account(mut, seeds = [b"bonding_curve", bonding_curve.token_mint.as_ref()], bump = bonding_curve.bump)]
+    pub bonding_curve: Account<'info, BondingCurve>, // 曲线
+    #[account(mut, seeds = [b"fee_split", bonding_curve.token_mint.as_ref()], bump = fee_split.bump)]
+    pub fee_split: Account<'info, FeeSplit>, // 分账账户
+}
+
+#[derive(Accounts)] // 领取分账份额上下文
+pub struct ClaimFeeSplitShare<'info> {
+    #[account(mut)]
+    pub recipient: Signer<'info>, // 分账接收者
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>, // 配置（检查暂停位）
+    #[account(mut, seeds = [b"bonding_curve", bonding_curve.token_mint.as_ref()], bump = bonding_curve.bump)]
+    pub bonding_curve: Account<'info, BondingCurve>, // 曲线
+    #[account(mut, seeds = [b"fee_split", bonding_curve.token_mint.as_ref()], bump = fee_split.bump)]
+    pub fee_split: Account<'info, FeeSplit>, // 分账账户
+}
+
+#[derive(Accounts)] // 创作者操作上下文（提名新创作者、撤回提名、设置费用接收者）
+pub struct CreatorAuthority<'info> {
+    pub creator: Signer<'info>, // 当前创作者
+    #[account(mut, seeds = [b"bonding_curve", bonding_curve.token_mint.as_ref()], bump = bonding_curve.bump, has_one = creator)]
+    pub bonding_curve: Account<'info, BondingCurve>, // 曲线
+}
+
+#[derive(Accounts)] // 接受创作者权限上下文
+pub struct AcceptCreator<'info> {
+    pub pending_creator: Signer<'info>, // 被提名的新创作者
+    #[account(
+        mut,
+        seeds = [b"bonding_curve", bonding_curve.token_mint.as_ref()],
+        bump = bonding_curve.bump,
+        has_one = pending_creator @ PumpError::NotPendingCreator
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>, // 曲线
+}
+
+#[derive(Accounts)] // 紧急赎回上下文
+pub struct EmergencyRedeem<'info> {
+    #[account(mut)]
+    pub holder: Signer<'info>, // 持有人
+    #[account(mut, seeds = [b"bonding_curve", token_mint.key().as_ref()], bump = bonding_curve.bump, has_one = token_mint, has_one = token_vault)]
+    pub bonding_curve: Account<'info, BondingCurve>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, associated_token::mint = token_mint, associated_token::authority = holder, associated_token::token_program = token_program)]
+    pub holder_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
 #[derive(Accounts)] // 完成迁移上下文
 pub struct CompleteAndMigrate<'info> {
     #[account(mut)]
-    pub creator: Signer<'info>,
+    pub payer: Signer<'info>, // 任何人都可以调用
 
     #[account(seeds = [b"protocol_config"], bump = protocol_config.bump)]
     pub protocol_config: Account<'info, ProtocolConfig>,
@@ -878,13 +3356,12 @@ pub struct CompleteAndMigrate<'info> {
     #[account(
         mut,
         seeds = [b"bonding_curve", token_mint.key().as_ref()],
-        bump,
-        has_one = creator
+        bump = bonding_curve.bump
     )]
     pub bonding_curve: Account<'info, BondingCurve>,
 
-    #[account(address = bonding_curve.token_mint)]
-    pub token_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, address = bonding_curve.token_mint)]
+    pub token_mint: InterfaceAccount<'info, Mint>, // 需要可写以撤销元数据权限和销毁
 
     #[account(
         mut,
@@ -892,6 +3369,9 @@ pub struct CompleteAndMigrate<'info> {
     )]
     pub token_vault: InterfaceAccount<'info, TokenAccount>,
 
+    #[account(mut, seeds = [b"migration_vault", token_mint.key().as_ref()], bump)]
+    pub migration_vault: InterfaceAccount<'info, TokenAccount>, // 迁移储备金库
+
     #[account(mut, seeds = [b"dex_pool", token_mint.key().as_ref()], bump)]
     pub dex_pool: Account<'info, DexPool>,
 
@@ -899,13 +3379,23 @@ pub struct CompleteAndMigrate<'info> {
     /// CHECK: PDA's correctness is guaranteed by seeds.
     pub dex_sol_vault: UncheckedAccount<'info>,
 
-    #[account(mut)]
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = dex_pool,
+        associated_token::token_program = token_program
+    )]
     pub dex_token_vault: InterfaceAccount<'info, TokenAccount>,
 
     #[account(mut, seeds=[b"lp_mint", token_mint.key().as_ref()], bump)]
     pub lp_mint: InterfaceAccount<'info, Mint>,
 
-    #[account(mut)]
+    #[account(
+        mut,
+        associated_token::mint = lp_mint,
+        associated_token::authority = dex_pool,
+        associated_token::token_program = token_program
+    )]
     pub lp_vault: InterfaceAccount<'info, TokenAccount>,
 
     pub system_program: Program<'info, System>,
@@ -913,76 +3403,334 @@ pub struct CompleteAndMigrate<'info> {
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)] // 配置V1结构
-pub struct ProtocolConfigV1 {
-    pub governance_authority: Pubkey,
-    pub treasury: Pubkey,
-    pub creation_fee_sol: u64,
-    pub total_trade_fee_bps: u64,
-    pub creator_fee_bps_share: u64,
-    pub migration_threshold_sol: u64,
-    pub is_paused: bool,
-}
-impl From<ProtocolConfigV1> for ProtocolConfig {
-    // 从V1转换到配置
-    fn from(v1: ProtocolConfigV1) -> Self {
-        Self {
-            governance_authority: v1.governance_authority,
-            treasury: v1.treasury,
-            creation_fee_sol: v1.creation_fee_sol,
-            total_trade_fee_bps: v1.total_trade_fee_bps,
-            creator_fee_bps_share: v1.creator_fee_bps_share,
-            migration_threshold_sol: v1.migration_threshold_sol,
-            is_paused: v1.is_paused,
-            bump: 0,
-        }
-    }
-}
-
 #[event] // 事件：配置初始化
+#[derive(Debug)]
 pub struct ConfigInitialized {
     pub governance: Pubkey,
     pub treasury: Pubkey,
 }
-#[event] // 事件：配置更新
-pub struct ConfigUpdated {
-    pub new_governance: Pubkey,
+#[event] // 事件：费用更新
+#[derive(Debug)]
+pub struct FeesUpdated {
+    pub old_total_trade_fee_bps: u64,
+    pub new_total_trade_fee_bps: u64,
+    pub old_creator_fee_bps_share: u64,
+    pub new_creator_fee_bps_share: u64,
+    pub old_dex_lp_fee_bps: u64,
+    pub new_dex_lp_fee_bps: u64,
+}
+#[event] // 事件：国库更新
+#[derive(Debug)]
+pub struct TreasuryUpdated {
+    pub old_treasury: Pubkey,
     pub new_treasury: Pubkey,
 }
+#[event] // 事件：创建费用更新
+#[derive(Debug)]
+pub struct CreationFeeUpdated {
+    pub old_creation_fee_sol: u64,
+    pub new_creation_fee_sol: u64,
+}
+#[event] // 事件：迁移阈值更新
+#[derive(Debug)]
+pub struct MigrationThresholdUpdated {
+    pub old_migration_threshold_sol: u64,
+    pub new_migration_threshold_sol: u64,
+}
+#[event] // 事件：配置变更延迟更新
+#[derive(Debug)]
+pub struct TimelockDelayUpdated {
+    pub old_delay_secs: i64,
+    pub new_delay_secs: i64,
+}
+#[event] // 事件：配置变更已排队
+#[derive(Debug)]
+pub struct ConfigChangeQueued {
+    pub id: u64,
+    pub change: ConfigChange,
+    pub executable_at: i64,
+}
+#[event] // 事件：配置变更已执行
+#[derive(Debug)]
+pub struct ConfigChangeExecuted {
+    pub id: u64,
+    pub change: ConfigChange,
+}
+#[event] // 事件：配置变更已撤回
+#[derive(Debug)]
+pub struct ConfigChangeCancelled {
+    pub id: u64,
+    pub change: ConfigChange,
+}
+#[event] // 事件：暂停位更新
+#[derive(Debug)]
+pub struct PausedUpdated {
+    pub authority: Pubkey,
+    pub old_paused: u8,
+    pub new_paused: u8,
+}
+#[event] // 事件：守护者更新
+#[derive(Debug)]
+pub struct GuardianUpdated {
+    pub old_guardian: Pubkey,
+    pub new_guardian: Pubkey,
+}
+#[event] // 事件：治理权限提名
+#[derive(Debug)]
+pub struct GovernanceProposed {
+    pub governance: Pubkey,
+    pub pending_governance: Pubkey,
+}
+#[event] // 事件：治理权限转移完成
+#[derive(Debug)]
+pub struct GovernanceAccepted {
+    pub previous_governance: Pubkey,
+    pub new_governance: Pubkey,
+}
+#[event] // 事件：治理权限提名撤回
+#[derive(Debug)]
+pub struct GovernanceProposalCancelled {
+    pub governance: Pubkey,
+    pub cancelled_governance: Pubkey,
+}
+#[event] // 事件：治理委员会成员更新（创建时也发出）
+#[derive(Debug)]
+pub struct GovernanceCouncilUpdated {
+    pub authority: Pubkey,
+    pub members: Vec<Pubkey>,
+    pub threshold: u8,
+    pub generation: u32,
+}
+#[event] // 事件：委员会提案创建
+#[derive(Debug)]
+pub struct CouncilProposalCreated {
+    pub id: u64,
+    pub proposer: Pubkey,
+    pub action: CouncilAction,
+}
+#[event] // 事件：委员会提案批准
+#[derive(Debug)]
+pub struct CouncilProposalApproved {
+    pub id: u64,
+    pub member: Pubkey,
+    pub approvals: u8,
+}
+#[event] // 事件：委员会提案执行
+#[derive(Debug)]
+pub struct CouncilProposalExecuted {
+    pub id: u64,
+    pub action: CouncilAction,
+}
+#[event] // 事件：委员会提案撤回
+#[derive(Debug)]
+pub struct CouncilProposalCancelled {
+    pub id: u64,
+    pub action: CouncilAction,
+}
+#[event] // 事件：曲线预设更新
+#[derive(Debug)]
+pub struct CurvePresetUpdated {
+    pub index: u8,
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub total_supply: u64,
+    pub decimals: u8,
+    pub curve_supply_bps: u16,
+    pub curve_kind: CurveKind,
+}
 #[event] // 事件：代币创建
+#[derive(Debug)]
 pub struct TokenCreated {
     pub mint: Pubkey,
     pub creator: Pubkey,
     pub bonding_curve: Pubkey,
     pub name: String,
     pub symbol: String,
+    pub uri: String,
+}
+#[event] // 事件：元数据更新
+#[derive(Debug)]
+pub struct TokenMetadataUpdated {
+    pub mint: Pubkey,
+    pub creator: Pubkey,
+    pub field: String,
+    pub value: String,
+}
+#[event] // 事件：元数据在毕业时冻结
+#[derive(Debug)]
+pub struct TokenMetadataFrozen {
+    pub mint: Pubkey,
 }
 #[event] // 事件：购买
+#[derive(Debug)]
 pub struct BuyEvent {
     pub mint: Pubkey,
     pub buyer: Pubkey,
     pub sol_in: u64,
     pub tokens_out: u64,
+    pub sol_refunded: u64, // 因截断到迁移阈值而未扣除的SOL
+    pub creator_fee: u64,  // 创作者费用
+    pub treasury_fee: u64, // 国库费用
+}
+#[event] // 事件：曲线达到迁移阈值而完成
+#[derive(Debug)]
+pub struct BondingCurveCompleted {
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub real_sol_reserves: u64,
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
 }
 #[event] // 事件：出售
+#[derive(Debug)]
 pub struct SellEvent {
     pub mint: Pubkey,
     pub seller: Pubkey,
     pub tokens_in: u64,
+    pub sol_out: u64,      // 卖家实际收到的SOL（扣费后）
+    pub creator_fee: u64,  // 创作者费用
+    pub treasury_fee: u64, // 国库费用
+}
+#[event] // 事件：曲线冻结状态更新
+#[derive(Debug)]
+pub struct CurveFreezeUpdated {
+    pub mint: Pubkey,
+    pub frozen: bool,
+}
+#[event] // 事件：紧急清退状态更新
+#[derive(Debug)]
+pub struct EmergencyUnwindUpdated {
+    pub mint: Pubkey,
+    pub unwinding: bool,
+    pub real_sol_reserves: u64,  // 可兑付的真实SOL储备
+    pub tokens_outstanding: u64, // 流通中的代币
+}
+#[event] // 事件：紧急赎回
+#[derive(Debug)]
+pub struct EmergencyRedeemed {
+    pub mint: Pubkey,
+    pub holder: Pubkey,
+    pub tokens_in: u64,
     pub sol_out: u64,
+    pub redeemed_tokens: u64, // 累计兑付的代币
+    pub redeemed_sol: u64,    // 累计兑付的SOL
+}
+#[event] // 事件：创作者费用释放计划创建
+#[derive(Debug)]
+pub struct CreatorVestingCreated {
+    pub mint: Pubkey,
+    pub creator: Pubkey,
+    pub start_time: i64,
+    pub cliff_secs: i64,
+    pub duration_secs: i64,
+}
+#[event] // 事件：领取已释放的创作者费用
+#[derive(Debug)]
+pub struct CreatorVestingClaimed {
+    pub mint: Pubkey,
+    pub creator: Pubkey,
+    pub recipient: Pubkey, // 实际收款的费用接收者
+    pub amount: u64,
+    pub total_accrued: u64, // 累计汇入
+    pub total_claimed: u64, // 累计领取
 }
 #[event] // 事件：费用领取
+#[derive(Debug)]
 pub struct CreatorFeeClaimed {
     pub mint: Pubkey,
     pub creator: Pubkey,
+    pub recipient: Pubkey, // 实际收款的费用接收者
+    pub amount: u64,
+}
+#[event] // 事件：分账设置
+#[derive(Debug)]
+pub struct FeeSplitUpdated {
+    pub mint: Pubkey,
+    pub creator: Pubkey,
+    pub recipients: Vec<Pubkey>,
+    pub shares_bps: Vec<u16>, // 与 recipients 一一对应
+}
+#[event] // 事件：分账份额领取
+#[derive(Debug)]
+pub struct FeeSplitClaimed {
+    pub mint: Pubkey,
+    pub recipient: Pubkey,
     pub amount: u64,
 }
+#[event] // 事件：创作者提名
+#[derive(Debug)]
+pub struct CreatorTransferProposed {
+    pub mint: Pubkey,
+    pub creator: Pubkey,
+    pub pending_creator: Pubkey,
+}
+#[event] // 事件：创作者转移完成
+#[derive(Debug)]
+pub struct CreatorTransferred {
+    pub mint: Pubkey,
+    pub previous_creator: Pubkey,
+    pub new_creator: Pubkey,
+}
+#[event] // 事件：创作者提名撤回
+#[derive(Debug)]
+pub struct CreatorTransferCancelled {
+    pub mint: Pubkey,
+    pub creator: Pubkey,
+    pub cancelled_creator: Pubkey,
+}
+#[event] // 事件：创作者费用接收者更新
+#[derive(Debug)]
+pub struct CreatorFeeRecipientUpdated {
+    pub mint: Pubkey,
+    pub old_recipient: Pubkey,
+    pub new_recipient: Pubkey,
+}
 #[event] // 事件：DEX迁移
+#[derive(Debug)]
 pub struct DexMigrated {
     pub mint: Pubkey,
     pub dex_pool: Pubkey,
     pub sol_reserves: u64,
     pub token_reserves: u64,
+    pub tokens_burned: u64,   // 销毁的未售出和多余储备代币
+}
+
+#[event] // 事件：DEX兑换
+#[derive(Debug)]
+pub struct DexSwapEvent {
+    pub mint: Pubkey,
+    pub user: Pubkey,
+    pub sol_to_tokens: bool, // true: SOL换代币；false: 代币换SOL
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub lp_fee: u64,
+    pub sol_reserves: u64,
+    pub token_reserves: u64,
+}
+
+#[event] // 事件：注入流动性
+#[derive(Debug)]
+pub struct LiquidityAdded {
+    pub mint: Pubkey,
+    pub user: Pubkey,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub lp_amount: u64,
+    pub lp_supply: u64,
+    pub sol_reserves: u64,
+    pub token_reserves: u64,
+}
+#[event] // 事件：移除流动性
+#[derive(Debug)]
+pub struct LiquidityRemoved {
+    pub mint: Pubkey,
+    pub user: Pubkey,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub lp_amount: u64,
+    pub lp_supply: u64,
+    pub sol_reserves: u64,
+    pub token_reserves: u64,
 }
 
 #[error_code] // 错误码枚举
@@ -997,7 +3745,7 @@ pub enum PumpError {
     InvalidMetadataLength, // 元数据长度无效
     #[msg("There are no creator fees available to claim.")]
     NoFeesToClaim, // 无费用可领
-    #[msg("The protocol is currently paused by the governance authority.")]
+    #[msg("This instruction family is currently paused.")]
     ProtocolPaused, // 协议暂停
     #[msg("The transaction deadline has been exceeded.")]
     DeadlineExceeded, // 截止时间超
@@ -1009,4 +3757,100 @@ pub enum PumpError {
     AlreadyMigrated, // 已迁移
     #[msg("The SOL reserves have not met the threshold for DEX migration.")]
     MigrationThresholdNotMet, // 未达迁移阈值
+    #[msg("Bonding curve lamports do not cover real SOL reserves, creator fees owed and rent.")]
+    LamportInvariantViolated, // lamports不变量被破坏
+    #[msg("The DEX pool has not been migrated into yet.")]
+    DexPoolNotActive, // DEX池未激活
+    #[msg("The DEX pool has no liquidity.")]
+    DexPoolEmpty, // DEX池无流动性
+    #[msg("Not enough withdrawable liquidity in the DEX pool.")]
+    InsufficientLiquidity, // 可取出流动性不足
+    #[msg("The token vault does not match the bonding curve.")]
+    InvalidTokenVault, // 代币金库不匹配
+    #[msg("The token program must be Token-2022.")]
+    InvalidTokenProgram, // 代币程序无效
+    #[msg("This metadata field cannot be updated.")]
+    InvalidMetadataField, // 元数据字段不可修改
+    #[msg("Too many additional metadata fields.")]
+    TooManyMetadataFields, // 自定义字段过多
+    #[msg("The curve preset is invalid or does not exist.")]
+    InvalidCurvePreset, // 曲线预设无效
+    #[msg("Insufficient token reserves in the bonding curve for this trade.")]
+    InsufficientTokenReserves, // 可售代币不足
+    #[msg("The trade would push the curve past its migration threshold.")]
+    ExceedsMigrationThreshold, // 超过迁移阈值
+    #[msg("Arithmetic overflow in curve or fee math.")]
+    MathOverflow, // 算术溢出
+    #[msg("The trade would take a reserve below zero.")]
+    ReserveUnderflow, // 储备下溢
+    #[msg("The proposed governance authority is empty or already the governance authority.")]
+    InvalidGovernanceProposal, // 治理提名无效
+    #[msg("There is no pending governance proposal.")]
+    NoPendingGovernance, // 无待接受的提名
+    #[msg("The signer is not the pending governance authority.")]
+    NotPendingGovernance, // 签名者不是被提名的权限
+    #[msg("The fee exceeds its hard cap.")]
+    FeeExceedsCap, // 费用超过上限
+    #[msg("The creator fee share exceeds the total trade fee.")]
+    CreatorShareExceedsTotal, // 创作者份额超过总费用
+    #[msg("The creation fee exceeds its hard cap.")]
+    CreationFeeExceedsCap, // 创建费用超过上限
+    #[msg("The migration threshold is outside the allowed range.")]
+    InvalidMigrationThreshold, // 迁移阈值超出范围
+    #[msg("The config change timelock has not elapsed yet.")]
+    TimelockNotElapsed, // 时间锁未到期
+    #[msg("The timelock delay is outside the allowed range.")]
+    InvalidTimelockDelay, // 时间锁延迟超出范围
+    #[msg("The new treasury account does not match the queued change.")]
+    InvalidTreasury, // 新国库不匹配
+    #[msg("The council members or threshold are invalid.")]
+    InvalidCouncil, // 委员会成员或门槛无效
+    #[msg("The signer is not a governance council member.")]
+    NotCouncilMember, // 签名者不是委员会成员
+    #[msg("The member has already approved this proposal.")]
+    ProposalAlreadyApproved, // 已批准过
+    #[msg("The proposal does not have enough approvals.")]
+    CouncilThresholdNotMet, // 批准数不足
+    #[msg("The council membership changed after this proposal was created.")]
+    StaleCouncilProposal, // 提案已失效
+    #[msg("The pause flags contain undefined bits or are empty.")]
+    InvalidPauseFlags, // 暂停位无效
+    #[msg("The signer is not the guardian.")]
+    NotGuardian, // 签名者不是守护者
+    #[msg("The bonding curve is frozen or being unwound by governance.")]
+    CurveFrozen, // 曲线已冻结或正在清退
+    #[msg("The bonding curve is not being unwound.")]
+    CurveNotUnwinding, // 曲线未处于紧急清退
+    #[msg("The vesting duration must be positive and capped, and the cliff must not exceed it.")]
+    InvalidVestingSchedule, // 释放计划无效
+    #[msg("Creator fees on this curve are released through its vesting schedule.")]
+    CreatorFeesVesting, // 创作者费用按释放计划领取
+    #[msg("The new creator must differ from the current creator and the default key.")]
+    InvalidCreatorTransfer, // 创作者提名无效
+    #[msg("There is no pending creator transfer.")]
+    NoPendingCreator, // 无待接受的创作者提名
+    #[msg("The signer is not the pending creator.")]
+    NotPendingCreator, // 签名者不是被提名的创作者
+    #[msg("The creator fee recipient is the default key or does not match the curve.")]
+    InvalidFeeRecipient, // 费用接收者无效
+    #[msg("A fee split needs 1 to 8 distinct recipients with positive shares summing to 10000 bps.")]
+    InvalidFeeSplit, // 分账设置无效
+    #[msg("The fee split recipient accounts do not match the registered recipients.")]
+    FeeSplitRecipientMismatch, // 分账接收者账户不匹配
+    #[msg("The signer is not a recipient of this fee split.")]
+    NotFeeSplitRecipient, // 签名者不是分账接收者
+    #[msg("Creator fees on this curve are routed through its fee split.")]
+    FeeSplitActive, // 已开启分账
+    #[msg("Holders have already redeemed from this curve, so it cannot reopen for trading.")]
+    RedemptionsStarted, // 已有兑付，不能关闭紧急清退
+}
+
+impl From<MathError> for PumpError {
+    fn from(error: MathError) -> Self {
+        // 转换：pumpfun-math 错误 -> 程序错误码
+        match error {
+            MathError::Overflow => PumpError::MathOverflow,
+            MathError::ReserveUnderflow => PumpError::ReserveUnderflow,
+        }
+    }
 }
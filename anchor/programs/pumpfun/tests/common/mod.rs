@@ -0,0 +1,301 @@
+//! 集成测试的公共环境：在 solana-program-test 的 bank 中加载 pumpfun 程序，初始化配置并提供常用操作。
+//!
+//! 默认以原生函数运行程序；先 `anchor build` 再以 `SBF_OUT_DIR=target/deploy cargo test -p pumpfun`
+//! 运行时，测试针对编译出的 `pumpfun.so` 执行。
+
+#![allow(dead_code)] // 各测试文件只用到其中一部分
+
+use std::collections::HashMap;
+
+use anchor_lang::{
+    prelude::{AccountInfo, Pubkey},
+    solana_program::{entrypoint::ProgramResult, instruction::Instruction},
+    AccountDeserialize, AccountSerialize, AnchorDeserialize, Discriminator,
+};
+use anchor_spl::token_interface::{
+    spl_token_2022::{
+        extension::{metadata_pointer::MetadataPointer, BaseStateWithExtensions, StateWithExtensions},
+        state::Mint,
+    },
+    spl_token_metadata_interface::state::TokenMetadata,
+};
+use pumpfun::{BondingCurve, ConfigChange, CouncilAction, DexPool, GovernanceCouncil, ProtocolConfig, PumpError};
+use pumpfun_client::{instructions as ix, pda};
+use pumpfun_svm::{processor, Keypair, ProgramTest, Signer, Svm, TransactionError, TransactionMeta};
+
+pub const SOL: u64 = 1_000_000_000; // 1 SOL（lamports）
+pub const FAR_DEADLINE: i64 = i64::MAX; // 不会过期的截止时间
+
+pub type TxResult = Result<TransactionMeta, TransactionError>;
+
+pub struct Env {
+    pub svm: Svm,                       // 运行时
+    pub governance: Pubkey,             // 治理权限
+    pub treasury: Pubkey,               // 国库
+    keypairs: HashMap<Pubkey, Keypair>, // 测试创建的签名者
+}
+
+fn entry(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    // 函数：原生回退时的程序入口；Anchor 的 entry 要求账户切片与账户同寿命，测试中泄漏一份拷贝即可
+    pumpfun::entry(program_id, Box::leak(accounts.to_vec().into_boxed_slice()), data)
+}
+
+pub fn program_test() -> ProgramTest {
+    ProgramTest::new("pumpfun", pumpfun::ID, processor!(entry))
+}
+
+impl Env {
+    pub fn new() -> Self {
+        // 函数：注册程序并以默认参数初始化配置
+        let mut env = Self {
+            svm: Svm::new(program_test()),
+            governance: Pubkey::default(),
+            treasury: Pubkey::default(),
+            keypairs: HashMap::new(),
+        };
+        let governance = env.user(10);
+        let treasury = env.user(1);
+        env.governance = governance;
+        env.treasury = treasury;
+        env.send(
+            &[ix::InitializeConfig {
+                authority: governance,
+                treasury,
+            }
+            .instruction()],
+            &[governance],
+        )
+        .unwrap();
+        env
+    }
+
+    pub fn send(&mut self, instructions: &[Instruction], signers: &[Pubkey]) -> TxResult {
+        // 函数：用签名者的密钥对签名并执行交易
+        let keypairs: Vec<&Keypair> = signers
+            .iter()
+            .map(|signer| self.keypairs.get(signer).expect("not a keypair created by the test"))
+            .collect();
+        self.svm.process_transaction(instructions, &keypairs)
+    }
+
+    pub fn keypair(&mut self) -> Pubkey {
+        // 函数：新建一个可以签名的地址
+        let keypair = Keypair::new();
+        let address = keypair.pubkey();
+        self.keypairs.insert(address, keypair);
+        address
+    }
+
+    pub fn user(&mut self, sol: u64) -> Pubkey {
+        // 函数：创建一个有余额的用户
+        let user = self.keypair();
+        self.svm.airdrop_sol(&user, sol);
+        user
+    }
+
+    pub fn account<T: AccountDeserialize>(&self, address: &Pubkey) -> T {
+        let account = self.svm.account(address).expect("account does not exist");
+        T::try_deserialize(&mut &account.data[..]).unwrap()
+    }
+
+    pub fn store<T: AccountSerialize>(&mut self, address: &Pubkey, value: &T) {
+        // 函数：直接改写程序账户的数据（模拟无法通过指令达到的状态）
+        let mut account = self.svm.account(address).expect("account does not exist");
+        account.data.clear();
+        value.try_serialize(&mut account.data).unwrap();
+        self.svm.set_account(*address, account);
+    }
+
+    pub fn config(&self) -> ProtocolConfig {
+        self.account(&pda::protocol_config().0)
+    }
+
+    pub fn curve(&self, mint: &Pubkey) -> BondingCurve {
+        self.account(&pda::bonding_curve(mint).0)
+    }
+
+    pub fn dex_pool(&self, mint: &Pubkey) -> DexPool {
+        self.account(&pda::dex_pool(mint).0)
+    }
+
+    pub fn governance(&self) -> ix::Governance {
+        ix::Governance {
+            governance_authority: self.governance,
+        }
+    }
+
+    pub fn govern(&mut self, instruction: Instruction) -> TxResult {
+        // 函数：以治理权限签名执行治理指令
+        let governance = self.governance;
+        self.send(&[instruction], &[governance])
+    }
+
+    pub fn queue(&mut self, change: ConfigChange) -> TxResult {
+        // 函数：以下一个编号排队配置变更
+        let id = self.config().next_config_change_id;
+        self.govern(self.governance().queue_config_change(id, change))
+    }
+
+    pub fn warp(&mut self, secs: i64) {
+        // 函数：时钟前进 secs 秒
+        let now = self.svm.clock().unix_timestamp;
+        self.svm.set_unix_timestamp(now + secs);
+    }
+
+    pub fn change_config(&mut self, change: ConfigChange) -> TransactionMeta {
+        // 函数：排队配置变更，等待时间锁到期后执行，返回执行交易
+        let id = self.config().next_config_change_id;
+        self.queue(change).unwrap();
+        let delay = self.config().config_timelock_secs;
+        self.warp(delay);
+        self.govern(self.governance().execute_config_change(id, &change)).unwrap()
+    }
+
+    pub fn create_council(&mut self, size: usize, threshold: u8) -> Vec<Pubkey> {
+        // 函数：创建治理委员会并返回成员；委员会权限 PDA 预存 1 SOL，用于支付治理指令创建的账户租金
+        let members: Vec<Pubkey> = (0..size).map(|_| self.user(1)).collect();
+        self.govern(self.governance().create_governance_council(members.clone(), threshold))
+            .unwrap();
+        self.svm.airdrop_sol(&pda::council_authority().0, 1);
+        members
+    }
+
+    pub fn council(&self) -> GovernanceCouncil {
+        self.account(&pda::governance_council().0)
+    }
+
+    pub fn pass(&mut self, members: &[Pubkey], action: CouncilAction, instruction: Option<&Instruction>) -> TxResult {
+        // 函数：第一位成员提案，后续成员依次批准直到达到门槛，再由提案人执行
+        let council = self.council();
+        let id = council.next_proposal_id;
+        let proposer = members[0];
+        self.send(&[ix::Council { member: proposer }.propose(id, action)], &[proposer])
+            .unwrap();
+        for &member in &members[1..council.threshold as usize] {
+            self.send(&[ix::Council { member }.approve(id)], &[member]).unwrap();
+        }
+        let execute = ix::Council { member: proposer }.execute(id, proposer, instruction);
+        self.send(&[execute], &[proposer])
+    }
+
+    pub fn hand_to_council(&mut self, members: &[Pubkey]) {
+        // 函数：提名委员会权限 PDA，并通过委员会提案接受，之后治理只能经由提案
+        let authority = pda::council_authority().0;
+        self.govern(self.governance().propose_governance(authority)).unwrap();
+        let accept = ix::AcceptGovernance {
+            pending_governance: authority,
+        }
+        .instruction();
+        self.pass(members, CouncilAction::AcceptGovernance, Some(&accept))
+            .unwrap();
+        self.governance = authority;
+    }
+
+    pub fn create_ix(&self, creator: Pubkey, mint: Pubkey, preset_index: u8) -> Instruction {
+        ix::Create {
+            creator,
+            treasury: self.treasury,
+            token_mint: mint,
+        }
+        .instruction("Pump".into(), "PUMP".into(), "https://example.com/pump.json".into(), preset_index)
+    }
+
+    pub fn create(&mut self, creator: Pubkey) -> Pubkey {
+        // 函数：用默认预设创建代币，返回铸币地址
+        let mint = self.keypair();
+        let instruction = self.create_ix(creator, mint, 0);
+        self.send(&[instruction], &[creator, mint]).unwrap();
+        mint
+    }
+
+    pub fn buy_ix(&self, buyer: Pubkey, mint: Pubkey) -> ix::Buy {
+        ix::Buy {
+            buyer,
+            treasury: self.treasury,
+            token_mint: mint,
+        }
+    }
+
+    pub fn sell_ix(&self, seller: Pubkey, mint: Pubkey) -> ix::Sell {
+        ix::Sell {
+            seller,
+            treasury: self.treasury,
+            token_mint: mint,
+        }
+    }
+
+    pub fn buy(&mut self, buyer: Pubkey, mint: Pubkey, sol: u64) -> TransactionMeta {
+        let instruction = self.buy_ix(buyer, mint).buy(sol, 0, FAR_DEADLINE);
+        self.send(&[instruction], &[buyer]).unwrap()
+    }
+
+    pub fn sell(&mut self, seller: Pubkey, mint: Pubkey, tokens: u64) -> TransactionMeta {
+        let instruction = self.sell_ix(seller, mint).sell(tokens, 0, FAR_DEADLINE);
+        self.send(&[instruction], &[seller]).unwrap()
+    }
+
+    pub fn migrate_ixs(&self, payer: Pubkey, mint: Pubkey) -> [Instruction; 2] {
+        [
+            ix::InitializeDexPool { payer, token_mint: mint }.instruction(),
+            ix::CompleteAndMigrate { payer, token_mint: mint }.instruction(),
+        ]
+    }
+
+    pub fn graduate(&mut self, mint: Pubkey) -> Pubkey {
+        // 函数：买满迁移阈值并迁移到DEX，返回买家
+        let whale = self.user(100);
+        self.buy(whale, mint, 60 * SOL);
+        assert!(self.curve(&mint).is_completed);
+        let instructions = self.migrate_ixs(whale, mint);
+        self.send(&instructions, &[whale]).unwrap();
+        whale
+    }
+
+    pub fn tokens(&self, owner: &Pubkey, mint: &Pubkey) -> u64 {
+        self.svm.token_amount(&pda::token_account(owner, mint))
+    }
+
+    pub fn mint_decimals(&self, mint: &Pubkey) -> u8 {
+        let account = self.svm.account(mint).expect("mint does not exist");
+        StateWithExtensions::<Mint>::unpack(&account.data).unwrap().base.decimals
+    }
+
+    pub fn metadata(&self, mint: &Pubkey) -> (MetadataPointer, TokenMetadata) {
+        // 函数：读取铸币上的元数据指针和元数据扩展
+        let account = self.svm.account(mint).expect("mint does not exist");
+        let state = StateWithExtensions::<Mint>::unpack(&account.data).unwrap();
+        let pointer = *state.get_extension::<MetadataPointer>().unwrap();
+        (pointer, state.get_variable_len_extension::<TokenMetadata>().unwrap())
+    }
+}
+
+pub fn events<T: AnchorDeserialize + Discriminator>(meta: &TransactionMeta) -> Vec<T> {
+    // 函数：从日志中取出指定类型的事件
+    meta.program_data()
+        .iter()
+        .filter_map(|data| data.strip_prefix(T::DISCRIMINATOR))
+        .map(|payload| T::deserialize(&mut &payload[..]).unwrap())
+        .collect()
+}
+
+pub fn event<T: AnchorDeserialize + Discriminator>(meta: &TransactionMeta) -> T {
+    let mut all = events::<T>(meta);
+    assert_eq!(all.len(), 1, "expected exactly one event");
+    all.remove(0)
+}
+
+pub fn code(result: TxResult) -> u32 {
+    // 函数：交易失败时的自定义错误码
+    let error = result.expect_err("transaction should fail");
+    error
+        .custom_code()
+        .unwrap_or_else(|| panic!("not a program error: {error:?}"))
+}
+
+pub fn assert_pump_error(result: TxResult, expected: PumpError) {
+    assert_eq!(code(result), u32::from(expected), "expected {expected:?}");
+}
+
+pub fn assert_anchor_error(result: TxResult, expected: anchor_lang::error::ErrorCode) {
+    assert_eq!(code(result), expected as u32, "expected {expected:?}");
+}
@@ -0,0 +1,158 @@
+//! 资金守恒：create → buy → sell → claim → migrate → DEX 全流程中，
+//! 每笔交易前后 lamports 总量不变、每一方的变化与事件一致，代币余额之和始终等于总供应量。
+
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use common::*;
+use pumpfun::{BuyEvent, CreatorFeeClaimed, DexMigrated, DexSwapEvent, SellEvent};
+use pumpfun_client::{instructions as ix, pda};
+use pumpfun_svm::TransactionMeta;
+
+struct Ledger {
+    env: Env,         // 环境
+    mint: Pubkey,     // 铸币
+    lamports: u128,   // lamports 总量
+    supply: u64,      // 当前总供应量
+}
+
+impl Ledger {
+    fn send(&mut self, instructions: &[anchor_lang::solana_program::instruction::Instruction], signers: &[Pubkey]) -> TransactionMeta {
+        // 函数：执行交易，并检查 lamports 总量不变、代币余额之和等于总供应量
+        let meta = self.env.send(instructions, signers).unwrap();
+        assert_eq!(self.env.svm.total_lamports(), self.lamports, "lamports were created or destroyed");
+        let supply = self.env.svm.mint_supply(&self.mint);
+        assert_eq!(self.env.svm.token_amount_held(&self.mint), supply, "token balances do not add up to supply");
+        self.supply = supply;
+        meta
+    }
+
+    fn balance(&self, address: &Pubkey) -> u64 {
+        self.env.svm.balance(address)
+    }
+
+    fn assert_curve_backed(&self) {
+        // 函数：曲线 lamports 恰好等于租金 + 真实SOL储备 + 欠创作者费用
+        let address = pda::bonding_curve(&self.mint).0;
+        let account = self.env.svm.account(&address).unwrap();
+        let curve = self.env.curve(&self.mint);
+        let rent = self.env.svm.rent().minimum_balance(account.data.len());
+        assert_eq!(account.lamports, rent + curve.real_sol_reserves + curve.creator_fees_owed);
+    }
+}
+
+#[test]
+fn lamports_and_tokens_are_conserved_through_the_whole_lifecycle() {
+    let mut env = Env::new();
+    let creator = env.user(10);
+    let alice = env.user(10);
+    let whale = env.user(100);
+    let treasury = env.treasury;
+    let lamports = env.svm.total_lamports();
+    let mint = env.keypair();
+    let curve = pda::bonding_curve(&mint).0;
+    let mut ledger = Ledger {
+        env,
+        mint,
+        lamports,
+        supply: 0,
+    };
+
+    // create：创作者支付创建费用和各账户租金，全部代币铸造到曲线金库和迁移储备
+    let treasury_before = ledger.balance(&treasury);
+    let create = ledger.env.create_ix(creator, mint, 0);
+    ledger.send(&[create], &[creator, mint]);
+    assert_eq!(ledger.balance(&treasury) - treasury_before, ledger.env.config().creation_fee_sol);
+    assert_eq!(ledger.supply, 1_000_000_000_000_000);
+    ledger.assert_curve_backed();
+
+    // buy：买家付出的 SOL 恰好分到曲线和国库
+    let (alice_before, curve_before, treasury_before) =
+        (ledger.balance(&alice), ledger.balance(&curve), ledger.balance(&treasury));
+    let buy = ledger.env.buy_ix(alice, mint).buy(3 * SOL, 0, FAR_DEADLINE);
+    let bought = event::<BuyEvent>(&ledger.send(&[buy], &[alice]));
+    let ata_rent = ledger.balance(&pda::token_account(&alice, &mint));
+    assert_eq!(alice_before - ledger.balance(&alice), bought.sol_in + ata_rent);
+    assert_eq!(ledger.balance(&treasury) - treasury_before, bought.treasury_fee);
+    assert_eq!(
+        ledger.balance(&curve) - curve_before,
+        bought.sol_in - bought.treasury_fee,
+        "buyer's SOL must reach the curve"
+    );
+    assert_eq!(ledger.env.tokens(&alice, &mint), bought.tokens_out);
+    ledger.assert_curve_backed();
+
+    // sell：曲线付出卖家净额和国库费用，创作者费用留在曲线
+    let (alice_before, curve_before, treasury_before) =
+        (ledger.balance(&alice), ledger.balance(&curve), ledger.balance(&treasury));
+    let sell = ledger.env.sell_ix(alice, mint).sell(bought.tokens_out / 2, 0, FAR_DEADLINE);
+    let sold = event::<SellEvent>(&ledger.send(&[sell], &[alice]));
+    assert_eq!(ledger.balance(&alice) - alice_before, sold.sol_out);
+    assert_eq!(ledger.balance(&treasury) - treasury_before, sold.treasury_fee);
+    assert_eq!(curve_before - ledger.balance(&curve), sold.sol_out + sold.treasury_fee);
+    assert_eq!(ledger.env.tokens(&alice, &mint), bought.tokens_out - sold.tokens_in);
+    ledger.assert_curve_backed();
+
+    // claim：欠创作者的费用从曲线转给创作者
+    let owed = ledger.env.curve(&mint).creator_fees_owed;
+    assert_eq!(owed, bought.creator_fee + sold.creator_fee);
+    let creator_before = ledger.balance(&creator);
+    let claim = ix::ClaimCreatorFees {
+        creator,
+        creator_fee_recipient: creator,
+        token_mint: mint,
+    }
+    .instruction();
+    let claimed = event::<CreatorFeeClaimed>(&ledger.send(&[claim], &[creator]));
+    assert_eq!(claimed.amount, owed);
+    assert_eq!(ledger.balance(&creator) - creator_before, owed);
+    ledger.assert_curve_backed();
+
+    // 买满阈值，曲线完成
+    let buy = ledger.env.buy_ix(whale, mint).buy(60 * SOL, 0, FAR_DEADLINE);
+    ledger.send(&[buy], &[whale]);
+    assert!(ledger.env.curve(&mint).is_completed);
+    ledger.assert_curve_backed();
+
+    // migrate：真实SOL储备全部进入DEX金库，国库分文不取；总供应量只因销毁减少
+    let real_sol = ledger.env.curve(&mint).real_sol_reserves;
+    let owed = ledger.env.curve(&mint).creator_fees_owed;
+    let supply_before = ledger.supply;
+    let treasury_before = ledger.balance(&treasury);
+    let instructions = ledger.env.migrate_ixs(whale, mint);
+    let migrated = event::<DexMigrated>(&ledger.send(&instructions, &[whale]));
+    assert_eq!(migrated.sol_reserves, real_sol);
+    assert_eq!(ledger.balance(&treasury), treasury_before);
+    let vault_rent = ledger.env.svm.rent().minimum_balance(0);
+    assert_eq!(ledger.balance(&pda::dex_sol_vault(&mint).0), vault_rent + migrated.sol_reserves);
+    assert_eq!(ledger.supply + migrated.tokens_burned, supply_before);
+    assert_eq!(ledger.env.svm.token_amount(&pda::dex_token_vault(&mint)), migrated.token_reserves);
+    assert_eq!(ledger.env.curve(&mint).creator_fees_owed, owed);
+    ledger.assert_curve_backed();
+
+    // 迁移后仍可领取剩余的创作者费用
+    let claim = ix::ClaimCreatorFees {
+        creator,
+        creator_fee_recipient: creator,
+        token_mint: mint,
+    }
+    .instruction();
+    ledger.send(&[claim], &[creator]);
+    ledger.assert_curve_backed();
+
+    // DEX 兑换：SOL 进出金库与事件一致
+    let swap = ix::DexSwap {
+        user: alice,
+        token_mint: mint,
+    };
+    let vault = pda::dex_sol_vault(&mint).0;
+    let vault_before = ledger.balance(&vault);
+    let swapped = event::<DexSwapEvent>(&ledger.send(&[swap.sol_for_tokens(SOL, 1, FAR_DEADLINE)], &[alice]));
+    assert_eq!(ledger.balance(&vault) - vault_before, swapped.amount_in);
+    let tokens = ledger.env.tokens(&alice, &mint);
+    let vault_before = ledger.balance(&vault);
+    let swapped = event::<DexSwapEvent>(&ledger.send(&[swap.tokens_for_sol(tokens, 1, FAR_DEADLINE)], &[alice]));
+    assert_eq!(vault_before - ledger.balance(&vault), swapped.amount_out);
+    assert_eq!(ledger.env.tokens(&alice, &mint), 0);
+    assert_eq!(ledger.supply, supply_before - migrated.tokens_burned);
+}
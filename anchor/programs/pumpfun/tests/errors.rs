@@ -0,0 +1,909 @@
+//! 每个 `PumpError` 的触发路径，以及几条关键的账户约束。
+//! 少数防御性检查无法通过正常指令触达，测试直接改写账户状态来模拟。
+
+mod common;
+
+use anchor_lang::{error::ErrorCode, prelude::Pubkey};
+use common::*;
+use pumpfun::{ConfigChange, CouncilAction, CurveKind, CurvePreset, PumpError, PAUSE_ALL, PAUSE_BUY, PAUSE_MIGRATE};
+use pumpfun_client::{instructions as ix, pda};
+
+fn launched() -> (Env, Pubkey, Pubkey) {
+    // 函数：创建代币并让一个买家买入 0.1 SOL，返回 (环境, 铸币, 买家)
+    let mut env = Env::new();
+    let creator = env.user(10);
+    let mint = env.create(creator);
+    let buyer = env.user(10);
+    env.buy(buyer, mint, SOL / 10);
+    (env, mint, buyer)
+}
+
+fn completed() -> (Env, Pubkey, Pubkey) {
+    // 函数：买满迁移阈值，返回 (环境, 铸币, 买家)
+    let mut env = Env::new();
+    let creator = env.user(10);
+    let mint = env.create(creator);
+    let whale = env.user(100);
+    env.buy(whale, mint, 60 * SOL);
+    (env, mint, whale)
+}
+
+fn split(env: &mut Env, mint: Pubkey, recipients: Vec<Pubkey>) {
+    // 函数：创作者开启分账，接收者均分
+    let creator = env.curve(&mint).creator;
+    let split = ix::CreatorFeeSplit {
+        creator,
+        token_mint: mint,
+    };
+    let shares_bps = vec![10_000 / recipients.len() as u16; recipients.len()];
+    env.send(&[split.create(recipients, shares_bps)], &[creator]).unwrap();
+}
+
+fn set_lamports(env: &mut Env, address: &Pubkey, lamports: u64) {
+    let mut account = env.svm.account(address).unwrap();
+    account.lamports = lamports;
+    env.svm.set_account(*address, account);
+}
+
+#[test]
+fn curve_completed() {
+    let (mut env, mint, whale) = completed();
+    let buy = env.buy_ix(whale, mint).buy(SOL, 0, FAR_DEADLINE);
+    assert_pump_error(env.send(&[buy], &[whale]), PumpError::CurveCompleted);
+    let sell = env.sell_ix(whale, mint).sell(1_000_000, 0, FAR_DEADLINE);
+    assert_pump_error(env.send(&[sell], &[whale]), PumpError::CurveCompleted);
+    let creator = env.curve(&mint).creator;
+    let update = ix::UpdateTokenMetadata {
+        creator,
+        token_mint: mint,
+    }
+    .instruction("uri".into(), "https://example.com".into());
+    assert_pump_error(env.send(&[update], &[creator]), PumpError::CurveCompleted);
+}
+
+#[test]
+fn insufficient_sol_reserves() {
+    let (mut env, mint, buyer) = launched();
+    let instruction = env
+        .sell_ix(buyer, mint)
+        .sell_for_exact_sol(SOL / 2, u64::MAX, FAR_DEADLINE);
+    assert_pump_error(env.send(&[instruction], &[buyer]), PumpError::InsufficientSolReserves);
+}
+
+#[test]
+fn slippage_limit_exceeded() {
+    let (mut env, mint, buyer) = launched();
+    let buy = env.buy_ix(buyer, mint).buy(SOL, u64::MAX, FAR_DEADLINE);
+    assert_pump_error(env.send(&[buy], &[buyer]), PumpError::SlippageLimitExceeded);
+    let buy_exact = env.buy_ix(buyer, mint).buy_exact_tokens_out(1_000_000_000, 1, FAR_DEADLINE);
+    assert_pump_error(env.send(&[buy_exact], &[buyer]), PumpError::SlippageLimitExceeded);
+    let tokens = env.tokens(&buyer, &mint);
+    let sell = env.sell_ix(buyer, mint).sell(tokens, u64::MAX, FAR_DEADLINE);
+    assert_pump_error(env.send(&[sell], &[buyer]), PumpError::SlippageLimitExceeded);
+    let sell_exact = env.sell_ix(buyer, mint).sell_for_exact_sol(SOL / 20, 1, FAR_DEADLINE);
+    assert_pump_error(env.send(&[sell_exact], &[buyer]), PumpError::SlippageLimitExceeded);
+}
+
+#[test]
+fn invalid_metadata_length() {
+    let mut env = Env::new();
+    let creator = env.user(10);
+    let mint = env.keypair();
+    let create = ix::Create {
+        creator,
+        treasury: env.treasury,
+        token_mint: mint,
+    }
+    .instruction(String::new(), "PUMP".into(), String::new(), 0);
+    assert_pump_error(env.send(&[create], &[creator, mint]), PumpError::InvalidMetadataLength);
+
+    let mint = env.create(creator);
+    let update = ix::UpdateTokenMetadata {
+        creator,
+        token_mint: mint,
+    }
+    .instruction("uri".into(), "x".repeat(201));
+    assert_pump_error(env.send(&[update], &[creator]), PumpError::InvalidMetadataLength);
+}
+
+#[test]
+fn no_fees_to_claim() {
+    let mut env = Env::new();
+    let creator = env.user(10);
+    let mint = env.create(creator);
+    let claim = ix::ClaimCreatorFees {
+        creator,
+        creator_fee_recipient: creator,
+        token_mint: mint,
+    }
+    .instruction();
+    assert_pump_error(env.send(&[claim], &[creator]), PumpError::NoFeesToClaim);
+}
+
+#[test]
+fn protocol_paused() {
+    let (mut env, mint, buyer) = launched();
+    env.govern(env.governance().set_paused(PAUSE_ALL)).unwrap();
+    let creator = env.user(10);
+    let new_mint = env.keypair();
+    let create = env.create_ix(creator, new_mint, 0);
+    assert_pump_error(env.send(&[create], &[creator, new_mint]), PumpError::ProtocolPaused);
+    let buy = env.buy_ix(buyer, mint).buy(SOL, 0, FAR_DEADLINE);
+    assert_pump_error(env.send(&[buy], &[buyer]), PumpError::ProtocolPaused);
+    let sell = env.sell_ix(buyer, mint).sell(env.tokens(&buyer, &mint), 0, FAR_DEADLINE);
+    assert_pump_error(env.send(&[sell], &[buyer]), PumpError::ProtocolPaused);
+    let creator = env.curve(&mint).creator;
+    let claim = ix::ClaimCreatorFees {
+        creator,
+        creator_fee_recipient: creator,
+        token_mint: mint,
+    }
+    .instruction();
+    assert_pump_error(env.send(&[claim], &[creator]), PumpError::ProtocolPaused);
+
+    let (mut env, mint, whale) = completed();
+    env.govern(env.governance().set_paused(PAUSE_MIGRATE)).unwrap();
+    let instructions = env.migrate_ixs(whale, mint);
+    assert_pump_error(env.send(&instructions, &[whale]), PumpError::ProtocolPaused);
+}
+
+#[test]
+fn deadline_exceeded() {
+    let (mut env, mint, buyer) = launched();
+    let past = env.svm.clock().unix_timestamp - 1;
+    let buy = env.buy_ix(buyer, mint).buy(SOL, 0, past);
+    assert_pump_error(env.send(&[buy], &[buyer]), PumpError::DeadlineExceeded);
+    let sell = env.sell_ix(buyer, mint).sell(env.tokens(&buyer, &mint), 0, past);
+    assert_pump_error(env.send(&[sell], &[buyer]), PumpError::DeadlineExceeded);
+}
+
+#[test]
+fn trade_amount_too_small() {
+    let (mut env, mint, buyer) = launched();
+    let buy = env.buy_ix(buyer, mint).buy(999_999, 0, FAR_DEADLINE);
+    assert_pump_error(env.send(&[buy], &[buyer]), PumpError::TradeAmountTooSmall);
+    let sell = env.sell_ix(buyer, mint).sell(1, 0, FAR_DEADLINE);
+    assert_pump_error(env.send(&[sell], &[buyer]), PumpError::TradeAmountTooSmall);
+}
+
+#[test]
+fn insufficient_fee_reserves() {
+    let (mut env, mint, _) = launched();
+    let curve = pda::bonding_curve(&mint).0;
+    let balance = env.svm.balance(&curve);
+    set_lamports(&mut env, &curve, balance - 1); // 模拟 lamports 被挪走
+    let creator = env.curve(&mint).creator;
+    let claim = ix::ClaimCreatorFees {
+        creator,
+        creator_fee_recipient: creator,
+        token_mint: mint,
+    }
+    .instruction();
+    assert_pump_error(env.send(&[claim], &[creator]), PumpError::InsufficientFeeReserves);
+}
+
+#[test]
+fn already_migrated() {
+    let (mut env, mint, whale) = completed();
+    let mut curve = env.curve(&mint);
+    curve.dex_pool = Pubkey::new_unique(); // 迁移后金库已关闭，重复迁移无法通过账户校验，这里直接标记
+    env.store(&pda::bonding_curve(&mint).0, &curve);
+    let instructions = env.migrate_ixs(whale, mint);
+    let error = env.send(&instructions, &[whale]);
+    assert_eq!(error.as_ref().unwrap_err().instruction_error().unwrap().0, 1);
+    assert_pump_error(error, PumpError::AlreadyMigrated);
+}
+
+#[test]
+fn migration_threshold_not_met() {
+    let (mut env, mint, buyer) = launched();
+    let instructions = env.migrate_ixs(buyer, mint);
+    assert_pump_error(env.send(&instructions, &[buyer]), PumpError::MigrationThresholdNotMet);
+}
+
+#[test]
+fn lamport_invariant_violated() {
+    let (mut env, mint, buyer) = launched();
+    let curve = pda::bonding_curve(&mint).0;
+    let balance = env.svm.balance(&curve);
+    set_lamports(&mut env, &curve, balance - 1);
+    let buy = env.buy_ix(buyer, mint).buy(SOL, 0, FAR_DEADLINE);
+    assert_pump_error(env.send(&[buy], &[buyer]), PumpError::LamportInvariantViolated);
+}
+
+#[test]
+fn dex_pool_not_active() {
+    let (mut env, mint, buyer) = launched();
+    let init = ix::InitializeDexPool {
+        payer: buyer,
+        token_mint: mint,
+    }
+    .instruction();
+    env.send(&[init], &[buyer]).unwrap();
+    let swap = ix::DexSwap {
+        user: buyer,
+        token_mint: mint,
+    }
+    .sol_for_tokens(SOL, 0, FAR_DEADLINE);
+    assert_pump_error(env.send(&[swap], &[buyer]), PumpError::DexPoolNotActive);
+}
+
+#[test]
+fn dex_pool_empty() {
+    let (mut env, mint, whale) = completed();
+    let instructions = env.migrate_ixs(whale, mint);
+    env.send(&instructions, &[whale]).unwrap();
+    let sol_vault = pda::dex_sol_vault(&mint).0;
+    let rent = env.svm.rent().minimum_balance(0);
+    set_lamports(&mut env, &sol_vault, rent); // 模拟池子SOL耗尽
+    let swap = ix::DexSwap {
+        user: whale,
+        token_mint: mint,
+    }
+    .sol_for_tokens(SOL, 0, FAR_DEADLINE);
+    assert_pump_error(env.send(&[swap], &[whale]), PumpError::DexPoolEmpty);
+}
+
+#[test]
+fn insufficient_liquidity() {
+    let mut env = Env::new();
+    let creator = env.user(10);
+    let mint = env.create(creator);
+    let whale = env.graduate(mint);
+    let lp_supply = env.dex_pool(&mint).lp_supply;
+    let remove = ix::DexLiquidity {
+        user: whale,
+        token_mint: mint,
+    }
+    .remove_liquidity(lp_supply, 0, 0, FAR_DEADLINE);
+    assert_pump_error(env.send(&[remove], &[whale]), PumpError::InsufficientLiquidity);
+}
+
+#[test]
+fn invalid_token_vault() {
+    let (mut env, mint, buyer) = launched();
+    let mut buy = env.buy_ix(buyer, mint).buy(SOL, 0, FAR_DEADLINE);
+    let vault = pda::token_vault(&mint);
+    let meta = buy.accounts.iter_mut().find(|meta| meta.pubkey == vault).unwrap();
+    meta.pubkey = pda::migration_vault(&mint).0;
+    assert_pump_error(env.send(&[buy], &[buyer]), PumpError::InvalidTokenVault);
+}
+
+#[test]
+fn invalid_token_program() {
+    let mut env = Env::new(); // bank 自带 SPL Token 程序，把 Token-2022 换成它
+    let swap_program = |instruction: &mut anchor_lang::solana_program::instruction::Instruction| {
+        let meta = instruction
+            .accounts
+            .iter_mut()
+            .find(|meta| meta.pubkey == anchor_spl::token_2022::ID)
+            .unwrap();
+        meta.pubkey = anchor_spl::token::ID;
+    };
+    let creator = env.user(10);
+    let mint = env.keypair();
+    let mut create = env.create_ix(creator, mint, 0);
+    swap_program(&mut create);
+    assert_pump_error(env.send(&[create], &[creator, mint]), PumpError::InvalidTokenProgram);
+
+    let mint = env.create(creator);
+    let mut update = ix::UpdateTokenMetadata {
+        creator,
+        token_mint: mint,
+    }
+    .instruction("uri".into(), "https://example.com".into());
+    swap_program(&mut update);
+    assert_pump_error(env.send(&[update], &[creator]), PumpError::InvalidTokenProgram);
+}
+
+#[test]
+fn invalid_metadata_field() {
+    let mut env = Env::new();
+    let creator = env.user(10);
+    let mint = env.create(creator);
+    let update = ix::UpdateTokenMetadata {
+        creator,
+        token_mint: mint,
+    };
+    for field in ["name", "symbol"] {
+        let instruction = update.instruction(field.into(), "RUG".into());
+        assert_pump_error(env.send(&[instruction], &[creator]), PumpError::InvalidMetadataField);
+    }
+}
+
+#[test]
+fn too_many_metadata_fields() {
+    let mut env = Env::new();
+    let creator = env.user(10);
+    let mint = env.create(creator);
+    let update = ix::UpdateTokenMetadata {
+        creator,
+        token_mint: mint,
+    };
+    for index in 0..8 {
+        env.send(&[update.instruction(format!("field{index}"), "v".into())], &[creator])
+            .unwrap();
+    }
+    let ninth = update.instruction("field8".into(), "v".into());
+    assert_pump_error(env.send(&[ninth], &[creator]), PumpError::TooManyMetadataFields);
+}
+
+#[test]
+fn invalid_curve_preset() {
+    let mut env = Env::new();
+    let governance = env.governance;
+    let preset = CurvePreset {
+        virtual_sol_reserves: 1,
+        virtual_token_reserves: 1,
+        total_supply: 1,
+        decimals: 6,
+        curve_supply_bps: 10000,
+        curve_kind: CurveKind::ConstantProduct,
+    };
+    let gap = env.governance().set_curve_preset(2, preset); // 只能追加到末尾
+    assert_pump_error(env.send(&[gap], &[governance]), PumpError::InvalidCurvePreset);
+    let bad = CurvePreset {
+        curve_supply_bps: 0,
+        ..preset
+    };
+    let invalid = env.governance().set_curve_preset(1, bad);
+    assert_pump_error(env.send(&[invalid], &[governance]), PumpError::InvalidCurvePreset);
+
+    let creator = env.user(10);
+    let mint = env.keypair();
+    let create = env.create_ix(creator, mint, 1);
+    assert_pump_error(env.send(&[create], &[creator, mint]), PumpError::InvalidCurvePreset);
+
+    // 固定价格 1 lamport/单位：50 SOL 阈值时需要 5e10 单位迁移储备，迁移不增发
+    let fixed_price = CurvePreset {
+        virtual_sol_reserves: 0,
+        virtual_token_reserves: 0,
+        total_supply: 1_000_000_000_000,
+        decimals: 6,
+        curve_supply_bps: 9600,
+        curve_kind: CurveKind::Linear {
+            base_price: 1_000_000_000,
+            slope: 0,
+        },
+    };
+    let short_reserve = env.governance().set_curve_preset(1, fixed_price); // 储备 4e10
+    assert_pump_error(env.send(&[short_reserve], &[governance]), PumpError::InvalidCurvePreset);
+    let fixed_price = CurvePreset {
+        curve_supply_bps: 9000,
+        ..fixed_price
+    };
+    env.govern(env.governance().set_curve_preset(1, fixed_price)).unwrap(); // 储备 1e11
+    // 阈值提高到 200 SOL 后该预设的储备不够，变更无法执行
+    let change = ConfigChange::MigrationThreshold {
+        migration_threshold_sol: 200 * SOL,
+    };
+    let id = env.config().next_config_change_id;
+    env.queue(change).unwrap();
+    let delay = env.config().config_timelock_secs;
+    env.warp(delay);
+    let execute = env.governance().execute_config_change(id, &change);
+    assert_pump_error(env.govern(execute), PumpError::InvalidCurvePreset);
+}
+
+#[test]
+fn insufficient_token_reserves() {
+    let (mut env, mint, buyer) = launched();
+    let instruction = env.buy_ix(buyer, mint).buy_exact_tokens_out(0, u64::MAX, FAR_DEADLINE);
+    assert_pump_error(env.send(&[instruction], &[buyer]), PumpError::InsufficientTokenReserves);
+}
+
+#[test]
+fn exceeds_migration_threshold() {
+    let (mut env, mint, _) = launched();
+    let whale = env.user(200);
+    let instruction = env
+        .buy_ix(whale, mint)
+        .buy_exact_tokens_out(90_000_000_000, u64::MAX, FAR_DEADLINE);
+    assert_pump_error(env.send(&[instruction], &[whale]), PumpError::ExceedsMigrationThreshold);
+}
+
+#[test]
+fn math_overflow() {
+    let (mut env, mint, buyer) = launched();
+    let address = pda::bonding_curve(&mint).0;
+    let original = env.curve(&mint);
+
+    // buy：虚拟SOL储备加上买入金额超出 u64
+    let mut curve = env.curve(&mint);
+    curve.virtual_sol_reserves = u64::MAX - SOL / 2;
+    env.store(&address, &curve);
+    let buy = env.buy_ix(buyer, mint).buy(SOL, 0, FAR_DEADLINE);
+    assert_pump_error(env.send(&[buy], &[buyer]), PumpError::MathOverflow);
+
+    // sell：虚拟代币储备加上卖出数量超出 u64（虚拟SOL储备按比例放大，使卖出仍值约 0.05 SOL）
+    let tokens = env.tokens(&buyer, &mint);
+    let mut curve = original.clone();
+    curve.initial_virtual_token_reserves = u64::MAX;
+    curve.virtual_token_reserves = u64::MAX;
+    curve.virtual_sol_reserves = SOL / 20 * (u64::MAX / tokens);
+    env.store(&address, &curve);
+    let sell = env.sell_ix(buyer, mint).sell(tokens, 0, FAR_DEADLINE);
+    assert_pump_error(env.send(&[sell], &[buyer]), PumpError::MathOverflow);
+
+    // buy：费用变更在排队时拒绝超过总费用的创作者份额，直接改写配置模拟
+    env.store(&address, &original);
+    let mut config = env.config();
+    config.creator_fee_bps_share = config.total_trade_fee_bps + 1;
+    env.store(&pda::protocol_config().0, &config);
+    let buy = env.buy_ix(buyer, mint).buy(SOL, 0, FAR_DEADLINE);
+    assert_pump_error(env.send(&[buy], &[buyer]), PumpError::MathOverflow);
+
+    // complete_and_migrate：最终价格为零，无法按价格配对SOL（除以零）
+    let (mut env, mint, whale) = completed();
+    let mut curve = env.curve(&mint);
+    curve.virtual_sol_reserves = 0;
+    env.store(&pda::bonding_curve(&mint).0, &curve);
+    let instructions = env.migrate_ixs(whale, mint);
+    assert_pump_error(env.send(&instructions, &[whale]), PumpError::MathOverflow);
+}
+
+#[test]
+fn reserve_underflow() {
+    let (mut env, mint, buyer) = launched();
+    let instruction = env
+        .sell_ix(buyer, mint)
+        .sell_for_exact_sol(2 * SOL, u64::MAX, FAR_DEADLINE);
+    assert_pump_error(env.send(&[instruction], &[buyer]), PumpError::ReserveUnderflow);
+
+    // 虚拟代币储备高于初始值时已售出数量为负：buy、sell 和迁移定价都要拒绝
+    let address = pda::bonding_curve(&mint).0;
+    let mut curve = env.curve(&mint);
+    curve.initial_virtual_token_reserves = curve.virtual_token_reserves - 1;
+    env.store(&address, &curve);
+    let buy = env.buy_ix(buyer, mint).buy(SOL, 0, FAR_DEADLINE);
+    assert_pump_error(env.send(&[buy], &[buyer]), PumpError::ReserveUnderflow);
+    let sell = env.sell_ix(buyer, mint).sell(env.tokens(&buyer, &mint), 0, FAR_DEADLINE);
+    assert_pump_error(env.send(&[sell], &[buyer]), PumpError::ReserveUnderflow);
+
+    let (mut env, mint, whale) = completed();
+    let mut curve = env.curve(&mint);
+    curve.initial_virtual_token_reserves = curve.virtual_token_reserves - 1;
+    env.store(&pda::bonding_curve(&mint).0, &curve);
+    let instructions = env.migrate_ixs(whale, mint);
+    assert_pump_error(env.send(&instructions, &[whale]), PumpError::ReserveUnderflow);
+}
+
+#[test]
+fn invalid_governance_proposal() {
+    let mut env = Env::new();
+    let governance = env.governance;
+    for nominee in [Pubkey::default(), governance] {
+        let propose = env.governance().propose_governance(nominee);
+        assert_pump_error(env.send(&[propose], &[governance]), PumpError::InvalidGovernanceProposal);
+    }
+}
+
+#[test]
+fn no_pending_governance() {
+    let mut env = Env::new();
+    let governance = env.governance;
+    let cancel = env.governance().cancel_governance_proposal();
+    assert_pump_error(env.send(&[cancel], &[governance]), PumpError::NoPendingGovernance);
+}
+
+#[test]
+fn not_pending_governance() {
+    let mut env = Env::new();
+    let governance = env.governance;
+    let (nominee, intruder) = (env.user(1), env.user(1));
+    env.send(&[env.governance().propose_governance(nominee)], &[governance])
+        .unwrap();
+    let accept = ix::AcceptGovernance {
+        pending_governance: intruder,
+    }
+    .instruction();
+    assert_pump_error(env.send(&[accept], &[intruder]), PumpError::NotPendingGovernance);
+}
+
+#[test]
+fn fee_exceeds_cap() {
+    let mut env = Env::new();
+    for (total, lp) in [(1_001, 25), (30, 1_001)] {
+        let fees = ConfigChange::Fees {
+            total_trade_fee_bps: total,
+            creator_fee_bps_share: 10,
+            dex_lp_fee_bps: lp,
+        };
+        assert_pump_error(env.queue(fees), PumpError::FeeExceedsCap);
+    }
+}
+
+#[test]
+fn creator_share_exceeds_total() {
+    let mut env = Env::new();
+    let fees = ConfigChange::Fees {
+        total_trade_fee_bps: 30,
+        creator_fee_bps_share: 31,
+        dex_lp_fee_bps: 25,
+    };
+    assert_pump_error(env.queue(fees), PumpError::CreatorShareExceedsTotal);
+}
+
+#[test]
+fn creation_fee_exceeds_cap() {
+    let mut env = Env::new();
+    let creation_fee = ConfigChange::CreationFee {
+        creation_fee_sol: 10 * SOL + 1,
+    };
+    assert_pump_error(env.queue(creation_fee), PumpError::CreationFeeExceedsCap);
+}
+
+#[test]
+fn invalid_migration_threshold() {
+    let mut env = Env::new();
+    for threshold in [SOL - 1, 10_000 * SOL + 1] {
+        let change = ConfigChange::MigrationThreshold {
+            migration_threshold_sol: threshold,
+        };
+        assert_pump_error(env.queue(change), PumpError::InvalidMigrationThreshold);
+    }
+}
+
+#[test]
+fn timelock_not_elapsed() {
+    let mut env = Env::new();
+    let change = ConfigChange::CreationFee { creation_fee_sol: 0 };
+    env.queue(change).unwrap();
+    env.warp(86_399);
+    let execute = env.governance().execute_config_change(0, &change);
+    assert_pump_error(env.govern(execute), PumpError::TimelockNotElapsed);
+}
+
+#[test]
+fn invalid_timelock_delay() {
+    let mut env = Env::new();
+    for delay_secs in [3_599, 30 * 86_400 + 1] {
+        let change = ConfigChange::TimelockDelay { delay_secs };
+        assert_pump_error(env.queue(change), PumpError::InvalidTimelockDelay);
+    }
+}
+
+#[test]
+fn invalid_treasury() {
+    let mut env = Env::new();
+    let change = ConfigChange::Treasury {
+        treasury: Pubkey::new_unique(),
+    };
+    env.queue(change).unwrap();
+    env.warp(86_400);
+    // 不传新国库账户
+    let execute = env
+        .governance()
+        .execute_config_change(0, &ConfigChange::CreationFee { creation_fee_sol: 0 });
+    assert_pump_error(env.govern(execute), PumpError::InvalidTreasury);
+}
+
+#[test]
+fn invalid_council() {
+    let mut env = Env::new();
+    let member = env.user(1);
+    let cases = [
+        (vec![], 1),                         // 没有成员
+        (vec![member], 0),                   // 门槛为 0
+        (vec![member], 2),                   // 门槛超过成员数
+        (vec![member, member], 1),           // 重复成员
+        (vec![Pubkey::default()], 1),        // 空地址
+        (vec![Pubkey::new_unique(); 11], 1), // 超过成员上限
+    ];
+    for (members, threshold) in cases {
+        let create = env.governance().create_governance_council(members, threshold);
+        assert_pump_error(env.govern(create), PumpError::InvalidCouncil);
+    }
+}
+
+#[test]
+fn not_council_member() {
+    let mut env = Env::new();
+    env.create_council(2, 2);
+    let outsider = env.user(1);
+    let propose = ix::Council { member: outsider }.propose(0, CouncilAction::AcceptGovernance);
+    assert_pump_error(env.send(&[propose], &[outsider]), PumpError::NotCouncilMember);
+}
+
+#[test]
+fn proposal_already_approved() {
+    let mut env = Env::new();
+    let members = env.create_council(2, 2);
+    let member = ix::Council { member: members[0] };
+    env.send(&[member.propose(0, CouncilAction::AcceptGovernance)], &[members[0]])
+        .unwrap();
+    assert_pump_error(env.send(&[member.approve(0)], &[members[0]]), PumpError::ProposalAlreadyApproved);
+}
+
+#[test]
+fn council_threshold_not_met() {
+    let mut env = Env::new();
+    let members = env.create_council(3, 2);
+    env.hand_to_council(&members);
+    let member = ix::Council { member: members[0] };
+    let set_paused = ix::Council::governance().set_paused(PAUSE_ALL);
+    env.send(&[member.propose(1, CouncilAction::SetPaused { paused: PAUSE_ALL })], &[members[0]])
+        .unwrap();
+    let execute = member.execute(1, members[0], Some(&set_paused));
+    assert_pump_error(env.send(&[execute], &[members[0]]), PumpError::CouncilThresholdNotMet);
+}
+
+#[test]
+fn stale_council_proposal() {
+    let mut env = Env::new();
+    let members = env.create_council(2, 2);
+    let member = ix::Council { member: members[0] };
+    env.send(&[member.propose(0, CouncilAction::AcceptGovernance)], &[members[0]])
+        .unwrap();
+    let mut same_members = [Pubkey::default(); 10];
+    same_members[..2].copy_from_slice(&members);
+    let update = CouncilAction::UpdateCouncil {
+        members: same_members,
+        member_count: 2,
+        threshold: 1,
+    };
+    env.pass(&members, update, None).unwrap();
+    let approve = ix::Council { member: members[1] }.approve(0);
+    assert_pump_error(env.send(&[approve], &[members[1]]), PumpError::StaleCouncilProposal);
+}
+
+#[test]
+fn invalid_pause_flags() {
+    let mut env = Env::new();
+    let undefined = env.governance().set_paused(PAUSE_ALL + 1);
+    assert_pump_error(env.govern(undefined), PumpError::InvalidPauseFlags);
+    let guardian = env.user(1);
+    env.govern(env.governance().set_guardian(guardian)).unwrap();
+    let nothing = ix::GuardianPause { guardian }.instruction(0);
+    assert_pump_error(env.send(&[nothing], &[guardian]), PumpError::InvalidPauseFlags);
+}
+
+#[test]
+fn not_guardian() {
+    let mut env = Env::new();
+    // 未设置守护者时没有人能以守护者身份暂停
+    let intruder = env.user(1);
+    let pause = ix::GuardianPause { guardian: intruder };
+    assert_pump_error(env.send(&[pause.instruction(PAUSE_BUY)], &[intruder]), PumpError::NotGuardian);
+    let guardian = env.user(1);
+    env.govern(env.governance().set_guardian(guardian)).unwrap();
+    assert_pump_error(env.send(&[pause.instruction(PAUSE_BUY)], &[intruder]), PumpError::NotGuardian);
+}
+
+#[test]
+fn curve_frozen() {
+    let (mut env, mint, buyer) = launched();
+    env.govern(env.governance().freeze_curve(mint, true)).unwrap();
+    let buy = env.buy_ix(buyer, mint).buy(SOL, 0, FAR_DEADLINE);
+    assert_pump_error(env.send(&[buy], &[buyer]), PumpError::CurveFrozen);
+    let sell = env.sell_ix(buyer, mint).sell(env.tokens(&buyer, &mint), 0, FAR_DEADLINE);
+    assert_pump_error(env.send(&[sell], &[buyer]), PumpError::CurveFrozen);
+
+    let (mut env, mint, whale) = completed();
+    env.govern(env.governance().emergency_unwind(mint, true)).unwrap();
+    let instructions = env.migrate_ixs(whale, mint);
+    assert_pump_error(env.send(&instructions, &[whale]), PumpError::CurveFrozen);
+}
+
+#[test]
+fn curve_not_unwinding() {
+    let (mut env, mint, buyer) = launched();
+    let redeem = ix::EmergencyRedeem {
+        holder: buyer,
+        token_mint: mint,
+    }
+    .instruction(env.tokens(&buyer, &mint));
+    assert_pump_error(env.send(&[redeem], &[buyer]), PumpError::CurveNotUnwinding);
+}
+
+#[test]
+fn redemptions_started() {
+    // 清退、兑付后再撤销清退：曲线储备已按比例付出，不能按曲线价格恢复交易
+    let (mut env, mint, buyer) = launched();
+    env.govern(env.governance().emergency_unwind(mint, true)).unwrap();
+    let redeem = ix::EmergencyRedeem {
+        holder: buyer,
+        token_mint: mint,
+    }
+    .instruction(env.tokens(&buyer, &mint) / 2);
+    env.send(&[redeem], &[buyer]).unwrap();
+    assert_pump_error(
+        env.govern(env.governance().emergency_unwind(mint, false)),
+        PumpError::RedemptionsStarted,
+    );
+    assert!(env.curve(&mint).is_unwinding);
+    let buy = env.buy_ix(buyer, mint).buy(SOL, 0, FAR_DEADLINE);
+    assert_pump_error(env.send(&[buy], &[buyer]), PumpError::CurveFrozen);
+}
+
+#[test]
+fn invalid_vesting_schedule() {
+    let mut env = Env::new();
+    let creator = env.user(10);
+    let mint = env.create(creator);
+    let vesting = ix::CreatorFeeVesting {
+        creator,
+        token_mint: mint,
+    };
+    for (cliff_secs, duration_secs) in [(0, 0), (101, 100), (-1, 100), (0, 5 * 365 * 86_400)] {
+        let create = vesting.create(cliff_secs, duration_secs);
+        assert_pump_error(env.send(&[create], &[creator]), PumpError::InvalidVestingSchedule);
+    }
+}
+
+#[test]
+fn creator_fees_vesting() {
+    let (mut env, mint, _) = launched();
+    let creator = env.curve(&mint).creator;
+    let vest = ix::CreatorFeeVesting {
+        creator,
+        token_mint: mint,
+    }
+    .create(0, 86_400);
+    env.send(&[vest], &[creator]).unwrap();
+    let claim = ix::ClaimCreatorFees {
+        creator,
+        creator_fee_recipient: creator,
+        token_mint: mint,
+    }
+    .instruction();
+    assert_pump_error(env.send(&[claim], &[creator]), PumpError::CreatorFeesVesting);
+}
+
+#[test]
+fn invalid_creator_transfer() {
+    let (mut env, mint, _) = launched();
+    let creator = env.curve(&mint).creator;
+    let rights = ix::Creator {
+        creator,
+        token_mint: mint,
+    };
+    for nominee in [Pubkey::default(), creator] {
+        let transfer = rights.transfer_creator(nominee);
+        assert_pump_error(env.send(&[transfer], &[creator]), PumpError::InvalidCreatorTransfer);
+    }
+}
+
+#[test]
+fn no_pending_creator() {
+    let (mut env, mint, buyer) = launched();
+    let creator = env.curve(&mint).creator;
+    let rights = ix::Creator {
+        creator,
+        token_mint: mint,
+    };
+    env.send(&[rights.transfer_creator(buyer)], &[creator]).unwrap();
+    env.send(&[rights.cancel_creator_transfer()], &[creator]).unwrap();
+    assert_eq!(env.curve(&mint).pending_creator, Pubkey::default());
+    assert_pump_error(env.send(&[rights.cancel_creator_transfer()], &[creator]), PumpError::NoPendingCreator);
+}
+
+#[test]
+fn not_pending_creator() {
+    let (mut env, mint, buyer) = launched();
+    let creator = env.curve(&mint).creator;
+    let nominee = env.user(1);
+    let transfer = ix::Creator {
+        creator,
+        token_mint: mint,
+    }
+    .transfer_creator(nominee);
+    env.send(&[transfer], &[creator]).unwrap();
+    let accept = ix::AcceptCreator {
+        pending_creator: buyer,
+        token_mint: mint,
+    };
+    assert_pump_error(env.send(&[accept.instruction()], &[buyer]), PumpError::NotPendingCreator);
+}
+
+#[test]
+fn invalid_fee_recipient() {
+    let (mut env, mint, buyer) = launched();
+    let creator = env.curve(&mint).creator;
+    let rights = ix::Creator {
+        creator,
+        token_mint: mint,
+    };
+    let default = rights.set_creator_fee_recipient(Pubkey::default());
+    assert_pump_error(env.send(&[default], &[creator]), PumpError::InvalidFeeRecipient);
+    // 领取时传入的接收者必须是曲线记录的接收者
+    let claim = ix::ClaimCreatorFees {
+        creator,
+        creator_fee_recipient: buyer,
+        token_mint: mint,
+    }
+    .instruction();
+    assert_pump_error(env.send(&[claim], &[creator]), PumpError::InvalidFeeRecipient);
+}
+
+#[test]
+fn invalid_fee_split() {
+    let (mut env, mint, buyer) = launched();
+    let creator = env.curve(&mint).creator;
+    let split = ix::CreatorFeeSplit {
+        creator,
+        token_mint: mint,
+    };
+    let too_many: Vec<Pubkey> = (0..9).map(|_| Pubkey::new_unique()).collect();
+    let mut too_many_shares = vec![1_000; 9];
+    too_many_shares[0] = 2_000;
+    for (recipients, shares_bps) in [
+        (vec![], vec![]),                                     // 没有接收者
+        (vec![buyer, creator], vec![5_000, 4_999]),           // 合计不足 10000
+        (vec![buyer, buyer], vec![5_000, 5_000]),             // 重复
+        (vec![buyer, creator], vec![10_000, 0]),              // 零比例
+        (vec![buyer, Pubkey::default()], vec![5_000, 5_000]), // 空地址
+        (vec![buyer], vec![5_000, 5_000]),                    // 数量不一致
+        (too_many, too_many_shares),                          // 超过上限
+    ] {
+        assert_pump_error(env.send(&[split.create(recipients, shares_bps)], &[creator]), PumpError::InvalidFeeSplit);
+    }
+}
+
+#[test]
+fn fee_split_recipient_mismatch() {
+    let (mut env, mint, buyer) = launched();
+    let creator = env.curve(&mint).creator;
+    let partner = env.user(1);
+    split(&mut env, mint, vec![creator, partner]);
+    let claim = ix::ClaimCreatorFees {
+        creator,
+        creator_fee_recipient: pda::fee_split(&mint).0,
+        token_mint: mint,
+    };
+    for recipients in [vec![partner, creator], vec![creator], vec![creator, buyer]] {
+        assert_pump_error(
+            env.send(&[claim.distribute(&recipients)], &[creator]),
+            PumpError::FeeSplitRecipientMismatch,
+        );
+    }
+}
+
+#[test]
+fn not_fee_split_recipient() {
+    let (mut env, mint, buyer) = launched();
+    let partner = env.user(1);
+    split(&mut env, mint, vec![partner]);
+    let pull = ix::ClaimFeeSplitShare {
+        recipient: buyer,
+        token_mint: mint,
+    };
+    assert_pump_error(env.send(&[pull.instruction()], &[buyer]), PumpError::NotFeeSplitRecipient);
+}
+
+#[test]
+fn fee_split_active() {
+    let (mut env, mint, buyer) = launched();
+    let creator = env.curve(&mint).creator;
+    split(&mut env, mint, vec![creator]);
+    let rights = ix::Creator {
+        creator,
+        token_mint: mint,
+    };
+    assert_pump_error(
+        env.send(&[rights.set_creator_fee_recipient(buyer)], &[creator]),
+        PumpError::FeeSplitActive,
+    );
+}
+
+#[test]
+fn governance_and_creator_constraints() {
+    let (mut env, mint, buyer) = launched();
+    let intruder = ix::Governance {
+        governance_authority: buyer,
+    }
+    .set_paused(PAUSE_ALL);
+    assert_anchor_error(env.send(&[intruder], &[buyer]), ErrorCode::ConstraintHasOne);
+
+    let claim = ix::ClaimCreatorFees {
+        creator: buyer,
+        creator_fee_recipient: buyer,
+        token_mint: mint,
+    }
+    .instruction();
+    assert_anchor_error(env.send(&[claim], &[buyer]), ErrorCode::ConstraintAddress);
+
+    let mut unsigned = env.buy_ix(buyer, mint).buy(SOL, 0, FAR_DEADLINE);
+    unsigned.accounts[0].is_signer = false; // 交易里没有买家的签名
+    assert_anchor_error(env.send(&[unsigned], &[]), ErrorCode::AccountNotSigner);
+}
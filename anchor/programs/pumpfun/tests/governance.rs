@@ -0,0 +1,217 @@
+//! 治理权限的两步转移（提名、接受和撤回），以及 M-of-N 治理委员会经由提案行使治理权限。
+
+mod common;
+
+use anchor_lang::{error::ErrorCode, prelude::Pubkey};
+use common::*;
+use pumpfun::{
+    ConfigChange, CouncilAction, CouncilProposalApproved, CouncilProposalCancelled, CouncilProposalCreated,
+    CouncilProposalExecuted, GovernanceAccepted, GovernanceCouncilUpdated, GovernanceProposalCancelled,
+    GovernanceProposed, PausedUpdated, PAUSE_ALL, PAUSE_BUY,
+};
+use pumpfun_client::{instructions as ix, pda};
+
+#[test]
+fn propose_and_accept_transfers_governance() {
+    let mut env = Env::new();
+    let old = env.governance;
+    let new = env.user(1);
+    let meta = env.send(&[env.governance().propose_governance(new)], &[old]).unwrap();
+    let proposed = event::<GovernanceProposed>(&meta);
+    assert_eq!((proposed.governance, proposed.pending_governance), (old, new));
+    assert_eq!(env.config().pending_governance, new);
+    assert_eq!(env.config().governance_authority, old); // 接受之前仍由原权限治理
+
+    let accept = ix::AcceptGovernance { pending_governance: new }.instruction();
+    let accepted = event::<GovernanceAccepted>(&env.send(&[accept], &[new]).unwrap());
+    assert_eq!((accepted.previous_governance, accepted.new_governance), (old, new));
+    let config = env.config();
+    assert_eq!(config.governance_authority, new);
+    assert_eq!(config.pending_governance, Pubkey::default());
+
+    // 原权限失去治理能力，新权限可以治理
+    assert_anchor_error(env.send(&[env.governance().propose_governance(old)], &[old]), ErrorCode::ConstraintHasOne);
+    env.governance = new;
+    env.govern(env.governance().set_paused(PAUSE_ALL)).unwrap();
+    assert_eq!(env.config().paused, PAUSE_ALL);
+}
+
+#[test]
+fn a_new_proposal_replaces_the_previous_one() {
+    let mut env = Env::new();
+    let governance = env.governance;
+    let (first, second) = (env.user(1), env.user(1));
+    env.send(&[env.governance().propose_governance(first)], &[governance]).unwrap();
+    env.send(&[env.governance().propose_governance(second)], &[governance]).unwrap();
+    assert_eq!(env.config().pending_governance, second);
+    let accept = ix::AcceptGovernance { pending_governance: first }.instruction();
+    assert!(env.send(&[accept], &[first]).is_err());
+}
+
+#[test]
+fn cancel_withdraws_the_proposal() {
+    let mut env = Env::new();
+    let governance = env.governance;
+    let nominee = env.user(1);
+    env.send(&[env.governance().propose_governance(nominee)], &[governance]).unwrap();
+    let meta = env.send(&[env.governance().cancel_governance_proposal()], &[governance]).unwrap();
+    let cancelled = event::<GovernanceProposalCancelled>(&meta);
+    assert_eq!((cancelled.governance, cancelled.cancelled_governance), (governance, nominee));
+    assert_eq!(env.config().pending_governance, Pubkey::default());
+
+    let accept = ix::AcceptGovernance { pending_governance: nominee }.instruction();
+    assert!(env.send(&[accept], &[nominee]).is_err());
+    assert_eq!(env.config().governance_authority, governance);
+}
+
+#[test]
+fn council_takes_over_governance() {
+    let mut env = Env::new();
+    let previous = env.governance;
+    let members = env.create_council(3, 2);
+    let council = env.council();
+    assert_eq!((council.members(), council.threshold), (&members[..], 2));
+
+    env.hand_to_council(&members);
+    assert_eq!(env.config().governance_authority, pda::council_authority().0);
+    // 原权限和单个成员都不能直接治理
+    for key in [previous, members[0]] {
+        let direct = ix::Governance {
+            governance_authority: key,
+        }
+        .set_paused(PAUSE_ALL);
+        assert_anchor_error(env.send(&[direct], &[key]), ErrorCode::ConstraintHasOne);
+    }
+}
+
+#[test]
+fn council_proposal_pauses_once_approved() {
+    let mut env = Env::new();
+    let members = env.create_council(3, 2);
+    env.hand_to_council(&members);
+    let action = CouncilAction::SetPaused { paused: PAUSE_BUY };
+    let set_paused = ix::Council::governance().set_paused(PAUSE_BUY);
+    let (proposer, approver) = (members[0], members[2]);
+    let proposer_balance = env.svm.balance(&proposer);
+
+    let meta = env.send(&[ix::Council { member: proposer }.propose(1, action)], &[proposer]).unwrap();
+    let created = event::<CouncilProposalCreated>(&meta);
+    assert_eq!((created.id, created.proposer, created.action), (1, proposer, action));
+    assert_eq!(env.account::<pumpfun::CouncilProposal>(&pda::council_proposal(1).0).approval_count(), 1);
+
+    let meta = env.send(&[ix::Council { member: approver }.approve(1)], &[approver]).unwrap();
+    let approved = event::<CouncilProposalApproved>(&meta);
+    assert_eq!((approved.id, approved.member, approved.approvals), (1, approver, 2));
+
+    // 任何成员都可以执行，提案账户的租金退还给提案人
+    let execute = ix::Council { member: approver }.execute(1, proposer, Some(&set_paused));
+    let meta = env.send(&[execute], &[approver]).unwrap();
+    assert_eq!(event::<CouncilProposalExecuted>(&meta).action, action);
+    assert_eq!(event::<PausedUpdated>(&meta).new_paused, PAUSE_BUY);
+    assert_eq!(env.config().paused, PAUSE_BUY);
+    assert!(env.svm.account(&pda::council_proposal(1).0).is_none());
+    assert_eq!(env.svm.balance(&proposer), proposer_balance);
+}
+
+#[test]
+fn council_routes_treasury_through_the_timelock() {
+    let mut env = Env::new();
+    let members = env.create_council(2, 2);
+    env.hand_to_council(&members);
+    let new_treasury = env.user(1);
+    let change = ConfigChange::Treasury {
+        treasury: new_treasury,
+    };
+    let governance = ix::Council::governance();
+    let queue = governance.queue_config_change(0, change);
+    env.pass(&members, CouncilAction::QueueConfigChange { change }, Some(&queue))
+        .unwrap();
+    env.warp(env.config().config_timelock_secs);
+    let execute = governance.execute_config_change(0, &change);
+    env.pass(&members, CouncilAction::ExecuteConfigChange { id: 0 }, Some(&execute))
+        .unwrap();
+    assert_eq!(env.config().treasury, new_treasury);
+    assert!(env.svm.account(&pda::pending_config_change(0).0).is_none());
+}
+
+#[test]
+fn council_proposal_cannot_run_a_different_instruction() {
+    let mut env = Env::new();
+    let members = env.create_council(2, 2);
+    env.hand_to_council(&members);
+    // 批准的是撤回 0 号变更，执行时却传入 1 号变更的账户：账户由指令自身的种子约束拒绝
+    let change = ConfigChange::CreationFee { creation_fee_sol: 0 };
+    let governance = ix::Council::governance();
+    for id in 0..2 {
+        let queue = governance.queue_config_change(id, change);
+        env.pass(&members, CouncilAction::QueueConfigChange { change }, Some(&queue))
+            .unwrap();
+    }
+    let wrong = governance.cancel_config_change(1);
+    assert_anchor_error(
+        env.pass(&members, CouncilAction::CancelConfigChange { id: 0 }, Some(&wrong)),
+        ErrorCode::ConstraintSeeds,
+    );
+    assert!(env.svm.account(&pda::pending_config_change(1).0).is_some());
+}
+
+#[test]
+fn council_freezes_only_the_approved_curve() {
+    let mut env = Env::new();
+    let members = env.create_council(2, 2);
+    env.hand_to_council(&members);
+    let creator = env.user(10);
+    let (approved, other) = (env.create(creator), env.create(creator));
+    let action = CouncilAction::FreezeCurve {
+        mint: approved,
+        frozen: true,
+    };
+    let governance = ix::Council::governance();
+    // 提案锁定了铸币：换成另一条曲线的账户会被种子约束拒绝
+    assert_anchor_error(
+        env.pass(&members, action, Some(&governance.freeze_curve(other, true))),
+        ErrorCode::ConstraintSeeds,
+    );
+    env.pass(&members, action, Some(&governance.freeze_curve(approved, true)))
+        .unwrap();
+    assert!(env.curve(&approved).is_frozen);
+    assert!(!env.curve(&other).is_frozen);
+}
+
+#[test]
+fn update_council_rotates_members() {
+    let mut env = Env::new();
+    let members = env.create_council(2, 2);
+    let newcomer = env.user(1);
+    let mut new_members = [Pubkey::default(); 10];
+    new_members[..3].copy_from_slice(&[members[0], members[1], newcomer]);
+    let action = CouncilAction::UpdateCouncil {
+        members: new_members,
+        member_count: 3,
+        threshold: 2,
+    };
+    let meta = env.pass(&members, action, None).unwrap();
+    let updated = event::<GovernanceCouncilUpdated>(&meta);
+    assert_eq!(updated.members, [members[0], members[1], newcomer]);
+    assert_eq!((updated.threshold, updated.generation), (2, 1));
+    assert_eq!(env.council().members(), [members[0], members[1], newcomer]);
+
+    // 新成员可以批准
+    env.pass(&[newcomer, members[0]], action, None).unwrap();
+    assert_eq!(env.council().generation, 2);
+}
+
+#[test]
+fn proposer_cancels_a_proposal() {
+    let mut env = Env::new();
+    let members = env.create_council(2, 2);
+    let action = CouncilAction::SetPaused { paused: PAUSE_ALL };
+    let proposer = members[0];
+    env.send(&[ix::Council { member: proposer }.propose(0, action)], &[proposer]).unwrap();
+    let meta = env.send(&[ix::Council { member: proposer }.cancel(0)], &[proposer]).unwrap();
+    let cancelled = event::<CouncilProposalCancelled>(&meta);
+    assert_eq!((cancelled.id, cancelled.action), (0, action));
+    assert!(env.svm.account(&pda::council_proposal(0).0).is_none());
+    let other = members[1];
+    assert!(env.send(&[ix::Council { member: other }.approve(0)], &[other]).is_err());
+}
@@ -0,0 +1,1129 @@
+//! 每条指令的成功路径：检查账户状态、代币余额、SOL 流向和事件。
+
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use common::*;
+use pumpfun::{
+    BondingCurveCompleted, BuyEvent, ConfigChange, ConfigChangeCancelled, ConfigChangeExecuted, ConfigChangeQueued,
+    ConfigInitialized, CreationFeeUpdated, CreatorFeeClaimed, CreatorFeeRecipientUpdated, CreatorTransferProposed,
+    CreatorTransferred, CreatorVesting, CreatorVestingClaimed, CreatorVestingCreated, CurveFreezeUpdated, CurveKind,
+    CurvePreset, CurvePresetUpdated, DexMigrated, DexSwapEvent, EmergencyRedeemed, EmergencyUnwindUpdated, FeeSplit,
+    FeeSplitClaimed, FeeSplitUpdated, FeesUpdated, GuardianUpdated, LiquidityAdded, LiquidityRemoved,
+    MigrationThresholdUpdated, PausedUpdated, PendingConfigChange, PumpError, SellEvent, TimelockDelayUpdated,
+    TokenCreated, TokenMetadataFrozen, TokenMetadataUpdated, TreasuryUpdated, PAUSE_BUY, PAUSE_CLAIM, PAUSE_SELL,
+};
+use pumpfun_client::{instructions as ix, pda};
+use pumpfun_svm::{Keypair, Signer};
+
+#[test]
+fn initialize_config_sets_defaults() {
+    let mut svm = pumpfun_svm::Svm::new(program_test());
+    let signer = Keypair::new();
+    let authority = signer.pubkey();
+    let treasury = Pubkey::new_unique();
+    svm.airdrop_sol(&authority, 1);
+    let meta = svm
+        .process_transaction(&[ix::InitializeConfig { authority, treasury }.instruction()], &[&signer])
+        .unwrap();
+    let event = event::<ConfigInitialized>(&meta);
+    assert_eq!((event.governance, event.treasury), (authority, treasury));
+
+    let data = svm.account(&pda::protocol_config().0).unwrap().data;
+    let config = pumpfun_client::accounts::protocol_config(&data).unwrap();
+    assert_eq!(config.governance_authority, authority);
+    assert_eq!(config.treasury, treasury);
+    assert_eq!(config.total_trade_fee_bps, 30);
+    assert_eq!(config.curve_preset_count, 1);
+    assert_eq!(config.config_timelock_secs, 86_400);
+    assert_eq!(config.paused, 0);
+    assert_eq!(config.guardian, Pubkey::default());
+}
+
+#[test]
+fn fee_change_updates_fees_and_reports_old_values() {
+    let mut env = Env::new();
+    let meta = env.change_config(ConfigChange::Fees {
+        total_trade_fee_bps: 100,
+        creator_fee_bps_share: 40,
+        dex_lp_fee_bps: 50,
+    });
+    let event = event::<FeesUpdated>(&meta);
+    assert_eq!((event.old_total_trade_fee_bps, event.new_total_trade_fee_bps), (30, 100));
+    assert_eq!((event.old_creator_fee_bps_share, event.new_creator_fee_bps_share), (10, 40));
+    assert_eq!((event.old_dex_lp_fee_bps, event.new_dex_lp_fee_bps), (25, 50));
+    let config = env.config();
+    assert_eq!(
+        (config.total_trade_fee_bps, config.creator_fee_bps_share, config.dex_lp_fee_bps),
+        (100, 40, 50)
+    );
+    assert_eq!(config.curve_preset_count, 1);
+    assert_eq!(config.bump, pda::protocol_config().1);
+}
+
+#[test]
+fn treasury_change_routes_fees_to_the_new_treasury() {
+    let mut env = Env::new();
+    let new_treasury = env.user(1);
+    let meta = env.change_config(ConfigChange::Treasury { treasury: new_treasury });
+    let event = event::<TreasuryUpdated>(&meta);
+    assert_eq!((event.old_treasury, event.new_treasury), (env.treasury, new_treasury));
+    env.treasury = new_treasury;
+
+    let creator = env.user(10);
+    env.create(creator);
+    assert_eq!(env.svm.balance(&new_treasury), 2 * SOL);
+}
+
+#[test]
+fn creation_fee_and_migration_threshold_changes() {
+    let mut env = Env::new();
+    let meta = env.change_config(ConfigChange::CreationFee { creation_fee_sol: 0 });
+    let fee = event::<CreationFeeUpdated>(&meta);
+    assert_eq!((fee.old_creation_fee_sol, fee.new_creation_fee_sol), (SOL, 0));
+    let meta = env.change_config(ConfigChange::MigrationThreshold {
+        migration_threshold_sol: 10 * SOL,
+    });
+    let threshold = event::<MigrationThresholdUpdated>(&meta);
+    assert_eq!(
+        (threshold.old_migration_threshold_sol, threshold.new_migration_threshold_sol),
+        (50 * SOL, 10 * SOL)
+    );
+
+    // 免费创建，10 SOL 即完成
+    let creator = env.user(10);
+    let treasury_before = env.svm.balance(&env.treasury);
+    let mint = env.create(creator);
+    assert_eq!(env.svm.balance(&env.treasury), treasury_before);
+    let whale = env.user(20);
+    let completed = event::<BondingCurveCompleted>(&env.buy(whale, mint, 15 * SOL));
+    assert_eq!(completed.real_sol_reserves, 10 * SOL);
+}
+
+#[test]
+fn config_change_waits_for_timelock_and_closes_pending_account() {
+    let mut env = Env::new();
+    let change = ConfigChange::CreationFee { creation_fee_sol: 0 };
+    let governance_before = env.svm.balance(&env.governance);
+    let queued_at = env.svm.clock().unix_timestamp;
+    let queued = event::<ConfigChangeQueued>(&env.queue(change).unwrap());
+    assert_eq!((queued.id, queued.change), (0, change));
+    assert_eq!(queued.executable_at, queued_at + 86_400);
+    assert_eq!(env.config().next_config_change_id, 1);
+    let pending = env.account::<PendingConfigChange>(&pda::pending_config_change(0).0);
+    assert_eq!((pending.queued_at, pending.executable_at), (queued_at, queued.executable_at));
+    assert_eq!(env.config().creation_fee_sol, SOL); // 排队不改变配置
+
+    env.warp(86_400);
+    let meta = env.govern(env.governance().execute_config_change(0, &change)).unwrap();
+    assert_eq!(event::<ConfigChangeExecuted>(&meta).change, change);
+    assert_eq!(env.config().creation_fee_sol, 0);
+    assert!(env.svm.account(&pda::pending_config_change(0).0).is_none());
+    assert_eq!(env.svm.balance(&env.governance), governance_before); // 租金退回
+
+    // 延迟本身也经过时间锁
+    let meta = env.change_config(ConfigChange::TimelockDelay { delay_secs: 3_600 });
+    let delay = event::<TimelockDelayUpdated>(&meta);
+    assert_eq!((delay.old_delay_secs, delay.new_delay_secs), (86_400, 3_600));
+    assert_eq!(env.config().config_timelock_secs, 3_600);
+}
+
+#[test]
+fn cancel_config_change_discards_it() {
+    let mut env = Env::new();
+    let change = ConfigChange::CreationFee { creation_fee_sol: 0 };
+    env.queue(change).unwrap();
+    let meta = env.govern(env.governance().cancel_config_change(0)).unwrap();
+    let cancelled = event::<ConfigChangeCancelled>(&meta);
+    assert_eq!((cancelled.id, cancelled.change), (0, change));
+    assert!(env.svm.account(&pda::pending_config_change(0).0).is_none());
+    env.warp(86_400);
+    assert!(env.govern(env.governance().execute_config_change(0, &change)).is_err());
+    assert_eq!(env.config().creation_fee_sol, SOL);
+    assert_eq!(env.config().next_config_change_id, 1); // 编号不复用
+}
+
+#[test]
+fn set_paused_pauses_each_family_independently() {
+    let mut env = Env::new();
+    let creator = env.user(10);
+    let mint = env.create(creator);
+    let trader = env.user(10);
+    env.buy(trader, mint, SOL);
+    let meta = env.govern(env.governance().set_paused(PAUSE_BUY)).unwrap();
+    let paused = event::<PausedUpdated>(&meta);
+    assert_eq!((paused.authority, paused.old_paused, paused.new_paused), (env.governance, 0, PAUSE_BUY));
+    let buy = env.buy_ix(trader, mint).buy(SOL, 0, FAR_DEADLINE);
+    assert!(env.send(&[buy], &[trader]).is_err());
+    // 买入暂停时仍可卖出、创建代币
+    env.sell(trader, mint, env.tokens(&trader, &mint) / 2);
+    env.create(creator);
+    env.govern(env.governance().set_paused(0)).unwrap();
+    env.buy(trader, mint, SOL);
+}
+
+#[test]
+fn guardian_pauses_but_cannot_unpause() {
+    let mut env = Env::new();
+    let guardian = env.user(1);
+    let meta = env.govern(env.governance().set_guardian(guardian)).unwrap();
+    let updated = event::<GuardianUpdated>(&meta);
+    assert_eq!((updated.old_guardian, updated.new_guardian), (Pubkey::default(), guardian));
+
+    let pause = ix::GuardianPause { guardian }.instruction(PAUSE_SELL | PAUSE_CLAIM);
+    let meta = env.send(&[pause], &[guardian]).unwrap();
+    let paused = event::<PausedUpdated>(&meta);
+    assert_eq!((paused.authority, paused.new_paused), (guardian, PAUSE_SELL | PAUSE_CLAIM));
+    // 守护者只能增加暂停位，恢复和其他治理操作都被拒绝
+    let pause = ix::GuardianPause { guardian }.instruction(PAUSE_BUY);
+    env.send(&[pause], &[guardian]).unwrap();
+    assert_eq!(env.config().paused, PAUSE_BUY | PAUSE_SELL | PAUSE_CLAIM);
+    let as_governance = ix::Governance {
+        governance_authority: guardian,
+    };
+    assert!(env.send(&[as_governance.set_paused(0)], &[guardian]).is_err());
+    assert!(env.send(&[as_governance.set_curve_preset(0, env.config().curve_presets[0])], &[guardian]).is_err());
+    env.govern(env.governance().set_paused(0)).unwrap();
+    assert_eq!(env.config().paused, 0);
+}
+
+#[test]
+fn set_curve_preset_appends_and_creates_linear_curve() {
+    let mut env = Env::new();
+    let preset = CurvePreset {
+        virtual_sol_reserves: 0,
+        virtual_token_reserves: 0,
+        total_supply: 1_000_000_000,
+        decimals: 3,
+        curve_supply_bps: 8000,
+        curve_kind: CurveKind::Linear {
+            base_price: 1_000_000_000_000,
+            slope: 1_000,
+        },
+    };
+    let governance = env.governance;
+    let meta = env
+        .send(&[env.governance().set_curve_preset(1, preset)], &[governance])
+        .unwrap();
+    assert_eq!(event::<CurvePresetUpdated>(&meta).index, 1);
+    assert_eq!(env.config().curve_preset_count, 2);
+
+    let creator = env.user(10);
+    let mint = env.keypair();
+    let instruction = env.create_ix(creator, mint, 1);
+    env.send(&[instruction], &[creator, mint]).unwrap();
+    let curve = env.curve(&mint);
+    assert_eq!(curve.curve_kind, preset.curve_kind);
+    assert_eq!(curve.real_token_reserves, 800_000_000);
+    assert_eq!(env.svm.mint_supply(&mint), 1_000_000_000);
+    assert_eq!(env.svm.token_amount(&pda::migration_vault(&mint).0), 200_000_000);
+
+    let buyer = env.user(10);
+    env.buy(buyer, mint, SOL);
+    assert!(env.tokens(&buyer, &mint) > 0);
+}
+
+#[test]
+fn exponential_curve_trades_at_exact_integral_prices() {
+    // 起始价格 1 lamport/单位，每售出 1000 万单位翻倍；0.0997 SOL 跨过两次翻倍
+    let mut env = Env::new();
+    let preset = CurvePreset {
+        virtual_sol_reserves: 0,
+        virtual_token_reserves: 0,
+        total_supply: 100_000_000,
+        decimals: 3,
+        curve_supply_bps: 8000,
+        curve_kind: CurveKind::Exponential {
+            base_price: 1_000_000_000,
+            doubling_interval: 10_000_000,
+        },
+    };
+    env.govern(env.governance().set_curve_preset(1, preset)).unwrap();
+    let creator = env.user(10);
+    let mint = env.keypair();
+    let instruction = env.create_ix(creator, mint, 1);
+    env.send(&[instruction], &[creator, mint]).unwrap();
+
+    // 1500 万 + 3000 万 + 第 2 段 9326148 单位，合计 99699999.3 lamports，不超过 0.0997 SOL
+    let buyer = env.user(10);
+    let buy = event::<BuyEvent>(&env.buy(buyer, mint, SOL / 10));
+    assert_eq!(buy.tokens_out, 29_326_148);
+    assert_eq!(env.curve(&mint).real_sol_reserves, 99_700_000);
+
+    // 卖回同一区间得到面积向下取整，取整余量留在曲线
+    let sell = event::<SellEvent>(&env.sell(buyer, mint, 29_326_148));
+    assert_eq!((sell.creator_fee, sell.treasury_fee), (99_699, 199_400));
+    assert_eq!(sell.sol_out, 99_400_900);
+    let curve = env.curve(&mint);
+    assert_eq!(curve.real_sol_reserves, 1);
+    assert_eq!(curve.virtual_token_reserves, curve.initial_virtual_token_reserves);
+}
+
+#[test]
+fn create_copies_the_preset_onto_the_curve_and_prices_from_it() {
+    let mut env = Env::new();
+    let preset = CurvePreset {
+        virtual_sol_reserves: 5 * SOL,
+        virtual_token_reserves: 2_000_000_000_000,
+        total_supply: 1_500_000_000_000,
+        decimals: 9,
+        curve_supply_bps: 6000,
+        curve_kind: CurveKind::ConstantProduct,
+    };
+    env.govern(env.governance().set_curve_preset(1, preset)).unwrap();
+    let creator = env.user(10);
+    let mint = env.keypair();
+    let instruction = env.create_ix(creator, mint, 1);
+    env.send(&[instruction], &[creator, mint]).unwrap();
+
+    let curve = env.curve(&mint);
+    assert_eq!((curve.virtual_sol_reserves, curve.initial_virtual_sol_reserves), (5 * SOL, 5 * SOL));
+    assert_eq!(
+        (curve.virtual_token_reserves, curve.initial_virtual_token_reserves),
+        (2_000_000_000_000, 2_000_000_000_000)
+    );
+    assert_eq!((curve.total_supply, curve.decimals), (1_500_000_000_000, 9));
+    assert_eq!(curve.real_token_reserves, 900_000_000_000);
+    assert_eq!(env.mint_decimals(&mint), 9);
+    assert_eq!(env.svm.token_amount(&pda::migration_vault(&mint).0), 600_000_000_000);
+
+    // 之后改写预设不影响已创建的曲线
+    env.govern(env.governance().set_curve_preset(1, env.config().curve_presets[0]))
+        .unwrap();
+    // 1 SOL 扣 30bps 费用后 0.997 SOL 进入曲线：2e12 - ceil(5e9 * 2e12 / 5.997e9)
+    let buyer = env.user(10);
+    let buy = event::<BuyEvent>(&env.buy(buyer, mint, SOL));
+    assert_eq!(buy.tokens_out, 332_499_583_124);
+    assert_eq!((buy.creator_fee, buy.treasury_fee), (1_000_000, 2_000_000));
+}
+
+#[test]
+fn create_mints_supply_and_charges_creation_fee() {
+    let mut env = Env::new();
+    let creator = env.user(10);
+    let treasury_before = env.svm.balance(&env.treasury);
+    let mint = env.keypair();
+    let instruction = env.create_ix(creator, mint, 0);
+    let meta = env.send(&[instruction], &[creator, mint]).unwrap();
+
+    let event = event::<TokenCreated>(&meta);
+    assert_eq!(event.mint, mint);
+    assert_eq!(event.creator, creator);
+    assert_eq!(event.bonding_curve, pda::bonding_curve(&mint).0);
+    assert_eq!(env.svm.balance(&env.treasury) - treasury_before, SOL);
+
+    let curve = env.curve(&mint);
+    assert_eq!(curve.creator, creator);
+    assert_eq!(curve.token_vault, pda::token_vault(&mint));
+    assert_eq!(curve.real_sol_reserves, 0);
+    assert_eq!(curve.real_token_reserves, 900_000_000_000_000);
+    assert_eq!(env.svm.mint_supply(&mint), 1_000_000_000_000_000);
+    assert_eq!(env.svm.token_amount(&pda::token_vault(&mint)), 900_000_000_000_000);
+    assert_eq!(env.svm.token_amount(&pda::migration_vault(&mint).0), 100_000_000_000_000);
+}
+
+#[test]
+fn create_writes_token_metadata_onto_the_mint() {
+    let mut env = Env::new();
+    let creator = env.user(10);
+    let mint = env.create(creator);
+    let curve = pda::bonding_curve(&mint).0;
+    let account = env.svm.account(&mint).unwrap();
+    assert_eq!(account.owner, pumpfun_client::TOKEN_PROGRAM_ID);
+    assert!(account.lamports >= env.svm.rent().minimum_balance(account.data.len())); // 租金按扩容后的大小预付
+
+    let (pointer, metadata) = env.metadata(&mint);
+    assert_eq!(Option::<Pubkey>::from(pointer.metadata_address), Some(mint)); // 元数据就存放在铸币上
+    assert_eq!(Option::<Pubkey>::from(pointer.authority), Some(curve));
+    assert_eq!(metadata.mint, mint);
+    assert_eq!(Option::<Pubkey>::from(metadata.update_authority), Some(curve));
+    assert_eq!(
+        (metadata.name.as_str(), metadata.symbol.as_str(), metadata.uri.as_str()),
+        ("Pump", "PUMP", "https://example.com/pump.json")
+    );
+    assert!(metadata.additional_metadata.is_empty());
+}
+
+#[test]
+fn update_token_metadata_sets_uri_and_custom_fields() {
+    let mut env = Env::new();
+    let creator = env.user(10);
+    let mint = env.create(creator);
+    let update = ix::UpdateTokenMetadata {
+        creator,
+        token_mint: mint,
+    };
+    for (field, value) in [("uri", "https://example.com/new.json"), ("website", "https://pump.example")] {
+        let meta = env
+            .send(&[update.instruction(field.into(), value.into())], &[creator])
+            .unwrap();
+        let event = event::<TokenMetadataUpdated>(&meta);
+        assert_eq!((event.field.as_str(), event.value.as_str()), (field, value));
+    }
+    let data = env.svm.account(&mint).unwrap().data;
+    let text = String::from_utf8_lossy(&data);
+    assert!(text.contains("https://example.com/new.json"));
+    assert!(text.contains("https://pump.example"));
+}
+
+#[test]
+fn migration_revokes_metadata_authorities_and_keeps_the_fields() {
+    let mut env = Env::new();
+    let creator = env.user(10);
+    let mint = env.create(creator);
+    let update = ix::UpdateTokenMetadata {
+        creator,
+        token_mint: mint,
+    };
+    env.send(&[update.instruction("twitter".into(), "@pump".into())], &[creator])
+        .unwrap();
+    env.graduate(mint);
+
+    let (pointer, metadata) = env.metadata(&mint);
+    assert_eq!(Option::<Pubkey>::from(metadata.update_authority), None);
+    assert_eq!(Option::<Pubkey>::from(pointer.authority), None);
+    assert_eq!(Option::<Pubkey>::from(pointer.metadata_address), Some(mint));
+    assert_eq!(metadata.additional_metadata, vec![("twitter".to_string(), "@pump".to_string())]);
+    assert_eq!(metadata.uri, "https://example.com/pump.json");
+    let result = env.send(&[update.instruction("uri".into(), "https://rug.example".into())], &[creator]);
+    assert_pump_error(result, PumpError::CurveCompleted);
+}
+
+#[test]
+fn buy_moves_sol_to_curve_and_treasury_and_tokens_to_buyer() {
+    let mut env = Env::new();
+    let creator = env.user(10);
+    let mint = env.create(creator);
+    let buyer = env.user(10);
+    let curve_address = pda::bonding_curve(&mint).0;
+    let quote = env.curve(&mint).quote_buy(&env.config(), SOL).unwrap();
+    let (buyer_before, curve_before, treasury_before) = (
+        env.svm.balance(&buyer),
+        env.svm.balance(&curve_address),
+        env.svm.balance(&env.treasury),
+    );
+
+    let meta = env.buy(buyer, mint, SOL);
+    let event = event::<BuyEvent>(&meta);
+    assert_eq!(event.tokens_out, quote.tokens_out);
+    assert_eq!(event.sol_in, SOL);
+    assert_eq!(event.sol_refunded, 0);
+    assert_eq!(env.tokens(&buyer, &mint), quote.tokens_out);
+
+    let ata_rent = env.svm.balance(&pda::token_account(&buyer, &mint));
+    assert_eq!(buyer_before - env.svm.balance(&buyer), SOL + ata_rent);
+    assert_eq!(
+        env.svm.balance(&curve_address) - curve_before,
+        quote.sol_to_curve + quote.creator_fee
+    );
+    assert_eq!(env.svm.balance(&env.treasury) - treasury_before, quote.treasury_fee);
+
+    let curve = env.curve(&mint);
+    assert_eq!(curve.real_sol_reserves, quote.sol_to_curve);
+    assert_eq!(curve.creator_fees_owed, quote.creator_fee);
+}
+
+#[test]
+fn repeat_buy_debits_exactly_sol_in_and_keeps_curve_lamports_backed() {
+    // 买家代币账户已存在时，买家只付出 sol_in：国库费用进国库，其余（含创作者费用）进曲线
+    let mut env = Env::new();
+    let creator = env.user(10);
+    let mint = env.create(creator);
+    let buyer = env.user(10);
+    env.buy(buyer, mint, SOL);
+    let curve_address = pda::bonding_curve(&mint).0;
+    let rent = env
+        .svm
+        .rent()
+        .minimum_balance(env.svm.account(&curve_address).unwrap().data.len());
+
+    for sol in [SOL / 3, 2 * SOL] {
+        let (buyer_before, curve_before, treasury_before) = (
+            env.svm.balance(&buyer),
+            env.svm.balance(&curve_address),
+            env.svm.balance(&env.treasury),
+        );
+        let event = event::<BuyEvent>(&env.buy(buyer, mint, sol));
+        assert_eq!(buyer_before - env.svm.balance(&buyer), sol);
+        assert_eq!(env.svm.balance(&env.treasury) - treasury_before, event.treasury_fee);
+        assert_eq!(env.svm.balance(&curve_address) - curve_before, sol - event.treasury_fee);
+        let curve = env.curve(&mint);
+        assert_eq!(env.svm.balance(&curve_address), rent + curve.real_sol_reserves + curve.creator_fees_owed);
+    }
+}
+
+#[test]
+fn buy_truncates_at_threshold_and_completes_curve() {
+    let mut env = Env::new();
+    let creator = env.user(10);
+    let mint = env.create(creator);
+    let whale = env.user(100);
+    let meta = env.buy(whale, mint, 60 * SOL);
+    let buy = event::<BuyEvent>(&meta);
+    assert!(buy.sol_refunded > 0);
+    assert_eq!(buy.sol_in + buy.sol_refunded, 60 * SOL);
+    let completed = event::<BondingCurveCompleted>(&meta);
+    assert_eq!(completed.real_sol_reserves, 50 * SOL);
+    assert!(env.curve(&mint).is_completed);
+}
+
+#[test]
+fn overshooting_buy_refunds_the_excess_and_anyone_can_migrate() {
+    let mut env = Env::new();
+    let creator = env.user(10);
+    let mint = env.create(creator);
+    let whale = env.user(100);
+    let threshold = env.config().migration_threshold_sol;
+    let balance = env.svm.balance(&whale);
+    let buy = event::<BuyEvent>(&env.buy(whale, mint, 60 * SOL));
+    let ata_rent = env.svm.balance(&pda::token_account(&whale, &mint));
+    assert_eq!(balance - env.svm.balance(&whale), buy.sol_in + ata_rent); // 只扣除实际使用的SOL
+    assert_eq!(env.curve(&mint).real_sol_reserves, threshold);
+
+    // 既不是创作者也不是买家的第三方完成迁移
+    let stranger = env.user(1);
+    let instructions = env.migrate_ixs(stranger, mint);
+    env.send(&instructions, &[stranger]).unwrap();
+    assert_eq!(env.curve(&mint).dex_pool, pda::dex_pool(&mint).0);
+}
+
+#[test]
+fn buy_exact_tokens_out_charges_quoted_amount() {
+    let mut env = Env::new();
+    let creator = env.user(10);
+    let mint = env.create(creator);
+    let buyer = env.user(10);
+    let quote = env
+        .curve(&mint)
+        .quote_buy_exact_tokens_out(&env.config(), 1_000_000_000)
+        .unwrap();
+    let instruction = env
+        .buy_ix(buyer, mint)
+        .buy_exact_tokens_out(1_000_000_000, quote.sol_in, FAR_DEADLINE);
+    let meta = env.send(&[instruction], &[buyer]).unwrap();
+    let event = event::<BuyEvent>(&meta);
+    assert_eq!((event.tokens_out, event.sol_in), (1_000_000_000, quote.sol_in));
+    assert_eq!(env.tokens(&buyer, &mint), 1_000_000_000);
+}
+
+#[test]
+fn exact_output_trades_round_toward_the_curve_and_report_fees() {
+    // 默认曲线 x = 1 SOL、y = 1e11：期望值按恒定乘积和 30bps 费用（创作者 10bps）手算
+    let mut env = Env::new();
+    let creator = env.user(10);
+    let mint = env.create(creator);
+    let trader = env.user(10);
+    let treasury_before = env.svm.balance(&env.treasury);
+
+    // 曲线成本 ceil(1e20 / (1e11 - n)) - 1e9 = 34482759，含费总额 ceil(34482759 / 0.997) = 34586519
+    let short = env
+        .buy_ix(trader, mint)
+        .buy_exact_tokens_out(3_333_333_333, 34_586_518, FAR_DEADLINE);
+    assert_pump_error(env.send(&[short], &[trader]), PumpError::SlippageLimitExceeded);
+    let instruction = env
+        .buy_ix(trader, mint)
+        .buy_exact_tokens_out(3_333_333_333, 34_586_519, FAR_DEADLINE);
+    let buy = event::<BuyEvent>(&env.send(&[instruction], &[trader]).unwrap());
+    assert_eq!((buy.tokens_out, buy.sol_in), (3_333_333_333, 34_586_519));
+    assert_eq!((buy.creator_fee, buy.treasury_fee), (34_586, 69_173));
+    let curve = env.curve(&mint);
+    assert_eq!(curve.real_sol_reserves, 34_482_760); // 比成本多 1 lamport
+    assert_eq!(curve.creator_fees_owed, 34_586);
+
+    // 到手 0.02 SOL：含费总额 ceil(0.02 / 0.997) 的费用加到曲线付出额上，代币按 ceil(k / (x - 20060180)) - y
+    let balance = env.svm.balance(&trader);
+    let instruction = env
+        .sell_ix(trader, mint)
+        .sell_for_exact_sol(20_000_000, 1_911_580_807, FAR_DEADLINE);
+    let sell = event::<SellEvent>(&env.send(&[instruction], &[trader]).unwrap());
+    assert_eq!((sell.tokens_in, sell.sol_out), (1_911_580_807, 20_000_000));
+    assert_eq!((sell.creator_fee, sell.treasury_fee), (20_060, 40_120));
+    assert_eq!(env.svm.balance(&trader) - balance, 20_000_000);
+    assert_eq!(env.tokens(&trader, &mint), 3_333_333_333 - 1_911_580_807);
+    assert_eq!(env.curve(&mint).real_sol_reserves, 34_482_760 - 20_060_180);
+    assert_eq!(env.svm.balance(&env.treasury) - treasury_before, 69_173 + 40_120);
+}
+
+#[test]
+fn sell_pays_seller_from_curve_and_keeps_creator_fee() {
+    let mut env = Env::new();
+    let creator = env.user(10);
+    let mint = env.create(creator);
+    let seller = env.user(10);
+    env.buy(seller, mint, 2 * SOL);
+    let tokens = env.tokens(&seller, &mint) / 2;
+    let quote = env.curve(&mint).quote_sell(&env.config(), tokens).unwrap();
+    let fees_before = env.curve(&mint).creator_fees_owed;
+    let (seller_before, treasury_before) = (env.svm.balance(&seller), env.svm.balance(&env.treasury));
+
+    let meta = env.sell(seller, mint, tokens);
+    let event = event::<SellEvent>(&meta);
+    assert_eq!((event.tokens_in, event.sol_out), (tokens, quote.sol_out));
+    assert_eq!(env.svm.balance(&seller) - seller_before, quote.sol_out);
+    assert_eq!(env.svm.balance(&env.treasury) - treasury_before, quote.treasury_fee);
+    assert_eq!(env.curve(&mint).creator_fees_owed - fees_before, quote.creator_fee);
+}
+
+#[test]
+fn sell_for_exact_sol_pays_requested_amount() {
+    let mut env = Env::new();
+    let creator = env.user(10);
+    let mint = env.create(creator);
+    let seller = env.user(10);
+    env.buy(seller, mint, 2 * SOL);
+    let balance = env.svm.balance(&seller);
+    let tokens = env.tokens(&seller, &mint);
+    let instruction = env
+        .sell_ix(seller, mint)
+        .sell_for_exact_sol(SOL / 2, tokens, FAR_DEADLINE);
+    let meta = env.send(&[instruction], &[seller]).unwrap();
+    let event = event::<SellEvent>(&meta);
+    assert_eq!(event.sol_out, SOL / 2);
+    assert_eq!(env.svm.balance(&seller) - balance, SOL / 2);
+    assert_eq!(tokens - env.tokens(&seller, &mint), event.tokens_in);
+}
+
+#[test]
+fn claim_creator_fees_pays_creator() {
+    let mut env = Env::new();
+    let creator = env.user(10);
+    let mint = env.create(creator);
+    let buyer = env.user(10);
+    env.buy(buyer, mint, 5 * SOL);
+    let owed = env.curve(&mint).creator_fees_owed;
+    let balance = env.svm.balance(&creator);
+
+    let instruction = ix::ClaimCreatorFees {
+        creator,
+        creator_fee_recipient: creator,
+        token_mint: mint,
+    }
+    .instruction();
+    let meta = env.send(&[instruction], &[creator]).unwrap();
+    assert_eq!(event::<CreatorFeeClaimed>(&meta).amount, owed);
+    assert_eq!(env.svm.balance(&creator) - balance, owed);
+    assert_eq!(env.curve(&mint).creator_fees_owed, 0);
+}
+
+#[test]
+fn creator_fee_recipient_receives_claims_and_transfer_resets_it() {
+    let mut env = Env::new();
+    let creator = env.user(10);
+    let mint = env.create(creator);
+    let multisig = env.user(1);
+    let rights = ix::Creator {
+        creator,
+        token_mint: mint,
+    };
+    let meta = env.send(&[rights.set_creator_fee_recipient(multisig)], &[creator]).unwrap();
+    let updated = event::<CreatorFeeRecipientUpdated>(&meta);
+    assert_eq!((updated.old_recipient, updated.new_recipient), (creator, multisig));
+    let buyer = env.user(10);
+    env.buy(buyer, mint, 5 * SOL);
+    let owed = env.curve(&mint).creator_fees_owed;
+    let balance = env.svm.balance(&multisig);
+    let claim = ix::ClaimCreatorFees {
+        creator,
+        creator_fee_recipient: multisig,
+        token_mint: mint,
+    }
+    .instruction();
+    let claimed = event::<CreatorFeeClaimed>(&env.send(&[claim], &[creator]).unwrap());
+    assert_eq!((claimed.creator, claimed.recipient, claimed.amount), (creator, multisig, owed));
+    assert_eq!(env.svm.balance(&multisig) - balance, owed);
+
+    // 两步转移：接受前原创作者仍控制曲线，接受后费用接收者重置为新创作者
+    let new_creator = env.user(1);
+    let meta = env.send(&[rights.transfer_creator(new_creator)], &[creator]).unwrap();
+    assert_eq!(event::<CreatorTransferProposed>(&meta).pending_creator, new_creator);
+    assert_eq!((env.curve(&mint).creator, env.curve(&mint).pending_creator), (creator, new_creator));
+    let accept = ix::AcceptCreator {
+        pending_creator: new_creator,
+        token_mint: mint,
+    };
+    let transferred = event::<CreatorTransferred>(&env.send(&[accept.instruction()], &[new_creator]).unwrap());
+    assert_eq!((transferred.previous_creator, transferred.new_creator), (creator, new_creator));
+    let curve = env.curve(&mint);
+    assert_eq!(
+        (curve.creator, curve.pending_creator, curve.creator_fee_recipient),
+        (new_creator, Pubkey::default(), new_creator)
+    );
+    assert!(env.send(&[rights.set_creator_fee_recipient(creator)], &[creator]).is_err());
+}
+
+#[test]
+fn fee_split_distributes_claims_and_lets_recipients_pull_their_share() {
+    let mut env = Env::new();
+    let creator = env.user(10);
+    let mint = env.create(creator);
+    let (a, b, c) = (env.user(1), env.user(1), env.user(1));
+    let split = ix::CreatorFeeSplit {
+        creator,
+        token_mint: mint,
+    };
+    let meta = env.send(&[split.create(vec![a, b, c], vec![6_000, 3_000, 1_000])], &[creator]).unwrap();
+    let updated = event::<FeeSplitUpdated>(&meta);
+    assert_eq!((updated.recipients, updated.shares_bps), (vec![a, b, c], vec![6_000, 3_000, 1_000]));
+    let fee_split = pda::fee_split(&mint).0;
+    assert_eq!(env.curve(&mint).creator_fee_recipient, fee_split);
+
+    // 一条指令领取并按比例付给全部接收者，每个接收者一条领取事件
+    let buyer = env.user(20);
+    env.buy(buyer, mint, 5 * SOL);
+    let owed = env.curve(&mint).creator_fees_owed;
+    let balances = [a, b, c].map(|key| env.svm.balance(&key));
+    let claim = ix::ClaimCreatorFees {
+        creator,
+        creator_fee_recipient: fee_split,
+        token_mint: mint,
+    };
+    let meta = env.send(&[claim.distribute(&[a, b, c])], &[creator]).unwrap();
+    assert_eq!(event::<CreatorFeeClaimed>(&meta).recipient, fee_split);
+    let claimed = events::<FeeSplitClaimed>(&meta);
+    assert_eq!(claimed.iter().map(|e| e.recipient).collect::<Vec<_>>(), [a, b, c]);
+    for ((key, bps), before) in [(a, 6_000), (b, 3_000), (c, 1_000)].into_iter().zip(balances) {
+        assert_eq!(env.svm.balance(&key) - before, owed * bps / 10_000);
+    }
+
+    // 接收者自行领取：曲线上的欠费先汇入分账账户
+    env.buy(buyer, mint, 5 * SOL);
+    let owed_again = env.curve(&mint).creator_fees_owed;
+    let pull = ix::ClaimFeeSplitShare {
+        recipient: b,
+        token_mint: mint,
+    };
+    let pulled = event::<FeeSplitClaimed>(&env.send(&[pull.instruction()], &[b]).unwrap());
+    assert_eq!((pulled.recipient, pulled.amount), (b, (owed + owed_again) * 3_000 / 10_000 - owed * 3_000 / 10_000));
+    assert_eq!(env.curve(&mint).creator_fees_owed, 0);
+    let share = env.account::<FeeSplit>(&fee_split);
+    assert_eq!(share.claimed[..3], [owed * 6_000 / 10_000, owed * 3_000 / 10_000 + pulled.amount, owed / 10]);
+
+    // 更换比例前按旧比例付清：a 和 c 拿到第二笔费用中各自的份额，b 已经领过
+    let before = [a, c].map(|key| env.svm.balance(&key));
+    let meta = env.send(&[split.update(&[a, b, c], vec![b, creator], vec![5_000, 5_000])], &[creator]).unwrap();
+    assert_eq!(events::<FeeSplitClaimed>(&meta).len(), 2);
+    let total = owed + owed_again;
+    assert_eq!(env.svm.balance(&a) - before[0], total * 6_000 / 10_000 - owed * 6_000 / 10_000);
+    assert_eq!(env.svm.balance(&c) - before[1], total / 10 - owed / 10);
+    let share = env.account::<FeeSplit>(&fee_split);
+    assert_eq!((share.recipients(), share.total_claimed), (&[b, creator][..], 0));
+    let stale = ix::ClaimFeeSplitShare {
+        recipient: a,
+        token_mint: mint,
+    };
+    assert!(env.send(&[stale.instruction()], &[a]).is_err());
+}
+
+#[test]
+fn creator_vesting_releases_fees_linearly_after_the_cliff() {
+    const DAY: i64 = 86_400;
+    let mut env = Env::new();
+    let creator = env.user(10);
+    let mint = env.create(creator);
+    let vesting = ix::CreatorFeeVesting {
+        creator,
+        token_mint: mint,
+    };
+    let meta = env.send(&[vesting.create(DAY, 10 * DAY)], &[creator]).unwrap();
+    let created = event::<CreatorVestingCreated>(&meta);
+    assert_eq!((created.cliff_secs, created.duration_secs), (DAY, 10 * DAY));
+    assert!(env.curve(&mint).has_creator_vesting);
+    let buyer = env.user(10);
+    env.buy(buyer, mint, 5 * SOL);
+    let owed = env.curve(&mint).creator_fees_owed;
+
+    // 悬崖期内不可领取
+    env.warp(DAY - 1);
+    assert!(env.send(&[vesting.claim(creator, u64::MAX)], &[creator]).is_err());
+    // 释放期过半：欠费汇入释放账户，领取一半
+    env.warp(4 * DAY + 1);
+    let balance = env.svm.balance(&creator);
+    let meta = env.send(&[vesting.claim(creator, u64::MAX)], &[creator]).unwrap();
+    let claimed = event::<CreatorVestingClaimed>(&meta);
+    assert_eq!((claimed.amount, claimed.total_accrued, claimed.total_claimed), (owed / 2, owed, owed / 2));
+    assert_eq!(env.svm.balance(&creator) - balance, owed / 2);
+    assert_eq!(env.curve(&mint).creator_fees_owed, 0);
+    let account = env.account::<CreatorVesting>(&pda::creator_vesting(&mint).0);
+    assert_eq!(account.unclaimed(), owed - owed / 2);
+
+    // 释放期结束后可以部分领取，剩余部分仍留在释放账户
+    env.warp(5 * DAY);
+    assert_eq!(event::<CreatorVestingClaimed>(&env.send(&[vesting.claim(creator, 1)], &[creator]).unwrap()).amount, 1);
+    let meta = env.send(&[vesting.claim(creator, u64::MAX)], &[creator]).unwrap();
+    assert_eq!(event::<CreatorVestingClaimed>(&meta).total_claimed, owed);
+    assert_eq!(env.account::<CreatorVesting>(&pda::creator_vesting(&mint).0).unclaimed(), 0);
+}
+
+#[test]
+fn freeze_curve_halts_one_curve_until_unfrozen() {
+    let mut env = Env::new();
+    let creator = env.user(10);
+    let (mint, other) = (env.create(creator), env.create(creator));
+    let trader = env.user(10);
+    env.buy(trader, mint, SOL);
+    let meta = env.govern(env.governance().freeze_curve(mint, true)).unwrap();
+    let frozen = event::<CurveFreezeUpdated>(&meta);
+    assert_eq!((frozen.mint, frozen.frozen), (mint, true));
+    assert!(env.curve(&mint).is_frozen);
+    let buy = env.buy_ix(trader, mint).buy(SOL, 0, FAR_DEADLINE);
+    assert!(env.send(&[buy], &[trader]).is_err());
+    let sell = env.sell_ix(trader, mint).sell(env.tokens(&trader, &mint), 0, FAR_DEADLINE);
+    assert!(env.send(&[sell], &[trader]).is_err());
+    env.buy(trader, other, SOL); // 其他曲线不受影响
+
+    env.govern(env.governance().freeze_curve(mint, false)).unwrap();
+    env.sell(trader, mint, env.tokens(&trader, &mint));
+}
+
+#[test]
+fn emergency_unwind_redeems_pro_rata_for_real_reserves() {
+    let mut env = Env::new();
+    let creator = env.user(10);
+    let mint = env.create(creator);
+    let (first, second) = (env.user(10), env.user(10));
+    env.buy(first, mint, 2 * SOL);
+    env.buy(second, mint, SOL);
+    let curve = env.curve(&mint);
+    let (reserves, outstanding) = (curve.real_sol_reserves, curve.tokens_sold().unwrap());
+    let meta = env.govern(env.governance().emergency_unwind(mint, true)).unwrap();
+    let unwind = event::<EmergencyUnwindUpdated>(&meta);
+    assert_eq!(
+        (unwind.mint, unwind.unwinding, unwind.real_sol_reserves, unwind.tokens_outstanding),
+        (mint, true, reserves, outstanding)
+    );
+
+    let tokens = env.tokens(&first, &mint);
+    let balance = env.svm.balance(&first);
+    let redeem = ix::EmergencyRedeem {
+        holder: first,
+        token_mint: mint,
+    };
+    let meta = env.send(&[redeem.instruction(tokens)], &[first]).unwrap();
+    let redeemed = event::<EmergencyRedeemed>(&meta);
+    let expected = (reserves as u128 * tokens as u128 / outstanding as u128) as u64;
+    assert_eq!((redeemed.holder, redeemed.tokens_in, redeemed.sol_out), (first, tokens, expected));
+    assert_eq!(env.svm.balance(&first) - balance, expected);
+    assert_eq!(env.tokens(&first, &mint), 0);
+
+    // 最后一位持有人取走剩余全部储备，欠创作者的费用不受影响
+    let fees_owed = env.curve(&mint).creator_fees_owed;
+    let redeem = ix::EmergencyRedeem {
+        holder: second,
+        token_mint: mint,
+    };
+    let meta = env
+        .send(&[redeem.instruction(env.tokens(&second, &mint))], &[second])
+        .unwrap();
+    let redeemed = event::<EmergencyRedeemed>(&meta);
+    assert_eq!(redeemed.sol_out, reserves - expected);
+    assert_eq!((redeemed.redeemed_tokens, redeemed.redeemed_sol), (outstanding, reserves));
+    let curve = env.curve(&mint);
+    assert_eq!((curve.real_sol_reserves, curve.creator_fees_owed), (0, fees_owed));
+    assert_eq!((curve.redeemed_tokens, curve.redeemed_sol), (outstanding, reserves));
+}
+
+#[test]
+fn emergency_unwind_lifted_before_any_redemption_resumes_trading_at_the_same_price() {
+    let mut env = Env::new();
+    let creator = env.user(10);
+    let mint = env.create(creator);
+    let buyer = env.user(10);
+    env.buy(buyer, mint, SOL);
+    let before = env.curve(&mint);
+    env.govern(env.governance().emergency_unwind(mint, true)).unwrap();
+    let meta = env.govern(env.governance().emergency_unwind(mint, false)).unwrap();
+    assert!(!event::<EmergencyUnwindUpdated>(&meta).unwinding);
+
+    let curve = env.curve(&mint);
+    assert_eq!(
+        (curve.virtual_sol_reserves, curve.virtual_token_reserves, curve.real_sol_reserves),
+        (before.virtual_sol_reserves, before.virtual_token_reserves, before.real_sol_reserves)
+    );
+    let tokens = env.tokens(&buyer, &mint);
+    let quote = before.get_sell_output(tokens).unwrap();
+    let sold = event::<SellEvent>(&env.sell(buyer, mint, tokens));
+    assert_eq!(sold.sol_out + sold.creator_fee + sold.treasury_fee, quote);
+}
+
+#[test]
+fn initialize_dex_pool_creates_vaults() {
+    let mut env = Env::new();
+    let creator = env.user(10);
+    let mint = env.create(creator);
+    let payer = env.user(10);
+    let instruction = ix::InitializeDexPool { payer, token_mint: mint }.instruction();
+    env.send(&[instruction], &[payer]).unwrap();
+
+    let pool = env.dex_pool(&mint);
+    assert_eq!(pool.bump, pda::dex_pool(&mint).1);
+    assert_eq!(pool.token_mint, Pubkey::default());
+    let sol_vault = env.svm.account(&pda::dex_sol_vault(&mint).0).unwrap();
+    assert_eq!(sol_vault.owner, pumpfun::ID);
+    assert!(env.svm.account(&pda::lp_mint(&mint).0).is_some());
+    assert!(env.svm.account(&pda::dex_token_vault(&mint)).is_some());
+    assert!(env.svm.account(&pda::lp_vault(&mint)).is_some());
+}
+
+#[test]
+fn complete_and_migrate_seeds_pool_and_freezes_metadata() {
+    let mut env = Env::new();
+    let creator = env.user(10);
+    let mint = env.create(creator);
+    let whale = env.user(100);
+    env.buy(whale, mint, 60 * SOL);
+    let quote = env
+        .curve(&mint)
+        .quote_migration(env.svm.token_amount(&pda::migration_vault(&mint).0))
+        .unwrap();
+    let instructions = env.migrate_ixs(whale, mint);
+    let meta = env.send(&instructions, &[whale]).unwrap();
+
+    let migrated = event::<DexMigrated>(&meta);
+    assert_eq!(migrated.sol_reserves, quote.sol_to_pool);
+    assert_eq!(migrated.token_reserves, quote.tokens_to_pool);
+    assert_eq!(event::<TokenMetadataFrozen>(&meta).mint, mint);
+
+    let curve = env.curve(&mint);
+    assert_eq!(curve.dex_pool, pda::dex_pool(&mint).0);
+    assert_eq!(curve.real_sol_reserves, 0);
+    let pool = env.dex_pool(&mint);
+    assert_eq!(pool.token_mint, mint);
+    assert_eq!(pool.lp_supply, quote.lp_tokens);
+    assert_eq!(env.svm.token_amount(&pda::dex_token_vault(&mint)), quote.tokens_to_pool);
+    assert_eq!(env.svm.token_amount(&pda::lp_vault(&mint)), quote.lp_tokens);
+    assert!(env.svm.account(&pda::token_vault(&mint)).is_none());
+    assert!(env.svm.account(&pda::migration_vault(&mint).0).is_none());
+}
+
+#[test]
+fn migration_reserve_is_untouched_by_trading_and_seeds_the_pool_at_the_final_price() {
+    let mut env = Env::new();
+    let creator = env.user(10);
+    let mint = env.create(creator);
+    let reserve = env.svm.token_amount(&pda::migration_vault(&mint).0);
+    assert_eq!(reserve, env.curve(&mint).total_supply / 10); // 默认预设 90% 在曲线上出售
+    let trader = env.user(10);
+    env.buy(trader, mint, 5 * SOL);
+    env.sell(trader, mint, env.tokens(&trader, &mint));
+    let whale = env.user(100);
+    env.buy(whale, mint, 60 * SOL);
+    assert_eq!(env.svm.token_amount(&pda::migration_vault(&mint).0), reserve);
+
+    // 池子价格 = 曲线最终价格：代币 = floor(SOL * 分母 / 分子)，多出的储备销毁
+    let curve = env.curve(&mint);
+    let (price_num, price_den) = curve.spot_price().unwrap();
+    let sol = curve.real_sol_reserves;
+    let tokens = (sol as u128 * price_den / price_num) as u64;
+    let unsold = env.svm.token_amount(&pda::token_vault(&mint));
+    let supply = env.svm.mint_supply(&mint);
+    let instructions = env.migrate_ixs(whale, mint);
+    let migrated = event::<DexMigrated>(&env.send(&instructions, &[whale]).unwrap());
+    assert_eq!((migrated.sol_reserves, migrated.token_reserves), (sol, tokens));
+    assert_eq!(migrated.tokens_burned, unsold + reserve - tokens);
+    assert_eq!(env.svm.mint_supply(&mint), supply - migrated.tokens_burned);
+    let pool = env.dex_pool(&mint);
+    assert_eq!((pool.sol_reserves, pool.token_reserves), (sol, tokens));
+}
+
+#[test]
+fn migration_never_mints_past_the_preset_supply() {
+    // 固定价格 1 lamport/单位：50 SOL 的阈值卖出 5e10 单位，迁移储备 1e11 足够配对，多余的销毁
+    let mut env = Env::new();
+    let preset = CurvePreset {
+        virtual_sol_reserves: 0,
+        virtual_token_reserves: 0,
+        total_supply: 1_000_000_000_000,
+        decimals: 6,
+        curve_supply_bps: 9000,
+        curve_kind: CurveKind::Linear {
+            base_price: 1_000_000_000,
+            slope: 0,
+        },
+    };
+    env.govern(env.governance().set_curve_preset(1, preset)).unwrap();
+    let creator = env.user(10);
+    let mint = env.keypair();
+    let instruction = env.create_ix(creator, mint, 1);
+    env.send(&[instruction], &[creator, mint]).unwrap();
+    let whale = env.user(100);
+    env.buy(whale, mint, 60 * SOL);
+
+    let sold = env.tokens(&whale, &mint);
+    assert_eq!(sold, 50 * SOL);
+    let instructions = env.migrate_ixs(whale, mint);
+    let migrated = event::<DexMigrated>(&env.send(&instructions, &[whale]).unwrap());
+    assert_eq!((migrated.sol_reserves, migrated.token_reserves), (50 * SOL, sold));
+    assert_eq!(env.svm.mint_supply(&mint), sold + migrated.token_reserves);
+    assert!(env.svm.mint_supply(&mint) <= preset.total_supply);
+    assert_eq!(env.curve(&mint).total_supply, preset.total_supply);
+}
+
+#[test]
+fn dex_swaps_trade_against_pool() {
+    let mut env = Env::new();
+    let creator = env.user(10);
+    let mint = env.create(creator);
+    env.graduate(mint);
+    let trader = env.user(10);
+    let swap = ix::DexSwap {
+        user: trader,
+        token_mint: mint,
+    };
+
+    let meta = env
+        .send(&[swap.sol_for_tokens(SOL, 1, FAR_DEADLINE)], &[trader])
+        .unwrap();
+    let bought = event::<DexSwapEvent>(&meta);
+    assert!(bought.sol_to_tokens);
+    assert_eq!(env.tokens(&trader, &mint), bought.amount_out);
+    assert_eq!(env.dex_pool(&mint).token_reserves, bought.token_reserves);
+
+    let balance = env.svm.balance(&trader);
+    let meta = env
+        .send(&[swap.tokens_for_sol(bought.amount_out, 1, FAR_DEADLINE)], &[trader])
+        .unwrap();
+    let sold = event::<DexSwapEvent>(&meta);
+    assert!(!sold.sol_to_tokens);
+    assert_eq!(env.svm.balance(&trader) - balance, sold.amount_out);
+    assert!(sold.amount_out < SOL); // 两次 LP 费用
+    assert_eq!(env.tokens(&trader, &mint), 0);
+}
+
+#[test]
+fn dex_swaps_price_against_vault_balances_after_the_lp_fee() {
+    // 直接打入SOL金库的lamports也计入储备；输出 = y * (dx - fee) / (x + dx - fee)，向下取整
+    let mut env = Env::new();
+    let creator = env.user(10);
+    let mint = env.create(creator);
+    env.graduate(mint);
+    let sol_vault = pda::dex_sol_vault(&mint).0;
+    env.svm.airdrop_sol(&sol_vault, 1);
+    let vault_rent = env
+        .svm
+        .rent()
+        .minimum_balance(env.svm.account(&sol_vault).unwrap().data.len());
+    let fee_bps = env.config().dex_lp_fee_bps as u128;
+    let trader = env.user(10);
+    let swap = ix::DexSwap {
+        user: trader,
+        token_mint: mint,
+    };
+
+    let x = (env.svm.balance(&sol_vault) - vault_rent) as u128;
+    let y = env.svm.token_amount(&pda::dex_token_vault(&mint)) as u128;
+    assert_eq!(x, env.dex_pool(&mint).sol_reserves as u128 + SOL as u128);
+    let sol_in = 2 * SOL as u128;
+    let fee = sol_in * fee_bps / 10_000;
+    let tokens_out = y * (sol_in - fee) / (x + sol_in - fee);
+    let meta = env
+        .send(&[swap.sol_for_tokens(sol_in as u64, tokens_out as u64, FAR_DEADLINE)], &[trader])
+        .unwrap();
+    let bought = event::<DexSwapEvent>(&meta);
+    assert_eq!((bought.amount_out, bought.lp_fee), (tokens_out as u64, fee as u64));
+    assert_eq!(
+        (bought.sol_reserves, bought.token_reserves),
+        ((x + sol_in) as u64, (y - tokens_out) as u64)
+    );
+
+    let (x, y) = (x + sol_in, y - tokens_out);
+    let tokens_in = tokens_out / 2;
+    let fee = tokens_in * fee_bps / 10_000;
+    let sol_out = x * (tokens_in - fee) / (y + tokens_in - fee);
+    let balance = env.svm.balance(&trader);
+    let meta = env
+        .send(&[swap.tokens_for_sol(tokens_in as u64, sol_out as u64, FAR_DEADLINE)], &[trader])
+        .unwrap();
+    let sold = event::<DexSwapEvent>(&meta);
+    assert_eq!((sold.amount_out, sold.lp_fee), (sol_out as u64, fee as u64));
+    assert_eq!(env.svm.balance(&trader) - balance, sol_out as u64);
+    assert_eq!(env.dex_pool(&mint).sol_reserves, (x - sol_out) as u64);
+}
+
+#[test]
+fn add_liquidity_mints_lp_in_proportion_to_pool_share() {
+    let mut env = Env::new();
+    let creator = env.user(10);
+    let mint = env.create(creator);
+    let whale = env.graduate(mint);
+    let lp_mint = pda::lp_mint(&mint).0;
+    // 迁移铸造的初始LP全部锁在池子自有的LP金库
+    let pool = env.dex_pool(&mint);
+    assert_eq!(env.svm.mint_supply(&lp_mint), pool.lp_supply);
+    assert_eq!(env.svm.token_amount(&pda::lp_vault(&mint)), pool.lp_supply);
+
+    let liquidity = ix::DexLiquidity {
+        user: whale,
+        token_mint: mint,
+    };
+    // SOL 一侧受限：代币按比例向上取整，LP 取两侧份额的较小值并向下取整
+    let (x, y, supply) = (pool.sol_reserves as u128, pool.token_reserves as u128, pool.lp_supply as u128);
+    let sol_in = 3 * SOL as u128 / 2;
+    let tokens_in = (sol_in * y).div_ceil(x);
+    let lp_out = (sol_in * supply / x).min(tokens_in * supply / y);
+    let tokens = env.tokens(&whale, &mint);
+    let meta = env
+        .send(&[liquidity.add_liquidity(sol_in as u64, tokens, lp_out as u64, FAR_DEADLINE)], &[whale])
+        .unwrap();
+    let added = event::<LiquidityAdded>(&meta);
+    assert_eq!(
+        (added.sol_amount, added.token_amount, added.lp_amount),
+        (sol_in as u64, tokens_in as u64, lp_out as u64)
+    );
+    assert_eq!(added.lp_supply, (supply + lp_out) as u64);
+
+    // 代币一侧受限：SOL 按比例向上取整
+    let (x, y, supply) = (x + sol_in, y + tokens_in, supply + lp_out);
+    let tokens_in = 100_000_000u128;
+    let sol_in = (tokens_in * x).div_ceil(y);
+    let lp_out = (sol_in * supply / x).min(tokens_in * supply / y);
+    let meta = env
+        .send(&[liquidity.add_liquidity(10 * SOL, tokens_in as u64, 1, FAR_DEADLINE)], &[whale])
+        .unwrap();
+    let added = event::<LiquidityAdded>(&meta);
+    assert_eq!(
+        (added.sol_amount, added.token_amount, added.lp_amount),
+        (sol_in as u64, tokens_in as u64, lp_out as u64)
+    );
+
+    // 撤出自己的全部LP后，锁定的初始流动性仍留在池中
+    let owned = env.svm.token_amount(&pda::token_account(&whale, &lp_mint));
+    env.send(&[liquidity.remove_liquidity(owned, 0, 0, FAR_DEADLINE)], &[whale])
+        .unwrap();
+    let pool = env.dex_pool(&mint);
+    assert_eq!(pool.lp_supply, env.svm.token_amount(&pda::lp_vault(&mint)));
+    assert!(pool.sol_reserves > 0 && pool.token_reserves > 0);
+}
+
+#[test]
+fn add_and_remove_liquidity_round_trip() {
+    let mut env = Env::new();
+    let creator = env.user(10);
+    let mint = env.create(creator);
+    let whale = env.graduate(mint);
+    let liquidity = ix::DexLiquidity {
+        user: whale,
+        token_mint: mint,
+    };
+    let tokens = env.tokens(&whale, &mint);
+
+    let meta = env
+        .send(&[liquidity.add_liquidity(SOL, tokens, 1, FAR_DEADLINE)], &[whale])
+        .unwrap();
+    let added = event::<LiquidityAdded>(&meta);
+    let lp_account = pda::token_account(&whale, &pda::lp_mint(&mint).0);
+    assert_eq!(env.svm.token_amount(&lp_account), added.lp_amount);
+    assert_eq!(env.dex_pool(&mint).lp_supply, added.lp_supply);
+
+    let meta = env
+        .send(&[liquidity.remove_liquidity(added.lp_amount, 0, 0, FAR_DEADLINE)], &[whale])
+        .unwrap();
+    let removed = event::<LiquidityRemoved>(&meta);
+    assert_eq!(removed.lp_supply, added.lp_supply - added.lp_amount);
+    assert_eq!(env.svm.token_amount(&lp_account), 0);
+    // LP 向下取整，取回的数量不多于注入的数量，误差在一个 LP 单位以内
+    let pool = env.dex_pool(&mint);
+    assert!(removed.sol_amount <= added.sol_amount);
+    assert!(added.sol_amount - removed.sol_amount <= pool.sol_reserves / pool.lp_supply + 1);
+    assert!(removed.token_amount <= added.token_amount);
+    assert!(added.token_amount - removed.token_amount <= pool.token_reserves / pool.lp_supply + 1);
+}
@@ -9,6 +9,7 @@ import {
   getAssociatedTokenAddressSync, // 同步获取关联代币地址函数
   getMint, // 获取铸币信息函数
   getAccount, // 获取代币账户信息函数
+  getTokenMetadata, // 获取Token-2022链上元数据函数
 } from '@solana/spl-token'
 import { BN } from 'bn.js' // 导入BN，用于处理大整数
 
@@ -34,6 +35,7 @@ describe('pumpfun', () => {
   let tokenMint: Keypair // 代币铸币密钥对
   let bondingCurvePda: PublicKey // 绑定曲线PDA
   let tokenVaultAta: PublicKey // 代币金库ATA
+  let migrationVaultPda: PublicKey // 迁移储备金库PDA
   let buyerTokenAta: PublicKey // 买家代币ATA
 
   // --- 帮助函数 ---  // 部分：帮助函数
@@ -121,6 +123,11 @@ describe('pumpfun', () => {
       [Buffer.from('bonding_curve'), tokenMint.publicKey.toBuffer()],
       program.programId,
     )
+    ;[migrationVaultPda] = PublicKey.findProgramAddressSync(
+      // 迁移储备金库PDA
+      [Buffer.from('migration_vault'), tokenMint.publicKey.toBuffer()],
+      program.programId,
+    )
     tokenVaultAta = getAssociatedTokenAddressSync(
       // 金库ATA
       tokenMint.publicKey,
@@ -157,27 +164,92 @@ describe('pumpfun', () => {
       const config = await program.account.protocolConfig.fetch(protocolConfigPda) // 获取配置
       expect(config.governanceAuthority.toString()).toEqual(governance.publicKey.toString()) // 断言治理权限
       expect(config.treasury.toString()).toEqual(treasury.publicKey.toString()) // 断言国库
+      expect(config.curvePresetCount).toEqual(1) // 断言默认曲线预设
     })
 
-    it('should update the protocol config', async () => {
-      // 测试：更新协议配置
-      const newTreasury = Keypair.generate() // 生成新国库
-      const originalConfig = await program.account.protocolConfig.fetch(protocolConfigPda) // 获取原配置
-      const newConfigData = { ...originalConfig, treasury: newTreasury.publicKey, isPaused: true } // 新配置数据
+    it('should let governance add a curve preset', async () => {
+      // 测试：治理新增曲线预设
+      const preset = {
+        virtualSolReserves: new BN(2 * LAMPORTS_PER_SOL),
+        virtualTokenReserves: new BN('200000000000'),
+        totalSupply: new BN('500000000000000'),
+        decimals: 9,
+        curveSupplyBps: 8000,
+        curveKind: { linear: { basePrice: new BN(10_000_000), slope: new BN(1) } }, // 线性曲线
+      }
+      await program.methods
+        .setCurvePreset(1, preset)
+        .accounts({ governanceAuthority: governance.publicKey, protocolConfig: protocolConfigPda })
+        .signers([governance])
+        .rpc({ commitment: 'confirmed' })
+
+      const config = await program.account.protocolConfig.fetch(protocolConfigPda) // 获取配置
+      expect(config.curvePresetCount).toEqual(2) // 断言预设已追加
+      expect(config.curvePresets[1].decimals).toEqual(9) // 断言预设参数
+      expect(config.curvePresets[1].curveKind.linear.slope.toNumber()).toEqual(1) // 断言曲线类型
 
-      await program.methods // 调用更新方法
-        .updateConfig(newConfigData)
+      const gapPromise = program.methods // 不允许跳过索引
+        .setCurvePreset(3, preset)
         .accounts({ governanceAuthority: governance.publicKey, protocolConfig: protocolConfigPda })
         .signers([governance])
         .rpc({ commitment: 'confirmed' })
+      await expect(gapPromise).rejects.toThrow('The curve preset is invalid or does not exist.')
+    })
+
+    it('should queue timelocked config changes and pause instantly', async () => {
+      // 测试：配置变更经过时间锁排队，暂停立即生效
+      const governanceAccounts = { governanceAuthority: governance.publicKey, protocolConfig: protocolConfigPda }
+      const config = await program.account.protocolConfig.fetch(protocolConfigPda) // 获取当前配置
+      const id = config.nextConfigChangeId as BN // 下一项变更编号
+      const [pendingChangePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from('pending_config_change'), id.toArrayLike(Buffer, 'le', 8)],
+        program.programId,
+      )
+      const newTreasury = Keypair.generate() // 生成新国库
+
+      await program.methods // 排队更换国库
+        .queueConfigChange({ treasury: { treasury: newTreasury.publicKey } })
+        .accounts({ ...governanceAccounts, pendingChange: pendingChangePda })
+        .signers([governance])
+        .rpc({ commitment: 'confirmed' })
+      const pending = await program.account.pendingConfigChange.fetch(pendingChangePda) // 获取待执行变更
+      expect(pending.executableAt.sub(pending.queuedAt).toNumber()).toEqual(config.configTimelockSecs.toNumber()) // 断言延迟
+
+      const earlyPromise = program.methods // 时间锁未到期，不能执行
+        .executeConfigChange(id)
+        .accounts({ ...governanceAccounts, pendingChange: pendingChangePda, newTreasury: newTreasury.publicKey })
+        .signers([governance])
+        .rpc({ commitment: 'confirmed' })
+      await expect(earlyPromise).rejects.toThrow('The config change timelock has not elapsed yet.')
+
+      await program.methods // 撤回变更
+        .cancelConfigChange(id)
+        .accounts({ ...governanceAccounts, pendingChange: pendingChangePda })
+        .signers([governance])
+        .rpc({ commitment: 'confirmed' })
+      expect(await connection.getAccountInfo(pendingChangePda)).toBeNull() // 断言待执行账户已关闭
 
+      const capPromise = program.methods // 超过硬上限的费用在排队时被拒绝
+        .queueConfigChange({
+          fees: { totalTradeFeeBps: new BN(10_001), creatorFeeBpsShare: new BN(10), dexLpFeeBps: new BN(25) },
+        })
+        .accounts({ ...governanceAccounts, pendingChange: pendingChangePda })
+        .signers([governance])
+        .rpc({ commitment: 'confirmed' })
+      await expect(capPromise).rejects.toThrow('The fee exceeds its hard cap.')
+
+      await program.methods // 暂停全部指令族
+        .setPaused(0x1f)
+        .accounts(governanceAccounts)
+        .signers([governance])
+        .rpc({ commitment: 'confirmed' })
       const updatedConfig = await program.account.protocolConfig.fetch(protocolConfigPda) // 获取更新配置
-      expect(updatedConfig.treasury.toString()).toEqual(newTreasury.publicKey.toString()) // 断言新国库
-      expect(updatedConfig.isPaused).toBe(true) // 断言暂停状态
+      expect(updatedConfig.paused).toBe(0x1f) // 断言暂停位
+      expect(updatedConfig.treasury.toString()).toEqual(treasury.publicKey.toString()) // 断言国库未变
 
-      await program.methods // 恢复原配置
-        .updateConfig({ ...newConfigData, isPaused: false, treasury: treasury.publicKey })
-        .accounts({ governanceAuthority: governance.publicKey, protocolConfig: protocolConfigPda })
+      await program.methods // 恢复交易
+        .setPaused(0)
+        .accounts(governanceAccounts)
         .signers([governance])
         .rpc({ commitment: 'confirmed' })
     })
@@ -188,7 +260,7 @@ describe('pumpfun', () => {
     it('should create a new token and bonding curve', async () => {
       // 测试：创建代币和曲线
       await program.methods // 调用创建方法
-        .create('Test Token', 'TEST')
+        .create('Test Token', 'TEST', 'https://example.com/test-token.json', 0)
         .accounts({
           creator: creator.publicKey,
           protocolConfig: protocolConfigPda,
@@ -196,6 +268,7 @@ describe('pumpfun', () => {
           tokenMint: tokenMint.publicKey,
           bondingCurve: bondingCurvePda,
           tokenVault: tokenVaultAta,
+          migrationVault: migrationVaultPda,
           systemProgram: SystemProgram.programId,
           tokenProgram: TOKEN_2022_PROGRAM_ID,
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -205,11 +278,92 @@ describe('pumpfun', () => {
 
       const mintInfo = await getMint(connection, tokenMint.publicKey, 'confirmed', TOKEN_2022_PROGRAM_ID) // 获取铸币信息
       expect(mintInfo.mintAuthority.toString()).toEqual(bondingCurvePda.toString()) // 断言铸币权限
+
+      const metadata = await getTokenMetadata(connection, tokenMint.publicKey, 'confirmed', TOKEN_2022_PROGRAM_ID) // 读取链上元数据
+      expect(metadata.name).toEqual('Test Token') // 断言名称
+      expect(metadata.symbol).toEqual('TEST') // 断言符号
+      expect(metadata.uri).toEqual('https://example.com/test-token.json') // 断言URI
+      expect(metadata.updateAuthority.toString()).toEqual(bondingCurvePda.toString()) // 断言更新权限为曲线PDA
+
+      const curveState = await program.account.bondingCurve.fetch(bondingCurvePda) // 曲线记录所选预设参数
+      expect(curveState.initialVirtualSolReserves.toString()).toEqual(LAMPORTS_PER_SOL.toString())
+      expect(curveState.totalSupply.toString()).toEqual(mintInfo.supply.toString())
+      expect(curveState.decimals).toEqual(mintInfo.decimals)
+      expect(curveState.curveKind).toEqual({ constantProduct: {} }) // 断言默认预设为恒定乘积曲线
+
+      const curveSupply = await getTokenBalance(tokenVaultAta) // 曲线可售部分
+      const migrationReserve = await getTokenBalance(migrationVaultPda) // 迁移储备部分
+      expect(curveSupply).toEqual(BigInt(curveState.realTokenReserves.toString())) // 断言曲线只能出售可售部分
+      expect(curveSupply + migrationReserve).toEqual(mintInfo.supply) // 断言两部分合计为总供应量
+      expect(migrationReserve * BigInt(10)).toEqual(mintInfo.supply) // 断言默认预设保留10%作为迁移储备
+    })
+
+    it('should reject metadata that is too long', async () => {
+      // 测试：元数据过长时拒绝创建
+      const badMint = Keypair.generate() // 新铸币
+      const [badCurvePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from('bonding_curve'), badMint.publicKey.toBuffer()],
+        program.programId,
+      )
+      const createPromise = program.methods
+        .create('x'.repeat(33), 'TEST', '', 0)
+        .accounts({
+          creator: creator.publicKey,
+          protocolConfig: protocolConfigPda,
+          treasury: treasury.publicKey,
+          tokenMint: badMint.publicKey,
+          bondingCurve: badCurvePda,
+          tokenVault: getAssociatedTokenAddressSync(badMint.publicKey, badCurvePda, true, TOKEN_2022_PROGRAM_ID),
+          migrationVault: PublicKey.findProgramAddressSync(
+            [Buffer.from('migration_vault'), badMint.publicKey.toBuffer()],
+            program.programId,
+          )[0],
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        })
+        .signers([creator, badMint])
+        .rpc({ commitment: 'confirmed' })
+
+      await expect(createPromise).rejects.toThrow('Metadata string length exceeds the maximum allowed.')
+    })
+
+    it('should let the creator update the URI and custom metadata fields', async () => {
+      // 测试：创作者更新URI和自定义字段
+      const updateAccounts = {
+        creator: creator.publicKey,
+        bondingCurve: bondingCurvePda,
+        tokenMint: tokenMint.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      }
+      await program.methods
+        .updateTokenMetadata('uri', 'https://example.com/test-token-v2.json')
+        .accounts(updateAccounts)
+        .signers([creator])
+        .rpc({ commitment: 'confirmed' })
+      await program.methods
+        .updateTokenMetadata('website', 'https://example.com')
+        .accounts(updateAccounts)
+        .signers([creator])
+        .rpc({ commitment: 'confirmed' })
+
+      const metadata = await getTokenMetadata(connection, tokenMint.publicKey, 'confirmed', TOKEN_2022_PROGRAM_ID) // 读取链上元数据
+      expect(metadata.uri).toEqual('https://example.com/test-token-v2.json') // 断言URI已更新
+      expect(metadata.additionalMetadata).toContainEqual(['website', 'https://example.com']) // 断言自定义字段
+
+      const renamePromise = program.methods // 名称不可修改
+        .updateTokenMetadata('name', 'Rugged')
+        .accounts(updateAccounts)
+        .signers([creator])
+        .rpc({ commitment: 'confirmed' })
+      await expect(renamePromise).rejects.toThrow('This metadata field cannot be updated.')
     })
 
     it('should allow a user to buy tokens', async () => {
       // 测试：用户购买代币
       const solIn = new BN(1 * LAMPORTS_PER_SOL) // 输入1 SOL
+      const treasurySolBefore = await getSolBalance(treasury.publicKey) // 购买前国库余额
       await program.methods // 调用购买方法
         .buy(solIn, new BN(0), new BN(Math.floor(Date.now() / 1000) + 60))
         .accounts({
@@ -225,18 +379,20 @@ describe('pumpfun', () => {
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         })
         .signers([buyer])
-        .preInstructions([
-          // 预指令：转移SOL到曲线
-          SystemProgram.transfer({
-            fromPubkey: buyer.publicKey,
-            toPubkey: bondingCurvePda,
-            lamports: solIn.toNumber(),
-          }),
-        ])
         .rpc({ commitment: 'confirmed' })
 
       const buyerTokenBalance = await getTokenBalance(buyerTokenAta) // 获取买家余额
       expect(buyerTokenBalance).toBeGreaterThan(BigInt(0)) // 断言余额>0
+
+      const treasurySolAfter = await getSolBalance(treasury.publicKey) // 购买后国库余额
+      expect(treasurySolAfter).toBeGreaterThan(treasurySolBefore) // 断言国库收到费用
+
+      const curveState = await program.account.bondingCurve.fetch(bondingCurvePda) // 购买后曲线状态
+      const curveLamports = await getSolBalance(bondingCurvePda) // 曲线lamports
+      expect(
+        // 断言曲线lamports覆盖真实储备 + 欠创作者费用
+        BigInt(curveLamports) >= BigInt(curveState.realSolReserves.add(curveState.creatorFeesOwed).toString()),
+      ).toBe(true)
     })
 
     it('should allow a user to sell tokens', async () => {
@@ -266,6 +422,58 @@ describe('pumpfun', () => {
       expect(sellerTokenBalanceAfter).toEqual(tokenBalanceBefore - tokensToSell) // 断言减少
     })
 
+    it('should buy an exact amount of tokens', async () => {
+      // 测试：精确买入代币数量
+      const tokensOut = BigInt(1_000_000_000) // 买入1000个代币
+      const tokenBalanceBefore = await getTokenBalance(buyerTokenAta) // 买入前余额
+      await program.methods
+        .buyExactTokensOut(new BN(tokensOut.toString()), new BN(LAMPORTS_PER_SOL), new BN(Math.floor(Date.now() / 1000) + 60))
+        .accounts({
+          buyer: buyer.publicKey,
+          protocolConfig: protocolConfigPda,
+          treasury: treasury.publicKey,
+          bondingCurve: bondingCurvePda,
+          tokenMint: tokenMint.publicKey,
+          tokenVault: tokenVaultAta,
+          buyerTokenAccount: buyerTokenAta,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        })
+        .signers([buyer])
+        .rpc({ commitment: 'confirmed' })
+
+      const tokenBalanceAfter = await getTokenBalance(buyerTokenAta) // 买入后余额
+      expect(tokenBalanceAfter - tokenBalanceBefore).toEqual(tokensOut) // 断言恰好收到指定数量
+    })
+
+    it('should sell tokens for an exact amount of SOL', async () => {
+      // 测试：卖出代币换取精确数量的SOL
+      const solOut = 1_000_000 // 0.001 SOL
+      const tokenBalanceBefore = await getTokenBalance(buyerTokenAta) // 卖出前余额
+      const solBefore = await getSolBalance(buyer.publicKey) // 卖出前SOL
+      await program.methods
+        .sellForExactSol(new BN(solOut), new BN(tokenBalanceBefore.toString()), new BN(Math.floor(Date.now() / 1000) + 60))
+        .accounts({
+          seller: buyer.publicKey,
+          protocolConfig: protocolConfigPda,
+          treasury: treasury.publicKey,
+          bondingCurve: bondingCurvePda,
+          tokenMint: tokenMint.publicKey,
+          tokenVault: tokenVaultAta,
+          sellerTokenAccount: buyerTokenAta,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        })
+        .signers([buyer])
+        .rpc({ commitment: 'confirmed' })
+
+      const solAfter = await getSolBalance(buyer.publicKey) // 卖出后SOL
+      expect(solAfter - solBefore).toEqual(solOut - 5000) // 断言恰好收到指定SOL（扣除签名费）
+      expect(await getTokenBalance(buyerTokenAta)).toBeLessThan(tokenBalanceBefore) // 断言代币减少
+    })
+
     it('should allow the creator to claim fees', async () => {
       // 测试：创作者领取费用
       const creatorSolBefore = await getSolBalance(creator.publicKey) // 领取前SOL余额
@@ -280,6 +488,8 @@ describe('pumpfun', () => {
         .claimCreatorFees()
         .accounts({
           creator: creator.publicKey,
+          creatorFeeRecipient: creator.publicKey, // 默认费用接收者是创作者本人
+          protocolConfig: protocolConfigPda,
           bondingCurve: bondingCurvePda,
           systemProgram: SystemProgram.programId,
         })
@@ -341,19 +551,12 @@ describe('pumpfun', () => {
             associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
           })
           .signers([buyer])
-          .preInstructions([
-            // 预指令：转移SOL
-            SystemProgram.transfer({
-              fromPubkey: buyer.publicKey,
-              toPubkey: bondingCurvePda,
-              lamports: solToBuy.toNumber(),
-            }),
-          ])
           .rpc({ commitment: 'confirmed' })
       }
 
       curveData = await program.account.bondingCurve.fetch(bondingCurvePda) // 重新获取曲线
-      expect(curveData.realSolReserves.gte(config.migrationThresholdSol)).toBe(true) // 断言达到阈值
+      expect(curveData.realSolReserves.eq(config.migrationThresholdSol)).toBe(true) // 断言恰好填满阈值（超出部分被退回）
+      expect(curveData.isCompleted).toBe(true) // 断言购买时自动完成曲线
     })
 
     it('should complete and migrate to a DEX pool', async () => {
@@ -380,19 +583,20 @@ describe('pumpfun', () => {
       // Step 2: Prepare for migration logic call  // 步骤2：准备迁移
       console.log('Migrating liquidity...') // 日志
       const curveStateBefore = await program.account.bondingCurve.fetch(bondingCurvePda) // 迁移前曲线状态
-      const vaultBalanceBefore = await getTokenBalance(tokenVaultAta) // 金库余额
       const bondingCurveLamportsBefore = await getSolBalance(bondingCurvePda) // 曲线lamports
       const dexSolVaultBalanceBefore = await getSolBalance(dexSolVaultPda) // DEX SOL金库余额
+      const treasuryBalanceBefore = await getSolBalance(treasury.publicKey) // 国库余额
 
       // Step 3: Call migration logic  // 步骤3：调用迁移
       await program.methods
         .completeAndMigrate()
         .accounts({
+          payer: buyer.publicKey, // 任何人都可以触发迁移
           protocolConfig: protocolConfigPda,
           bondingCurve: bondingCurvePda,
-          creator: creator.publicKey,
           tokenMint: tokenMint.publicKey,
           tokenVault: tokenVaultAta,
+          migrationVault: migrationVaultPda,
           dexPool: dexPoolPda,
           dexSolVault: dexSolVaultPda,
           dexTokenVault: dexTokenVaultAta,
@@ -402,7 +606,7 @@ describe('pumpfun', () => {
           tokenProgram: TOKEN_2022_PROGRAM_ID,
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         })
-        .signers([creator])
+        .signers([buyer])
         .rpc({ commitment: 'confirmed' })
 
       // Step 4: Assertions (与新的链上逻辑匹配)  // 步骤4：断言（匹配链上逻辑）
@@ -414,19 +618,136 @@ describe('pumpfun', () => {
       // 2. 验证 token_vault 帐户已被成功关闭
       const tokenVaultInfoAfter = await connection.getAccountInfo(tokenVaultAta) // 获取金库信息
       expect(tokenVaultInfoAfter).toBeNull() // 断言已关闭（null）
+      expect(await connection.getAccountInfo(migrationVaultPda)).toBeNull() // 断言迁移储备金库已关闭
 
-      // 3. 验证 SOL 守恒：两个账户的总余额在迁移前后应该相等
+      // 3. 验证 SOL 守恒：曲线储备全部进入DEX池，国库不变
       const bondingCurveLamportsAfter = await getSolBalance(bondingCurvePda) // 后lamports
       const dexSolVaultBalanceAfter = await getSolBalance(dexSolVaultPda) // 后DEX余额
       expect(bondingCurveLamportsAfter + dexSolVaultBalanceAfter).toEqual(
         // 断言SOL守恒
         bondingCurveLamportsBefore + dexSolVaultBalanceBefore,
       )
+      expect(await getSolBalance(treasury.publicKey)).toEqual(treasuryBalanceBefore) // 断言国库没有收到迁移的SOL
 
-      // 4. 验证 DexPool 状态中的业务逻辑数据
+      // 4. 验证 DexPool 以曲线最终价格开盘（价格连续）
       const dexPoolState = await program.account.dexPool.fetch(dexPoolPda) // 获取DEX池状态
-      expect(dexPoolState.solReserves.toString()).toEqual(curveStateBefore.realSolReserves.toString()) // 断言SOL储备
-      expect(dexPoolState.tokenReserves.toString()).toEqual(vaultBalanceBefore.toString()) // 断言代币储备
+      expect(dexPoolState.solReserves.eq(curveStateBefore.realSolReserves)).toBe(true) // 断言曲线储备全部注入池子
+      const poolPriceSide = dexPoolState.solReserves.mul(curveStateBefore.virtualTokenReserves) // 池子价格（交叉相乘）
+      const curvePriceSide = dexPoolState.tokenReserves.mul(curveStateBefore.virtualSolReserves) // 曲线最终价格（交叉相乘）
+      const roundingBound = curveStateBefore.virtualSolReserves.add(curveStateBefore.virtualTokenReserves) // 取整误差上界
+      expect(curvePriceSide.sub(poolPriceSide).abs().lt(roundingBound)).toBe(true) // 断言价格只差一个取整单位
+
+      // 5. 验证元数据更新权限在毕业时已被撤销
+      const metadata = await getTokenMetadata(connection, tokenMint.publicKey, 'confirmed', TOKEN_2022_PROGRAM_ID)
+      expect(metadata.updateAuthority).toBeUndefined() // 断言更新权限为空
+    })
+
+    it('should swap SOL for tokens on the migrated DEX pool', async () => {
+      // 测试：在迁移后的DEX池中用SOL兑换代币
+      const poolBefore = await program.account.dexPool.fetch(dexPoolPda) // 兑换前池子状态
+      const tokenBalanceBefore = await getTokenBalance(buyerTokenAta) // 兑换前代币余额
+      const solIn = new BN(0.5 * LAMPORTS_PER_SOL) // 输入0.5 SOL
+
+      await program.methods
+        .dexSwapSolForTokens(solIn, new BN(1), new BN(Math.floor(Date.now() / 1000) + 60))
+        .accounts({
+          user: buyer.publicKey,
+          protocolConfig: protocolConfigPda,
+          tokenMint: tokenMint.publicKey,
+          dexPool: dexPoolPda,
+          dexSolVault: dexSolVaultPda,
+          dexTokenVault: dexTokenVaultAta,
+          userTokenAccount: buyerTokenAta,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        })
+        .signers([buyer])
+        .rpc({ commitment: 'confirmed' })
+
+      const poolAfter = await program.account.dexPool.fetch(dexPoolPda) // 兑换后池子状态
+      const tokenBalanceAfter = await getTokenBalance(buyerTokenAta) // 兑换后代币余额
+      expect(poolAfter.solReserves.sub(poolBefore.solReserves).eq(solIn)).toBe(true) // 断言SOL储备增加
+      expect(tokenBalanceAfter).toBeGreaterThan(tokenBalanceBefore) // 断言收到代币
+      expect(
+        // 断言 k 不减少（LP费用留在池中）
+        poolAfter.solReserves.mul(poolAfter.tokenReserves).gte(poolBefore.solReserves.mul(poolBefore.tokenReserves)),
+      ).toBe(true)
+    })
+
+    it('should swap tokens for SOL on the migrated DEX pool', async () => {
+      // 测试：在迁移后的DEX池中用代币兑换SOL
+      const tokenBalanceBefore = await getTokenBalance(buyerTokenAta) // 兑换前代币余额
+      const tokensIn = tokenBalanceBefore / BigInt(10) // 卖出10%
+
+      await program.methods
+        .dexSwapTokensForSol(new BN(tokensIn.toString()), new BN(0), new BN(Math.floor(Date.now() / 1000) + 60))
+        .accounts({
+          user: buyer.publicKey,
+          protocolConfig: protocolConfigPda,
+          tokenMint: tokenMint.publicKey,
+          dexPool: dexPoolPda,
+          dexSolVault: dexSolVaultPda,
+          dexTokenVault: dexTokenVaultAta,
+          userTokenAccount: buyerTokenAta,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        })
+        .signers([buyer])
+        .rpc({ commitment: 'confirmed' })
+
+      const tokenBalanceAfter = await getTokenBalance(buyerTokenAta) // 兑换后代币余额
+      expect(tokenBalanceAfter).toEqual(tokenBalanceBefore - tokensIn) // 断言代币减少
+    })
+
+    it('should add and remove liquidity with proportional LP tokens', async () => {
+      // 测试：注入并移除流动性，LP按份额铸造和销毁
+      const userLpAta = getAssociatedTokenAddressSync(lpMintPda, buyer.publicKey, false, TOKEN_2022_PROGRAM_ID) // 用户LP账户
+      const liquidityAccounts = {
+        user: buyer.publicKey,
+        protocolConfig: protocolConfigPda,
+        tokenMint: tokenMint.publicKey,
+        dexPool: dexPoolPda,
+        dexSolVault: dexSolVaultPda,
+        dexTokenVault: dexTokenVaultAta,
+        lpMint: lpMintPda,
+        lpVault: lpVaultAta,
+        userTokenAccount: buyerTokenAta,
+        userLpAccount: userLpAta,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      }
+      const poolBefore = await program.account.dexPool.fetch(dexPoolPda) // 注入前池子状态
+      expect(poolBefore.lpSupply.gtn(0)).toBe(true) // 迁移时已铸造并锁定初始LP
+
+      const tokenBalance = await getTokenBalance(buyerTokenAta) // 用户代币余额
+      await program.methods
+        .addLiquidity(
+          new BN(LAMPORTS_PER_SOL),
+          new BN(tokenBalance.toString()),
+          new BN(1),
+          new BN(Math.floor(Date.now() / 1000) + 60),
+        )
+        .accounts(liquidityAccounts)
+        .signers([buyer])
+        .rpc({ commitment: 'confirmed' })
+
+      const lpBalance = await getTokenBalance(userLpAta) // 用户LP余额
+      const poolAfterAdd = await program.account.dexPool.fetch(dexPoolPda) // 注入后池子状态
+      expect(lpBalance).toBeGreaterThan(BigInt(0)) // 断言收到LP
+      expect(poolAfterAdd.lpSupply.sub(poolBefore.lpSupply).toString()).toEqual(lpBalance.toString()) // 断言LP供应量同步
+
+      await program.methods
+        .removeLiquidity(new BN(lpBalance.toString()), new BN(0), new BN(0), new BN(Math.floor(Date.now() / 1000) + 60))
+        .accounts(liquidityAccounts)
+        .signers([buyer])
+        .rpc({ commitment: 'confirmed' })
+
+      const poolAfterRemove = await program.account.dexPool.fetch(dexPoolPda) // 移除后池子状态
+      expect(await getTokenBalance(userLpAta)).toEqual(BigInt(0)) // 断言LP已销毁
+      expect(poolAfterRemove.lpSupply.toString()).toEqual(poolBefore.lpSupply.toString()) // 断言LP供应量恢复
     })
 
     it('should fail to trade on a completed curve', async () => {
@@ -447,14 +768,6 @@ describe('pumpfun', () => {
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         })
         .signers([buyer])
-        .preInstructions([
-          // 预指令：转移SOL
-          SystemProgram.transfer({
-            fromPubkey: buyer.publicKey,
-            toPubkey: bondingCurvePda,
-            lamports: solIn.toNumber(),
-          }),
-        ])
         .rpc({ commitment: 'confirmed' })
 
       // 验证交易因我们预期的业务逻辑错误而失败  // 注释已存在：预期抛出错误
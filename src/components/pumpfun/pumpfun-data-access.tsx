@@ -76,7 +76,7 @@ export function usePumpfunProgram() {
 
   const createMutation = useMutation({
     mutationKey: ['pumpfun', 'create', { cluster, publicKey }], // 创建代币 mutation 键
-    mutationFn: async ({ name, symbol }: { name: string; symbol: string }) => {
+    mutationFn: async ({ name, symbol, uri }: { name: string; symbol: string; uri: string }) => {
       const currentConfig = queryClient.getQueryData<any>(protocolConfigQueryKey) // 获取当前协议配置
 
       if (!publicKey || !currentConfig) {
@@ -94,9 +94,13 @@ export function usePumpfunProgram() {
         true,
         TOKEN_2022_PROGRAM_ID,
       ) // 获取代币金库关联账户地址
+      const [migrationVaultPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from('migration_vault'), tokenMint.publicKey.toBuffer()], // 计算迁移储备金库 PDA
+        programId,
+      )
 
       return program.methods
-        .create(name, symbol) // 调用创建代币方法
+        .create(name, symbol, uri, 0) // 使用默认曲线预设（索引0）； 调用创建代币方法（元数据写入 Token-2022 铸币）
         .accounts({
           creator: publicKey, // 设置创建者账户
           protocolConfig: protocolConfigPda, // 协议配置 PDA
@@ -104,6 +108,7 @@ export function usePumpfunProgram() {
           tokenMint: tokenMint.publicKey, // 代币铸造地址
           bondingCurve: bondingCurvePda, // 绑定曲线 PDA
           tokenVault: tokenVaultAta, // 代币金库账户
+          migrationVault: migrationVaultPda, // 迁移储备金库 PDA
           systemProgram: SystemProgram.programId, // 系统程序
           tokenProgram: TOKEN_2022_PROGRAM_ID, // Token 2022 程序
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID, // 关联代币程序
@@ -158,12 +163,7 @@ export function useBondingCurve({ bondingCurve: bondingCurvePda }: { bondingCurv
         false,
         TOKEN_2022_PROGRAM_ID,
       ) // 获取买家代币账户地址
-      const solInBn = new BN(solIn * LAMPORTS_PER_SOL) // 转换为 lamports
-      const transferInstruction = SystemProgram.transfer({
-        fromPubkey: user,
-        toPubkey: bondingCurvePda,
-        lamports: solInBn.toNumber(),
-      }) // 创建 SOL 转账指令
+      const solInBn = new BN(solIn * LAMPORTS_PER_SOL) // 转换为 lamports（程序内部从买家扣款）
 
       return program.methods
         .buy(solInBn, new BN(0), new BN(Math.floor(Date.now() / 1000) + 60)) // 调用购买方法
@@ -179,7 +179,6 @@ export function useBondingCurve({ bondingCurve: bondingCurvePda }: { bondingCurv
           tokenProgram: TOKEN_2022_PROGRAM_ID, // Token 2022 程序
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID, // 关联代币程序
         })
-        .preInstructions([transferInstruction]) // 添加转账指令
         .rpc() // 执行购买的 RPC 调用
     },
     onSuccess: (tx) => {
@@ -226,14 +225,24 @@ export function useBondingCurve({ bondingCurve: bondingCurvePda }: { bondingCurv
     onError: (error) => toast.error(`Sell failed: ${error.message}`), // 显示出售失败通知
   })
 
-  // The claimFeesMutation does not directly interact with the token program, so it doesn't need the fix.
+  // 费用支付给曲线记录的 creatorFeeRecipient（默认是创作者本人，可被改为多签或分账账户），签名者仍是创作者
   const claimFeesMutation = useMutation({
     mutationKey: ['pumpfun', 'claimFees', { cluster, bondingCurvePda, user }], // 提取费用 mutation 键
-    mutationFn: () => {
+    mutationFn: async () => {
       if (!user || !accountQuery.data || !user.equals(accountQuery.data.creator)) {
         throw new Error('Only the creator can claim fees.') // 确保只有创建者可以提取费用
       }
-      return program.methods.claimCreatorFees().accounts({ creator: user, bondingCurve: bondingCurvePda }).rpc() // 执行提取费用的 RPC 调用
+      const curve = await program.account.bondingCurve.fetch(bondingCurvePda) // 重新读取曲线，拿到当前的费用接收者
+      return program.methods
+        .claimCreatorFees()
+        .accounts({
+          creator: user, // 创作者（签名者）
+          creatorFeeRecipient: curve.creatorFeeRecipient, // 费用接收者（链上约束为曲线记录的地址）
+          protocolConfig: protocolConfigPda, // 协议配置 PDA
+          bondingCurve: bondingCurvePda, // 绑定曲线 PDA
+          systemProgram: SystemProgram.programId, // 系统程序
+        })
+        .rpc() // 执行提取费用的 RPC 调用
     },
     onSuccess: (tx) => {
       transactionToast(tx) // 显示交易成功通知
@@ -279,8 +288,11 @@ export function useBondingCurve({ bondingCurve: bondingCurvePda }: { bondingCurv
   const migrateMutation = useMutation({
     mutationKey: ['pumpfun', 'migrate', { cluster, bondingCurvePda, user }], // 迁移 mutation 键
     mutationFn: () => {
-      if (!user || !accountQuery.data || !user.equals(accountQuery.data.creator)) {
-        throw new Error('Only the creator can migrate.') // 确保只有创建者可以迁移
+      if (!user || !protocolConfig.data || !accountQuery.data) {
+        throw new Error('Wallet or account data not ready') // 检查钱包和账户数据
+      }
+      if (!accountQuery.data.isCompleted) {
+        throw new Error('The bonding curve has not reached the migration threshold.') // 曲线未完成时不能迁移
       }
       const tokenMint = accountQuery.data.tokenMint // 获取代币铸造地址
       const [dexPoolPda] = PublicKey.findProgramAddressSync([Buffer.from('dex_pool'), tokenMint.toBuffer()], programId) // 计算 DEX 池 PDA
@@ -291,15 +303,20 @@ export function useBondingCurve({ bondingCurve: bondingCurvePda }: { bondingCurv
       const [lpMintPda] = PublicKey.findProgramAddressSync([Buffer.from('lp_mint'), tokenMint.toBuffer()], programId) // 计算 LP 铸造 PDA
       const dexTokenVaultAta = getAssociatedTokenAddressSync(tokenMint, dexPoolPda, true, TOKEN_2022_PROGRAM_ID) // 获取 DEX 代币金库账户
       const lpVaultAta = getAssociatedTokenAddressSync(lpMintPda, dexPoolPda, true, TOKEN_2022_PROGRAM_ID) // 获取 LP 金库账户
+      const [migrationVaultPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from('migration_vault'), tokenMint.toBuffer()],
+        programId,
+      ) // 计算迁移储备金库 PDA
 
       return program.methods
         .completeAndMigrate()
         .accounts({
-          creator: user, // 创建者账户
+          payer: user, // 调用者账户（任何人都可以迁移）
           protocolConfig: protocolConfigPda, // 协议配置 PDA
           bondingCurve: bondingCurvePda, // 绑定曲线 PDA
           tokenMint: tokenMint, // 代币铸造地址
           tokenVault: accountQuery.data.tokenVault, // 代币金库账户
+          migrationVault: migrationVaultPda, // 迁移储备金库 PDA
           dexPool: dexPoolPda, // DEX 池 PDA
           dexSolVault: dexSolVaultPda, // DEX SOL 金库
           dexTokenVault: dexTokenVaultAta, // DEX 代币金库账户
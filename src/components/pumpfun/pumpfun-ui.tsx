@@ -22,6 +22,7 @@ export function PumpfunCreate({ isLoading }: PumpfunCreateProps) {
   const { createMutation } = usePumpfunProgram() // 获取 Pumpfun 程序的创建代币 mutation
   const [name, setName] = useState('') // 管理代币名称的状态
   const [symbol, setSymbol] = useState('') // 管理代币符号的状态
+  const [uri, setUri] = useState('') // 管理元数据 URI 的状态
 
   const handleSubmit = () => {
     // 处理表单提交的函数
@@ -29,7 +30,7 @@ export function PumpfunCreate({ isLoading }: PumpfunCreateProps) {
       // 检查名称和符号是否为空
       return toast.error('Please enter a name and a symbol') // 显示错误提示
     }
-    createMutation.mutateAsync({ name, symbol }) // 异步调用创建代币的 mutation
+    createMutation.mutateAsync({ name, symbol, uri }) // 异步调用创建代币的 mutation
   }
 
   return (
@@ -41,6 +42,8 @@ export function PumpfunCreate({ isLoading }: PumpfunCreateProps) {
           {/* 代币名称输入框 */}
           <Input placeholder="Symbol (e.g. CAT)" value={symbol} onChange={(e) => setSymbol(e.target.value)} />{' '}
           {/* 代币符号输入框 */}
+          <Input placeholder="Metadata URI (optional)" value={uri} onChange={(e) => setUri(e.target.value)} />{' '}
+          {/* 元数据 URI 输入框 */}
         </div>
         <Button onClick={handleSubmit} disabled={createMutation.isPending || isLoading}>
           {' '}
@@ -154,7 +157,8 @@ function PumpfunCard({ bondingCurve }: { bondingCurve: PublicKey }) {
   const migrationThreshold = config ? config.migrationThresholdSol.toNumber() : 1 // 获取迁移阈值
   const currentSolReserves = data ? data.realSolReserves.toNumber() : 0 // 获取当前 SOL 储备
   const migrationProgress = Math.min((currentSolReserves / migrationThreshold) * 100, 100) // 计算迁移进度
-  const canMigrate = migrationProgress >= 100 // 判断是否可以迁移到 DEX
+  const isMigrated = data ? !data.dexPool.equals(PublicKey.default) : false // 迁移时程序会记录 DEX 池地址
+  const canMigrate = !!data && (data.isCompleted || migrationProgress >= 100) && !isMigrated // 曲线完成且未迁移时任何人都可以迁移
 
   // --- Effects for managing state and side effects ---
   useEffect(() => {
@@ -309,7 +313,7 @@ function PumpfunCard({ bondingCurve }: { bondingCurve: PublicKey }) {
             </Button>
           </div>
         </div>
-        {data.isCompleted && (
+        {isMigrated && (
           <p className="text-center font-bold text-green-500 p-2 bg-green-500/10 rounded-md">Trading Migrated to DEX</p> // 显示已迁移到 DEX 的提示
         )}
       </CardContent>
@@ -327,7 +331,7 @@ function PumpfunCard({ bondingCurve }: { bondingCurve: PublicKey }) {
         )}
 
         {/* Creator Actions Section */}
-        {isCreator && !data.isCompleted && (
+        {isCreator && (
           <div className="w-full border-t pt-3 flex flex-col gap-2">
             <h3 className="text-sm font-semibold text-center">Creator Actions</h3>
             <Button
@@ -338,11 +342,17 @@ function PumpfunCard({ bondingCurve }: { bondingCurve: PublicKey }) {
             >
               Claim {feesToClaim.toFixed(4)} SOL Fees
             </Button>
+          </div>
+        )}
+
+        {/* Migration Action: permissionless once the curve is complete */}
+        {canMigrate && (
+          <div className="w-full border-t pt-3 flex flex-col gap-2">
             <Button
               variant="default"
               size="sm"
               onClick={handleMigrate}
-              disabled={!canMigrate || migrateMutation.isPending || initializeDexPoolMutation.isPending} // 迁移到 DEX 按钮
+              disabled={migrateMutation.isPending || initializeDexPoolMutation.isPending} // 迁移到 DEX 按钮
             >
               {migrateMutation.isPending || initializeDexPoolMutation.isPending ? 'Migrating...' : 'Migrate to DEX'}
             </Button>